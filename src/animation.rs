@@ -0,0 +1,9 @@
+pub mod track;
+pub mod tween;
+pub mod timeline;
+pub mod spring;
+
+pub use track::*;
+pub use tween::*;
+pub use timeline::*;
+pub use spring::*;
@@ -0,0 +1,299 @@
+use std::ops::{Add, Mul};
+use crate::math::*;
+use std::f32::consts::{PI, TAU};
+
+
+/// Types that can be simulated by a `Spring`.
+/// Springs operate on the displacement of a value from its target, which lives in `Velocity` space.
+pub trait SpringValue: Copy {
+	type Velocity: Copy + Default + Add<Output=Self::Velocity> + Mul<f32, Output=Self::Velocity>;
+
+	/// Returns the displacement of `self` from `target`.
+	fn displacement(self, target: Self) -> Self::Velocity;
+
+	/// Returns `target` displaced by `displacement`. The inverse of `displacement`.
+	fn displace(target: Self, displacement: Self::Velocity) -> Self;
+}
+
+macro_rules! impl_spring_value_for_vec {
+	($ty:ty) => (
+		impl SpringValue for $ty {
+			type Velocity = $ty;
+
+			fn displacement(self, target: $ty) -> $ty { self - target }
+			fn displace(target: $ty, displacement: $ty) -> $ty { target + displacement }
+		}
+	);
+}
+
+impl_spring_value_for_vec!(f32);
+impl_spring_value_for_vec!(Vec2);
+impl_spring_value_for_vec!(Vec3);
+impl_spring_value_for_vec!(Vec4);
+
+impl SpringValue for Color {
+	type Velocity = Vec4;
+
+	fn displacement(self, target: Color) -> Vec4 { self.to_vec4() - target.to_vec4() }
+	fn displace(target: Color, displacement: Vec4) -> Color { (target.to_vec4() + displacement).into() }
+}
+
+/// Quaternions are displaced by world space rotation vectors, so `Spring::velocity` is an angular velocity.
+impl SpringValue for Quat {
+	type Velocity = Vec3;
+
+	fn displacement(self, target: Quat) -> Vec3 {
+		(self * target.conjugate()).to_scaled_axis()
+	}
+
+	fn displace(target: Quat, displacement: Vec3) -> Quat {
+		(Quat::from_scaled_axis(displacement) * target).normalize()
+	}
+}
+
+
+/// A damped harmonic oscillator pulling `value` towards `target`.
+/// Steps are solved analytically, so the simulation is stable and consistent for any `dt`.
+#[derive(Copy, Clone, Debug)]
+pub struct Spring<T: SpringValue> {
+	pub value: T,
+	pub velocity: T::Velocity,
+	pub target: T,
+
+	/// Undamped oscillation frequency, in Hz.
+	pub frequency: f32,
+
+	/// 1 is critically damped and settles as fast as possible without overshooting,
+	/// less than 1 overshoots and oscillates, greater than 1 approaches the target more slowly.
+	pub damping_ratio: f32,
+}
+
+impl<T: SpringValue> Spring<T> {
+	/// Creates a spring at rest at `value`.
+	pub fn new(value: T, frequency: f32, damping_ratio: f32) -> Spring<T> {
+		Spring {
+			value,
+			velocity: T::Velocity::default(),
+			target: value,
+			frequency,
+			damping_ratio,
+		}
+	}
+
+	pub fn critically_damped(value: T, frequency: f32) -> Spring<T> {
+		Spring::new(value, frequency, 1.0)
+	}
+
+	pub fn with_target(self, target: T) -> Spring<T> {
+		Spring { target, ..self }
+	}
+
+	/// Moves the spring to `value` and stops it, without affecting `target`.
+	pub fn reset(&mut self, value: T) {
+		self.value = value;
+		self.velocity = T::Velocity::default();
+	}
+
+	/// Advances the simulation by `dt` seconds and returns the new value.
+	pub fn update(&mut self, dt: f32) -> T {
+		let coefficients = SpringCoefficients::new(TAU * self.frequency, self.damping_ratio, dt);
+
+		let displacement = self.value.displacement(self.target);
+		let velocity = self.velocity;
+
+		let new_displacement = displacement * coefficients.pos_pos + velocity * coefficients.pos_vel;
+		self.velocity = displacement * coefficients.vel_pos + velocity * coefficients.vel_vel;
+		self.value = T::displace(self.target, new_displacement);
+		self.value
+	}
+}
+
+impl Spring<f32> {
+	/// Like `update`, but treats `value` and `target` as angles in radians and takes the shortest path between them.
+	/// `target` may be replaced by an equivalent angle closer to `value`.
+	pub fn update_angle(&mut self, dt: f32) -> f32 {
+		let delta = (self.target - self.value + PI).rem_euclid(TAU) - PI;
+		self.target = self.value + delta;
+		self.update(dt)
+	}
+}
+
+
+// Describes the new displacement and velocity of a spring after some time as a linear combination of its current state.
+// http://www.ryanjuckett.com/programming/damped-springs/
+struct SpringCoefficients {
+	pos_pos: f32,
+	pos_vel: f32,
+	vel_pos: f32,
+	vel_vel: f32,
+}
+
+impl SpringCoefficients {
+	fn new(angular_frequency: f32, damping_ratio: f32, dt: f32) -> SpringCoefficients {
+		const EPSILON: f32 = 0.0001;
+
+		let omega = angular_frequency.max(0.0);
+		let zeta = damping_ratio.max(0.0);
+
+		if omega < EPSILON {
+			return SpringCoefficients {
+				pos_pos: 1.0,
+				pos_vel: 0.0,
+				vel_pos: 0.0,
+				vel_vel: 1.0,
+			};
+		}
+
+		if zeta > 1.0 + EPSILON {
+			// Over-damped
+			let za = -omega * zeta;
+			let zb = omega * (zeta*zeta - 1.0).sqrt();
+			let z1 = za - zb;
+			let z2 = za + zb;
+
+			let e1 = (z1 * dt).exp();
+			let e2 = (z2 * dt).exp();
+
+			let inv_two_zb = 1.0 / (2.0 * zb);
+			let e1_over_two_zb = e1 * inv_two_zb;
+			let e2_over_two_zb = e2 * inv_two_zb;
+			let z1e1_over_two_zb = z1 * e1_over_two_zb;
+			let z2e2_over_two_zb = z2 * e2_over_two_zb;
+
+			SpringCoefficients {
+				pos_pos: e1_over_two_zb * z2 - z2e2_over_two_zb + e2,
+				pos_vel: -e1_over_two_zb + e2_over_two_zb,
+				vel_pos: (z1e1_over_two_zb - z2e2_over_two_zb + e2) * z2,
+				vel_vel: -z1e1_over_two_zb + z2e2_over_two_zb,
+			}
+
+		} else if zeta < 1.0 - EPSILON {
+			// Under-damped
+			let omega_zeta = omega * zeta;
+			let alpha = omega * (1.0 - zeta*zeta).sqrt();
+
+			let exp_term = (-omega_zeta * dt).exp();
+			let (sin_term, cos_term) = (alpha * dt).sin_cos();
+
+			let exp_sin = exp_term * sin_term;
+			let exp_cos = exp_term * cos_term;
+			let exp_omega_zeta_sin_over_alpha = exp_term * omega_zeta * sin_term / alpha;
+
+			SpringCoefficients {
+				pos_pos: exp_cos + exp_omega_zeta_sin_over_alpha,
+				pos_vel: exp_sin / alpha,
+				vel_pos: -exp_sin * alpha - omega_zeta * exp_omega_zeta_sin_over_alpha,
+				vel_vel: exp_cos - exp_omega_zeta_sin_over_alpha,
+			}
+
+		} else {
+			// Critically damped
+			let exp_term = (-omega * dt).exp();
+			let time_exp = dt * exp_term;
+			let time_exp_freq = time_exp * omega;
+
+			SpringCoefficients {
+				pos_pos: time_exp_freq + exp_term,
+				pos_vel: time_exp,
+				vel_pos: -omega * time_exp_freq,
+				vel_vel: -time_exp_freq + exp_term,
+			}
+		}
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn test_critically_damped() {
+		let mut spring = Spring::critically_damped(0.0, 2.0).with_target(1.0);
+
+		let mut max_value = 0.0f32;
+		for _ in 0..120 {
+			max_value = max_value.max(spring.update(1.0/60.0));
+		}
+
+		assert!(max_value <= 1.0, "critically damped spring overshot");
+		assert_almost_eq!(spring.value, 1.0);
+		assert_almost_eq!(spring.velocity, 0.0);
+	}
+
+	#[test]
+	fn test_underdamped_overshoots() {
+		let mut spring = Spring::new(Vec2::zero(), 1.0, 0.2).with_target(Vec2::one());
+
+		let mut max_x = 0.0f32;
+		for _ in 0..600 {
+			max_x = max_x.max(spring.update(1.0/60.0).x);
+		}
+
+		assert!(max_x > 1.2);
+		assert_vec_eq!(spring.value, Vec2::one());
+	}
+
+	#[test]
+	fn test_step_size_independent() {
+		for damping_ratio in [0.3, 1.0, 2.5] {
+			let mut coarse = Spring::new(Vec3::zero(), 1.5, damping_ratio).with_target(Vec3::new(1.0, -2.0, 3.0));
+			let mut fine = coarse;
+
+			coarse.update(0.5);
+			for _ in 0..50 {
+				fine.update(0.01);
+			}
+
+			assert_vec_eq!(coarse.value, fine.value);
+			assert_vec_eq!(coarse.velocity, fine.velocity);
+		}
+
+		// Huge timesteps settle rather than explode
+		let mut spring = Spring::new(5.0, 10.0, 0.5);
+		spring.target = 0.0;
+		assert_almost_eq!(spring.update(100.0), 0.0);
+	}
+
+	#[test]
+	fn test_angle() {
+		let mut spring = Spring::critically_damped(PI * 0.9, 2.0).with_target(-PI * 0.9);
+		spring.update_angle(0.1);
+		assert!(spring.value > PI * 0.9);
+
+		for _ in 0..100 {
+			spring.update_angle(0.1);
+		}
+
+		assert_almost_eq!(spring.value, PI * 1.1);
+	}
+
+	#[test]
+	fn test_color() {
+		let mut spring = Spring::critically_damped(Color::black(), 3.0).with_target(Color::red().with_alpha(0.5));
+		for _ in 0..60 {
+			spring.update(1.0/30.0);
+		}
+
+		assert_vec_eq!(spring.value.to_vec4(), Vec4::new(1.0, 0.0, 0.0, 0.5));
+	}
+
+	#[test]
+	fn test_quat() {
+		let target = Quat::from_yaw(PI * 0.75);
+		let mut spring = Spring::critically_damped(Quat::identity(), 2.0).with_target(target);
+
+		spring.update(0.1);
+		let yaw_after_step = spring.value.yaw();
+		assert!(yaw_after_step > 0.0 && yaw_after_step < PI * 0.75);
+		assert_vec_eq!(spring.velocity.normalize(), Vec3::from_y(1.0));
+
+		for _ in 0..100 {
+			spring.update(0.1);
+		}
+
+		assert_almost_eq!(spring.value.dot(target).abs(), 1.0);
+		assert_vec_eq!(spring.value.forward(), target.forward());
+	}
+}
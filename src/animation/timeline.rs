@@ -0,0 +1,180 @@
+use crate::math::lerp::Lerp;
+use crate::animation::Track;
+
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimelineEntry<K, T> {
+	/// Time at which the track starts playing, relative to the start of the timeline.
+	pub start: f32,
+
+	/// Identifies the property animated by `track`.
+	pub key: K,
+	pub track: Track<T>,
+}
+
+impl<K, T> TimelineEntry<K, T> {
+	pub fn end(&self) -> f32 {
+		self.start + self.track.end_time()
+	}
+}
+
+
+/// Schedules tracks against a shared clock, in sequence or in parallel.
+/// Each track animates the property identified by its key. Once a track has started, it overrides any
+/// earlier tracks with the same key, and it holds its final value until the next one starts.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timeline<K, T> {
+	pub entries: Vec<TimelineEntry<K, T>>,
+}
+
+impl<K, T> Timeline<K, T> {
+	pub fn new() -> Timeline<K, T> {
+		Timeline { entries: Vec::new() }
+	}
+
+	/// The time at which every track has finished.
+	pub fn duration(&self) -> f32 {
+		self.entries.iter()
+			.map(TimelineEntry::end)
+			.fold(0.0, f32::max)
+	}
+
+	pub fn is_finished(&self, time: f32) -> bool {
+		time >= self.duration()
+	}
+
+	pub fn insert(&mut self, start: f32, key: K, track: impl Into<Track<T>>) {
+		self.entries.push(TimelineEntry {
+			start,
+			key,
+			track: track.into(),
+		});
+	}
+
+	/// Adds a track that starts once every previously added track has finished.
+	pub fn then(mut self, key: K, track: impl Into<Track<T>>) -> Self {
+		let start = self.duration();
+		self.insert(start, key, track);
+		self
+	}
+
+	/// Adds a track that starts at the same time as the previously added track.
+	pub fn with(mut self, key: K, track: impl Into<Track<T>>) -> Self {
+		let start = self.entries.last().map_or(0.0, |e| e.start);
+		self.insert(start, key, track);
+		self
+	}
+
+	/// Adds a track that starts `delay` seconds after the previously added track.
+	pub fn after(mut self, delay: f32, key: K, track: impl Into<Track<T>>) -> Self {
+		let start = self.entries.last().map_or(0.0, |e| e.start) + delay;
+		self.insert(start, key, track);
+		self
+	}
+}
+
+impl<K, T> Timeline<K, T>
+	where K: PartialEq, T: Copy, f32: Lerp<T>
+{
+	/// Samples the property identified by `key`, or returns `None` if no track animates it.
+	/// Before the first track for `key` starts, its first value is held.
+	pub fn sample_key(&self, key: &K, time: f32) -> Option<T> {
+		self.active_entry(key, time)
+			.and_then(|entry| entry.track.sample(time - entry.start))
+	}
+
+	/// Samples every property animated by the timeline, once each.
+	pub fn sample(&self, time: f32) -> Vec<(&K, T)> {
+		let mut values: Vec<(&K, T)> = Vec::new();
+
+		for entry in self.entries.iter() {
+			if values.iter().any(|(key, _)| *key == &entry.key) {
+				continue
+			}
+
+			if let Some(value) = self.sample_key(&entry.key, time) {
+				values.push((&entry.key, value));
+			}
+		}
+
+		values
+	}
+
+	fn active_entry(&self, key: &K, time: f32) -> Option<&TimelineEntry<K, T>> {
+		let mut matching = self.entries.iter().filter(|e| e.key == *key);
+		let first = matching.next()?;
+
+		// Entries are not necessarily sorted, so find both the earliest entry and the latest started entry.
+		let mut earliest = first;
+		let mut latest_started = (first.start <= time).then_some(first);
+
+		for entry in matching {
+			if entry.start < earliest.start {
+				earliest = entry;
+			}
+
+			if entry.start <= time && latest_started.is_none_or(|latest| entry.start >= latest.start) {
+				latest_started = Some(entry);
+			}
+		}
+
+		Some(latest_started.unwrap_or(earliest))
+	}
+}
+
+impl<K, T> Default for Timeline<K, T> {
+	fn default() -> Timeline<K, T> {
+		Timeline::new()
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[derive(Debug, PartialEq)]
+	enum Property {
+		Position,
+		Scale,
+	}
+
+	#[test]
+	fn test_sequence_and_parallel() {
+		let timeline = Timeline::new()
+			.then(Property::Position, Tween::new(Vec2::zero(), Vec2::one(), 1.0))
+			.with(Property::Scale, Tween::new(Vec2::one(), Vec2::splat(2.0), 0.5))
+			.then(Property::Position, Tween::new(Vec2::one(), Vec2::splat(3.0), 2.0))
+			.after(1.0, Property::Scale, Tween::new(Vec2::splat(2.0), Vec2::zero(), 1.0));
+
+		assert_almost_eq!(timeline.duration(), 3.0);
+
+		assert_vec_eq!(timeline.sample_key(&Property::Position, -1.0).unwrap(), Vec2::zero());
+		assert_vec_eq!(timeline.sample_key(&Property::Position, 0.5).unwrap(), Vec2::splat(0.5));
+		assert_vec_eq!(timeline.sample_key(&Property::Position, 2.0).unwrap(), Vec2::splat(2.0));
+		assert_vec_eq!(timeline.sample_key(&Property::Position, 5.0).unwrap(), Vec2::splat(3.0));
+
+		assert_vec_eq!(timeline.sample_key(&Property::Scale, 0.25).unwrap(), Vec2::splat(1.5));
+		// Holds between tracks
+		assert_vec_eq!(timeline.sample_key(&Property::Scale, 1.5).unwrap(), Vec2::splat(2.0));
+		assert_vec_eq!(timeline.sample_key(&Property::Scale, 2.5).unwrap(), Vec2::splat(1.0));
+
+		let values = timeline.sample(2.5);
+		assert_eq!(values.len(), 2);
+		assert_eq!(*values[0].0, Property::Position);
+		assert_vec_eq!(values[0].1, Vec2::splat(2.5));
+		assert_eq!(*values[1].0, Property::Scale);
+		assert_vec_eq!(values[1].1, Vec2::splat(1.0));
+	}
+
+	#[test]
+	fn test_missing_key() {
+		let timeline = Timeline::new()
+			.then(Property::Position, Tween::new(0.0, 1.0, 1.0));
+
+		assert!(timeline.sample_key(&Property::Scale, 0.5).is_none());
+	}
+}
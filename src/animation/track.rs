@@ -0,0 +1,345 @@
+use crate::math::lerp::{Lerp, Easing};
+
+
+/// How a `Track` interpolates from a keyframe to the next.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Interpolation {
+	/// Hold the value of the keyframe until the next one.
+	Step,
+
+	#[default]
+	Linear,
+
+	/// Cubic Hermite interpolation with Catmull-Rom tangents derived from neighbouring keyframes.
+	/// Tangents are not wrapped around the ends of looping tracks.
+	Cubic,
+}
+
+/// How a `Track` is sampled outside of the range of its keyframes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LoopMode {
+	/// Hold the first or last keyframe.
+	#[default]
+	Clamp,
+
+	/// Wrap back around to the first keyframe.
+	Loop,
+
+	/// Alternate between playing forwards and backwards.
+	PingPong,
+}
+
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Keyframe<T> {
+	pub time: f32,
+	pub value: T,
+
+	/// Interpolation of the segment between this keyframe and the next.
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub interpolation: Interpolation,
+
+	/// Easing of the segment between this keyframe and the next.
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub easing: Easing,
+}
+
+impl<T> Keyframe<T> {
+	pub fn new(time: f32, value: T) -> Keyframe<T> {
+		Keyframe {
+			time,
+			value,
+			interpolation: Interpolation::Linear,
+			easing: Easing::Linear,
+		}
+	}
+
+	pub fn with_interpolation(self, interpolation: Interpolation) -> Keyframe<T> {
+		Keyframe { interpolation, ..self }
+	}
+
+	pub fn with_easing(self, easing: Easing) -> Keyframe<T> {
+		Keyframe { easing, ..self }
+	}
+}
+
+
+/// A sequence of keyframes, kept sorted by time.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "TrackDesc<T>"))]
+pub struct Track<T> {
+	keyframes: Vec<Keyframe<T>>,
+	pub loop_mode: LoopMode,
+}
+
+// Authored tracks may not be sorted, so deserialization goes through `Track::from_keyframes`.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct TrackDesc<T> {
+	keyframes: Vec<Keyframe<T>>,
+	#[serde(default)]
+	loop_mode: LoopMode,
+}
+
+#[cfg(feature = "serde")]
+impl<T> From<TrackDesc<T>> for Track<T> {
+	fn from(TrackDesc{keyframes, loop_mode}: TrackDesc<T>) -> Track<T> {
+		Track::from_keyframes(keyframes).with_loop_mode(loop_mode)
+	}
+}
+
+impl<T> Track<T> {
+	pub fn new() -> Track<T> {
+		Track {
+			keyframes: Vec::new(),
+			loop_mode: LoopMode::Clamp,
+		}
+	}
+
+	pub fn from_keyframes(mut keyframes: Vec<Keyframe<T>>) -> Track<T> {
+		keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+		Track {
+			keyframes,
+			loop_mode: LoopMode::Clamp,
+		}
+	}
+
+	pub fn with_loop_mode(self, loop_mode: LoopMode) -> Track<T> {
+		Track { loop_mode, ..self }
+	}
+
+	/// Inserts `keyframe` after any existing keyframes with the same time.
+	pub fn insert(&mut self, keyframe: Keyframe<T>) {
+		let index = self.keyframes.partition_point(|k| k.time <= keyframe.time);
+		self.keyframes.insert(index, keyframe);
+	}
+
+	pub fn remove(&mut self, index: usize) -> Keyframe<T> {
+		self.keyframes.remove(index)
+	}
+
+	pub fn keyframes(&self) -> &[Keyframe<T>] {
+		&self.keyframes
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.keyframes.is_empty()
+	}
+
+	pub fn start_time(&self) -> f32 {
+		self.keyframes.first().map_or(0.0, |k| k.time)
+	}
+
+	pub fn end_time(&self) -> f32 {
+		self.keyframes.last().map_or(0.0, |k| k.time)
+	}
+
+	pub fn duration(&self) -> f32 {
+		self.end_time() - self.start_time()
+	}
+
+	/// Maps `time` into the range of the track according to `loop_mode`.
+	pub fn wrap_time(&self, time: f32) -> f32 {
+		let start = self.start_time();
+		let duration = self.duration();
+
+		if duration <= 0.0 {
+			return start;
+		}
+
+		match self.loop_mode {
+			LoopMode::Clamp => time.clamp(start, start + duration),
+			LoopMode::Loop => start + (time - start).rem_euclid(duration),
+			LoopMode::PingPong => {
+				let local = (time - start).rem_euclid(duration * 2.0);
+				start + duration - (local - duration).abs()
+			}
+		}
+	}
+}
+
+impl<T> Track<T>
+	where T: Copy, f32: Lerp<T>
+{
+	/// Returns `None` if the track has no keyframes.
+	pub fn sample(&self, time: f32) -> Option<T> {
+		let time = self.wrap_time(time);
+
+		// Index of the first keyframe after `time`
+		let next_index = self.keyframes.partition_point(|k| k.time <= time);
+		if next_index == 0 {
+			return self.keyframes.first().map(|k| k.value);
+		}
+
+		let current = &self.keyframes[next_index - 1];
+		let Some(next) = self.keyframes.get(next_index) else {
+			return Some(current.value);
+		};
+
+		let segment_duration = next.time - current.time;
+		let t = current.easing.apply((time - current.time) / segment_duration);
+
+		let value = match current.interpolation {
+			Interpolation::Step => current.value,
+			Interpolation::Linear => t.lerp(current.value, next.value),
+			Interpolation::Cubic => {
+				// Keyframes sharing a time with `current` or `next` would make a zero length span, so they're skipped
+				let previous = next_index.checked_sub(2).map(|i| &self.keyframes[i]).filter(|k| k.time < current.time);
+				let after_next = self.keyframes.get(next_index + 1).filter(|k| k.time > next.time);
+
+				// Extrapolate the segment at the ends of the track so tangents stay well defined
+				let (prev_time, prev_value) = previous
+					.map_or((current.time - segment_duration, (-1.0).lerp(current.value, next.value)), |k| (k.time, k.value));
+				let (after_time, after_value) = after_next
+					.map_or((next.time + segment_duration, 2.0.lerp(current.value, next.value)), |k| (k.time, k.value));
+
+				catmull_rom(
+					[prev_value, current.value, next.value, after_value],
+					[prev_time, current.time, next.time, after_time],
+					current.time + segment_duration * t,
+				)
+			}
+		};
+
+		Some(value)
+	}
+}
+
+impl<T> Default for Track<T> {
+	fn default() -> Track<T> {
+		Track::new()
+	}
+}
+
+impl<T> FromIterator<Keyframe<T>> for Track<T> {
+	fn from_iter<I>(iter: I) -> Track<T> where I: IntoIterator<Item=Keyframe<T>> {
+		Track::from_keyframes(iter.into_iter().collect())
+	}
+}
+
+
+/// Evaluates the Catmull-Rom segment between `points[1]` and `points[2]` at `time`, given the knot time of each point.
+/// Uses the Barry-Goldman pyramidal formulation, which only requires `Lerp`.
+pub(crate) fn catmull_rom<T>(points: [T; 4], knots: [f32; 4], time: f32) -> T
+	where T: Copy, f32: Lerp<T>
+{
+	let [p0, p1, p2, p3] = points;
+	let [t0, t1, t2, t3] = knots;
+
+	let factor = |from: f32, to: f32| (time - from) / (to - from);
+
+	let a1 = factor(t0, t1).lerp(p0, p1);
+	let a2 = factor(t1, t2).lerp(p1, p2);
+	let a3 = factor(t2, t3).lerp(p2, p3);
+
+	let b1 = factor(t0, t2).lerp(a1, a2);
+	let b2 = factor(t1, t3).lerp(a2, a3);
+
+	factor(t1, t2).lerp(b1, b2)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn linear_track() -> Track<f32> {
+		Track::from_keyframes(vec![
+			Keyframe::new(2.0, 10.0),
+			Keyframe::new(0.0, 0.0),
+			Keyframe::new(1.0, 5.0),
+		])
+	}
+
+	#[test]
+	fn test_sample_linear() {
+		let track = linear_track();
+		assert_almost_eq!(track.start_time(), 0.0);
+		assert_almost_eq!(track.end_time(), 2.0);
+
+		assert_almost_eq!(track.sample(-1.0).unwrap(), 0.0);
+		assert_almost_eq!(track.sample(0.0).unwrap(), 0.0);
+		assert_almost_eq!(track.sample(0.5).unwrap(), 2.5);
+		assert_almost_eq!(track.sample(1.0).unwrap(), 5.0);
+		assert_almost_eq!(track.sample(1.5).unwrap(), 7.5);
+		assert_almost_eq!(track.sample(3.0).unwrap(), 10.0);
+
+		assert!(Track::<f32>::new().sample(0.0).is_none());
+	}
+
+	#[test]
+	fn test_sample_step_and_easing() {
+		let track: Track<Vec2> = [
+			Keyframe::new(0.0, Vec2::zero()).with_interpolation(Interpolation::Step),
+			Keyframe::new(1.0, Vec2::one()).with_easing(Easing::QuadIn),
+			Keyframe::new(2.0, Vec2::splat(2.0)),
+		].into_iter().collect();
+
+		assert_vec_eq!(track.sample(0.99).unwrap(), Vec2::zero());
+		assert_vec_eq!(track.sample(1.0).unwrap(), Vec2::one());
+		assert_vec_eq!(track.sample(1.5).unwrap(), Vec2::splat(1.25));
+	}
+
+	#[test]
+	fn test_sample_cubic() {
+		let track: Track<f32> = [0.0, 1.0, 2.0, 3.0].into_iter()
+			.map(|x| Keyframe::new(x, x * 2.0).with_interpolation(Interpolation::Cubic))
+			.collect();
+
+		// Catmull-Rom reproduces linear data exactly
+		for i in 0..=30 {
+			let t = i as f32 / 10.0;
+			assert_almost_eq!(track.sample(t).unwrap(), t * 2.0);
+		}
+
+		let track: Track<f32> = [(0.0, 0.0), (1.0, 1.0), (2.0, 0.0)].into_iter()
+			.map(|(t, x)| Keyframe::new(t, x).with_interpolation(Interpolation::Cubic))
+			.collect();
+
+		// Passes through keyframes, and overshoots linear interpolation on the way
+		assert_almost_eq!(track.sample(1.0).unwrap(), 1.0);
+		assert!(track.sample(0.75).unwrap() > 0.75);
+	}
+
+	#[test]
+	fn test_sample_cubic_duplicate_times() {
+		let track: Track<f32> = [(0.0, 0.0), (1.0, 2.0), (1.0, 4.0), (2.0, 6.0), (2.0, 8.0)].into_iter()
+			.map(|(t, x)| Keyframe::new(t, x).with_interpolation(Interpolation::Cubic))
+			.collect();
+
+		for i in 0..=20 {
+			assert!(track.sample(i as f32 / 10.0).unwrap().is_finite());
+		}
+
+		assert_almost_eq!(track.sample(1.5).unwrap(), 5.0);
+	}
+
+	#[test]
+	fn test_loop_modes() {
+		let track = linear_track().with_loop_mode(LoopMode::Loop);
+		assert_almost_eq!(track.sample(2.5).unwrap(), 2.5);
+		assert_almost_eq!(track.sample(-0.5).unwrap(), 7.5);
+
+		let track = linear_track().with_loop_mode(LoopMode::PingPong);
+		assert_almost_eq!(track.sample(2.5).unwrap(), 7.5);
+		assert_almost_eq!(track.sample(4.5).unwrap(), 2.5);
+		assert_almost_eq!(track.sample(-0.5).unwrap(), 2.5);
+	}
+
+	#[test]
+	fn test_insert() {
+		let mut track = linear_track();
+		track.insert(Keyframe::new(0.5, 100.0));
+		track.insert(Keyframe::new(5.0, 0.0));
+
+		let times: Vec<f32> = track.keyframes().iter().map(|k| k.time).collect();
+		assert_eq!(times, [0.0, 0.5, 1.0, 2.0, 5.0]);
+		assert_almost_eq!(track.sample(0.5).unwrap(), 100.0);
+	}
+}
@@ -0,0 +1,81 @@
+use crate::math::lerp::{Lerp, Easing};
+use crate::animation::{Track, Keyframe};
+
+
+/// A single eased transition from one value to another.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tween<T> {
+	pub from: T,
+	pub to: T,
+	pub duration: f32,
+
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub easing: Easing,
+}
+
+impl<T> Tween<T> {
+	pub fn new(from: T, to: T, duration: f32) -> Tween<T> {
+		Tween {
+			from,
+			to,
+			duration,
+			easing: Easing::Linear,
+		}
+	}
+
+	pub fn with_easing(self, easing: Easing) -> Tween<T> {
+		Tween { easing, ..self }
+	}
+
+	pub fn is_finished(&self, time: f32) -> bool {
+		time >= self.duration
+	}
+}
+
+impl<T> Tween<T>
+	where T: Copy, f32: Lerp<T>
+{
+	pub fn sample(&self, time: f32) -> T {
+		let t = if self.duration > 0.0 {
+			time / self.duration
+		} else {
+			1.0
+		};
+
+		self.easing.apply(t.clamp(0.0, 1.0))
+			.lerp(self.from, self.to)
+	}
+}
+
+impl<T> From<Tween<T>> for Track<T> {
+	fn from(Tween{from, to, duration, easing}: Tween<T>) -> Track<T> {
+		Track::from_keyframes(vec![
+			Keyframe::new(0.0, from).with_easing(easing),
+			Keyframe::new(duration, to),
+		])
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn test_sample() {
+		let tween = Tween::new(Vec3::zero(), Vec3::new(4.0, 2.0, 0.0), 2.0);
+		assert_vec_eq!(tween.sample(-1.0), Vec3::zero());
+		assert_vec_eq!(tween.sample(1.0), Vec3::new(2.0, 1.0, 0.0));
+		assert_vec_eq!(tween.sample(3.0), Vec3::new(4.0, 2.0, 0.0));
+		assert!(tween.is_finished(2.0));
+
+		let eased = tween.with_easing(Easing::QuadOut);
+		let track = Track::from(eased);
+		for i in 0..=10 {
+			let time = i as f32 / 5.0;
+			assert_vec_eq!(track.sample(time).unwrap(), eased.sample(time));
+		}
+	}
+}
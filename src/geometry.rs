@@ -0,0 +1,36 @@
+pub mod path;
+pub mod stroke;
+pub mod triangulate;
+pub mod polygon;
+pub mod boolean;
+pub mod offset;
+pub mod hull;
+pub mod delaunay;
+pub mod voronoi;
+pub mod shapes;
+pub mod intersect;
+pub mod gjk;
+pub mod sweep;
+pub mod bvh;
+pub mod dynamic_tree;
+pub mod loose_tree;
+pub mod spatial_hash;
+pub(crate) mod slot_map;
+
+pub use path::*;
+pub use stroke::*;
+pub use triangulate::*;
+pub use polygon::*;
+pub use boolean::*;
+pub use offset::*;
+pub use hull::*;
+pub use delaunay::*;
+pub use voronoi::*;
+pub use shapes::*;
+pub use intersect::*;
+pub use gjk::*;
+pub use sweep::*;
+pub use bvh::*;
+pub use dynamic_tree::*;
+pub use loose_tree::*;
+pub use spatial_hash::*;
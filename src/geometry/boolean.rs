@@ -0,0 +1,901 @@
+use crate::math::*;
+use crate::geometry::polygon::{Polygon2, Winding};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, BTreeSet, HashMap};
+use std::ops::Bound;
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BooleanOp {
+	Union,
+	Intersection,
+	Difference,
+	Xor,
+}
+
+/// Decides which regions of a `PolygonSet2` are filled, given the number of times its contours wind around them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FillRule {
+	/// Filled where contours wind around an odd number of times, regardless of direction.
+	EvenOdd,
+
+	/// Filled where the winding number is not zero.
+	#[default]
+	NonZero,
+
+	/// Filled where the winding number is greater than zero.
+	Positive,
+}
+
+impl FillRule {
+	pub fn is_filled(&self, winding_number: i32) -> bool {
+		match self {
+			FillRule::EvenOdd => winding_number % 2 != 0,
+			FillRule::NonZero => winding_number != 0,
+			FillRule::Positive => winding_number > 0,
+		}
+	}
+}
+
+
+/// A region bounded by any number of contours, allowing for holes and disjoint parts.
+///
+/// Results of boolean operations have counter-clockwise outer contours and clockwise holes,
+/// with no overlapping or self intersecting contours.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PolygonSet2 {
+	pub contours: Vec<Polygon2>,
+}
+
+/// Constructors
+impl PolygonSet2 {
+	pub fn new(contours: Vec<Polygon2>) -> PolygonSet2 {
+		PolygonSet2 { contours }
+	}
+
+	pub fn empty() -> PolygonSet2 {
+		PolygonSet2::default()
+	}
+
+	/// Creates a set from an outer contour and holes, wound in any direction.
+	pub fn with_holes(outer: Polygon2, holes: impl IntoIterator<Item=Polygon2>) -> PolygonSet2 {
+		let mut contours = vec![outer.with_winding(Winding::CounterClockwise)];
+		contours.extend(holes.into_iter().map(|hole| hole.with_winding(Winding::Clockwise)));
+		PolygonSet2 { contours }
+	}
+}
+
+/// Properties
+impl PolygonSet2 {
+	pub fn is_empty(&self) -> bool {
+		self.contours.iter().all(Polygon2::is_empty)
+	}
+
+	/// The filled area, assuming contours are wound consistently and don't overlap.
+	pub fn area(&self) -> f32 {
+		self.contours.iter().map(Polygon2::signed_area).sum()
+	}
+
+	pub fn bounds(&self) -> Aabb2 {
+		self.contours.iter()
+			.fold(Aabb2::empty(), |bounds, contour| bounds.include_rect(contour.bounds()))
+	}
+
+	pub fn winding_number(&self, point: Vec2) -> i32 {
+		self.contours.iter().map(|contour| contour.winding_number(point)).sum()
+	}
+
+	/// Whether `point` is inside the set, using the nonzero winding rule.
+	pub fn contains_point(&self, point: Vec2) -> bool {
+		self.winding_number(point) != 0
+	}
+
+	/// Groups contours into outer contours and the holes directly inside them.
+	pub fn regions(&self) -> Vec<(Polygon2, Vec<Polygon2>)> {
+		let mut regions: Vec<(Polygon2, Vec<Polygon2>)> = self.contours.iter()
+			.filter(|contour| contour.winding() == Winding::CounterClockwise)
+			.map(|contour| (contour.clone(), Vec::new()))
+			.collect();
+
+		for hole in self.contours.iter().filter(|contour| contour.winding() == Winding::Clockwise) {
+			let Some(&point) = hole.points.first() else { continue };
+
+			// Holes belong to the smallest outer contour containing them
+			let owner = regions.iter_mut()
+				.filter(|(outer, _)| outer.contains_point(point) || outer.points.iter().any(|p| p.x == point.x && p.y == point.y))
+				.min_by(|(a, _), (b, _)| a.area().total_cmp(&b.area()));
+
+			if let Some((_, holes)) = owner {
+				holes.push(hole.clone());
+			}
+		}
+
+		regions
+	}
+}
+
+/// Operations
+impl PolygonSet2 {
+	pub fn union(&self, other: &PolygonSet2) -> PolygonSet2 {
+		boolean(self, other, BooleanOp::Union, FillRule::NonZero)
+	}
+
+	pub fn intersection(&self, other: &PolygonSet2) -> PolygonSet2 {
+		boolean(self, other, BooleanOp::Intersection, FillRule::NonZero)
+	}
+
+	pub fn difference(&self, other: &PolygonSet2) -> PolygonSet2 {
+		boolean(self, other, BooleanOp::Difference, FillRule::NonZero)
+	}
+
+	pub fn xor(&self, other: &PolygonSet2) -> PolygonSet2 {
+		boolean(self, other, BooleanOp::Xor, FillRule::NonZero)
+	}
+
+	/// Resolves overlapping and self intersecting contours according to `fill_rule`, producing a well formed set.
+	pub fn simplify(&self, fill_rule: FillRule) -> PolygonSet2 {
+		boolean(self, &PolygonSet2::empty(), BooleanOp::Union, fill_rule)
+	}
+}
+
+impl From<Polygon2> for PolygonSet2 {
+	fn from(polygon: Polygon2) -> PolygonSet2 {
+		PolygonSet2::new(vec![polygon])
+	}
+}
+
+impl FromIterator<Polygon2> for PolygonSet2 {
+	fn from_iter<I>(iter: I) -> PolygonSet2 where I: IntoIterator<Item=Polygon2> {
+		PolygonSet2::new(iter.into_iter().collect())
+	}
+}
+
+
+/// Computes a boolean operation between the regions filled by `a` and `b` under `fill_rule`.
+/// Output is deterministic for a given input.
+///
+/// Uses a Martinez-Rueda style sweep line, which splits edges where they meet as it passes over them and takes the
+/// winding numbers beside each piece from the piece below it. Runs in O((n + k) log n) for n edges and k intersections.
+pub fn boolean(a: &PolygonSet2, b: &PolygonSet2, op: BooleanOp, fill_rule: FillRule) -> PolygonSet2 {
+	let mut segments = Sweep::new(collect_segments(a, b)).run();
+	fold_coincident(&mut segments);
+
+	let in_result = |in_a: bool, in_b: bool| match op {
+		BooleanOp::Union => in_a || in_b,
+		BooleanOp::Intersection => in_a && in_b,
+		BooleanOp::Difference => in_a && !in_b,
+		BooleanOp::Xor => in_a != in_b,
+	};
+
+	let is_inside = |winding: [i32; 2]| in_result(fill_rule.is_filled(winding[0]), fill_rule.is_filled(winding[1]));
+
+	let mut kept = Vec::new();
+
+	for segment in segments.iter().filter(|segment| !segment.merged) {
+		let below = segment.below;
+		let above = [below[0] + segment.wind[0], below[1] + segment.wind[1]];
+
+		// Result contours keep their inside on the left
+		match (is_inside(above), is_inside(below)) {
+			(true, false) => kept.push((segment.left, segment.right)),
+			(false, true) => kept.push((segment.right, segment.left)),
+			_ => {}
+		}
+	}
+
+	PolygonSet2::new(connect_edges(&kept))
+}
+
+
+#[derive(Copy, Clone, Debug)]
+struct Segment {
+	left: Vec2,
+	right: Vec2,
+
+	/// The right end of the edge this was split from, so that pieces sort along the same line as the whole edge.
+	far: Vec2,
+
+	/// How much crossing upwards through the segment changes the winding number of each operand.
+	wind: [i32; 2],
+
+	/// The winding numbers of each operand just below the segment.
+	below: [i32; 2],
+
+	/// Whether the segment coincided with others and was folded into one of them.
+	merged: bool,
+}
+
+// Bitwise point identity, treating -0 and 0 as equal.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct PointKey(u32, u32);
+
+impl PointKey {
+	fn new(point: Vec2) -> PointKey {
+		PointKey((point.x + 0.0).to_bits(), (point.y + 0.0).to_bits())
+	}
+}
+
+// Folds identical segments together, so that each piece of boundary is classified once. Identical segments are
+// stacked directly on top of each other, so between them they separate the winding numbers below the lowest
+// from those above the highest.
+fn fold_coincident(segments: &mut [Segment]) {
+	let mut groups: HashMap<(PointKey, PointKey), Vec<usize>> = HashMap::new();
+	for (index, segment) in segments.iter().enumerate() {
+		groups.entry((PointKey::new(segment.left), PointKey::new(segment.right))).or_default().push(index);
+	}
+
+	let above = |segment: &Segment| [segment.below[0] + segment.wind[0], segment.below[1] + segment.wind[1]];
+
+	for group in groups.values().filter(|group| group.len() > 1) {
+		let lowest = group.iter().copied()
+			.find(|&index| !group.iter().any(|&other| other != index && above(&segments[other]) == segments[index].below))
+			.unwrap_or(group[0]);
+
+		let wind = group.iter().fold([0, 0], |wind, &index| [wind[0] + segments[index].wind[0], wind[1] + segments[index].wind[1]]);
+
+		for &index in group {
+			segments[index].merged = index != lowest;
+		}
+
+		segments[lowest].wind = wind;
+	}
+}
+
+fn collect_segments(a: &PolygonSet2, b: &PolygonSet2) -> Vec<Segment> {
+	let mut segments = Vec::new();
+
+	for (source, set) in [a, b].into_iter().enumerate() {
+		for contour in set.contours.iter() {
+			for (start, end) in contour.edges() {
+				// Adding zero turns -0 into 0, so that points compare the same way they hash
+				let (start, end) = (start + Vec2::zero(), end + Vec2::zero());
+
+				let (left, right, direction) = match compare_points(start, end) {
+					Ordering::Less => (start, end, 1),
+					Ordering::Greater => (end, start, -1),
+					Ordering::Equal => continue,
+				};
+
+				let mut wind = [0, 0];
+				wind[source] = direction;
+				segments.push(Segment { left, right, far: right, wind, below: [0, 0], merged: false });
+			}
+		}
+	}
+
+	segments
+}
+
+
+// An end point of a segment waiting to be passed over by the sweep line.
+#[derive(Copy, Clone, Debug)]
+struct Event {
+	point: Vec2,
+
+	/// The far end of the segment's line, used to order segments leaving the same point.
+	other: Vec2,
+
+	left: bool,
+	segment: usize,
+}
+
+// Events are processed from left to right, removing segments that end at a point before inserting those that
+// start at it, and inserting segments that start at the same point from the bottom up.
+impl Ord for Event {
+	fn cmp(&self, other: &Event) -> Ordering {
+		compare_points(self.point, other.point)
+			.then(self.left.cmp(&other.left))
+			.then_with(|| match self.left {
+				true => orientation(self.point, self.other, other.other).total_cmp(&0.0).reverse(),
+				false => Ordering::Equal,
+			})
+			.then(self.segment.cmp(&other.segment))
+	}
+}
+
+impl PartialOrd for Event {
+	fn partial_cmp(&self, other: &Event) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl PartialEq for Event {
+	fn eq(&self, other: &Event) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl Eq for Event {}
+
+
+// A segment crossing the sweep line. Segments on the sweep line never cross each other, so their order
+// can be decided from their left end points and lines alone, regardless of where the sweep line is.
+#[derive(Copy, Clone, Debug)]
+struct StatusKey {
+	left: Vec2,
+	far: Vec2,
+	segment: usize,
+}
+
+impl StatusKey {
+	fn is_below(&self, point: Vec2) -> bool {
+		orientation(self.left, self.far, point) > 0.0
+	}
+}
+
+impl Ord for StatusKey {
+	fn cmp(&self, other: &StatusKey) -> Ordering {
+		if self.segment == other.segment {
+			return Ordering::Equal;
+		}
+
+		let collinear = orientation(self.left, self.far, other.left) == 0.0 && orientation(self.left, self.far, other.far) == 0.0;
+		let below = |is_below: bool| if is_below { Ordering::Less } else { Ordering::Greater };
+
+		if collinear {
+			return compare_points(self.left, other.left).then(self.segment.cmp(&other.segment));
+		}
+
+		if PointKey::new(self.left) == PointKey::new(other.left) {
+			below(self.is_below(other.far))
+		} else if self.left.x == other.left.x {
+			below(self.left.y < other.left.y)
+		} else if compare_points(self.left, other.left) == Ordering::Greater {
+			// Compare against whichever segment was inserted first, since it spans the other's left end
+			below(!other.is_below(self.left))
+		} else {
+			below(self.is_below(other.left))
+		}
+	}
+}
+
+impl PartialOrd for StatusKey {
+	fn partial_cmp(&self, other: &StatusKey) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl PartialEq for StatusKey {
+	fn eq(&self, other: &StatusKey) -> bool {
+		self.segment == other.segment
+	}
+}
+
+impl Eq for StatusKey {}
+
+
+struct Sweep {
+	segments: Vec<Segment>,
+	events: BinaryHeap<Reverse<Event>>,
+	status: BTreeSet<StatusKey>,
+}
+
+impl Sweep {
+	fn new(segments: Vec<Segment>) -> Sweep {
+		let mut sweep = Sweep {
+			segments,
+			events: BinaryHeap::new(),
+			status: BTreeSet::new(),
+		};
+
+		for index in 0..sweep.segments.len() {
+			sweep.push_events(index);
+		}
+
+		sweep
+	}
+
+	// Splits every segment where it meets another, and finds the winding numbers below each piece.
+	fn run(mut self) -> Vec<Segment> {
+		while let Some(Reverse(event)) = self.events.pop() {
+			if event.left {
+				self.insert(event.segment);
+			} else if PointKey::new(event.point) == PointKey::new(self.segments[event.segment].right) {
+				self.remove(event.segment);
+			}
+
+			// Otherwise the segment has been split since the event was queued, and its pieces have their own events
+		}
+
+		self.segments
+	}
+
+	fn insert(&mut self, index: usize) {
+		let key = self.key(index);
+		self.status.insert(key);
+
+		let (below, above) = self.neighbors(&key);
+
+		// Neighbors are split where the new segment meets them, but the new segment is only split at the first point
+		// it meets either of them. Further points are found when the pieces are inserted.
+		let mut was_split = false;
+		let mut first_contact: Option<(Vec2, f32)> = None;
+
+		for neighbor in [below, above].into_iter().flatten() {
+			let (segment, other) = (self.segments[index], self.segments[neighbor]);
+
+			for (point, t, t_other) in segment_contacts(segment.left, segment.right, other.left, other.right) {
+				if is_interior(t_other) {
+					was_split |= self.split(neighbor, point);
+				}
+
+				if is_interior(t) && first_contact.is_none_or(|(_, first)| t < first) {
+					first_contact = Some((point, t));
+				}
+			}
+		}
+
+		if let Some((point, _)) = first_contact {
+			was_split |= self.split(index, point);
+		}
+
+		// Splitting adds new segments at this point, which may need to go below this one, so try again once they have been.
+		// The segment may have already been taken out to be turned around.
+		if was_split {
+			if self.unlink(&key) {
+				self.events.push(Reverse(self.left_event(index)));
+			}
+
+			return;
+		}
+
+		self.segments[index].below = match below {
+			Some(below) => {
+				let below = &self.segments[below];
+				[below.below[0] + below.wind[0], below.below[1] + below.wind[1]]
+			}
+
+			None => [0, 0],
+		};
+	}
+
+	fn remove(&mut self, index: usize) {
+		let key = self.key(index);
+		let (below, above) = self.neighbors(&key);
+		self.unlink(&key);
+
+		// The segments either side are now next to each other, so may meet further along
+		let (Some(below), Some(above)) = (below, above) else {
+			return;
+		};
+
+		let (lower, upper) = (self.segments[below], self.segments[above]);
+		for (point, t_lower, t_upper) in segment_contacts(lower.left, lower.right, upper.left, upper.right) {
+			if is_interior(t_lower) {
+				self.split(below, point);
+			}
+
+			if is_interior(t_upper) {
+				self.split(above, point);
+			}
+		}
+	}
+
+	// Shortens a segment to end at `point`, and adds the rest of it as a new segment, returning whether it was split.
+	// Rounding can put `point` beyond either end of a nearly vertical segment, in which case that piece is turned around.
+	fn split(&mut self, index: usize, point: Vec2) -> bool {
+		let point = point + Vec2::zero();
+		let segment = self.segments[index];
+
+		if PointKey::new(point) == PointKey::new(segment.left) || PointKey::new(point) == PointKey::new(segment.right) {
+			return false;
+		}
+
+		let negated = [-segment.wind[0], -segment.wind[1]];
+
+		let piece = match compare_points(point, segment.right) {
+			Ordering::Less => Segment { left: point, below: [0, 0], ..segment },
+			_ => Segment { left: segment.right, right: point, far: point, wind: negated, below: [0, 0], merged: false },
+		};
+
+		self.segments.push(piece);
+		self.push_events(self.segments.len() - 1);
+
+		if compare_points(segment.left, point) == Ordering::Less {
+			self.segments[index].right = point;
+			self.events.push(Reverse(self.right_event(index)));
+		} else {
+			// The segment changes which end is on the left, so has to be taken out and inserted again
+			self.unlink(&self.key(index));
+			self.segments[index] = Segment { left: point, right: segment.left, far: segment.left, wind: negated, ..segment };
+			self.push_events(index);
+		}
+
+		true
+	}
+
+	fn push_events(&mut self, index: usize) {
+		self.events.push(Reverse(self.left_event(index)));
+		self.events.push(Reverse(self.right_event(index)));
+	}
+
+	fn left_event(&self, index: usize) -> Event {
+		let segment = &self.segments[index];
+		Event { point: segment.left, other: segment.far, left: true, segment: index }
+	}
+
+	fn right_event(&self, index: usize) -> Event {
+		let segment = &self.segments[index];
+		Event { point: segment.right, other: segment.left, left: false, segment: index }
+	}
+
+	fn key(&self, index: usize) -> StatusKey {
+		let segment = &self.segments[index];
+		StatusKey { left: segment.left, far: segment.far, segment: index }
+	}
+
+	fn neighbors(&self, key: &StatusKey) -> (Option<usize>, Option<usize>) {
+		let below = self.status.range(..key).next_back();
+		let above = self.status.range((Bound::Excluded(key), Bound::Unbounded)).next();
+		(below.map(|key| key.segment), above.map(|key| key.segment))
+	}
+
+	// Takes a segment off the sweep line, returning whether it was on it.
+	fn unlink(&mut self, key: &StatusKey) -> bool {
+		if self.status.remove(key) {
+			return true;
+		}
+
+		// Rounding can make the order inconsistent in nearly degenerate cases, so fall back to searching every segment
+		let count = self.status.len();
+		self.status.retain(|other| other.segment != key.segment);
+		self.status.len() != count
+	}
+}
+
+fn is_interior(t: f32) -> bool {
+	t > 0.0 && t < 1.0
+}
+
+
+fn compare_points(a: Vec2, b: Vec2) -> Ordering {
+	a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+}
+
+// Twice the signed area of triangle abc, positive if c is left of the line from a to b.
+// Computed in double precision, so that it is exact for all but extreme inputs.
+fn orientation(a: Vec2, b: Vec2, c: Vec2) -> f64 {
+	let (ax, ay) = (a.x as f64, a.y as f64);
+	let (bx, by) = (b.x as f64, b.y as f64);
+	let (cx, cy) = (c.x as f64, c.y as f64);
+	(bx - ax) * (cy - ay) - (by - ay) * (cx - ax)
+}
+
+// Finds where segment a0-a1 touches segment b0-b1, returning each contact point with its parameter along each segment.
+// Contacts near end points snap to the end point, so that shared vertices stay bitwise identical.
+fn segment_contacts(a0: Vec2, a1: Vec2, b0: Vec2, b1: Vec2) -> Vec<(Vec2, f32, f32)> {
+	const EPSILON: f32 = 1.0e-6;
+
+	let r = a1 - a0;
+	let s = b1 - b0;
+	let offset = b0 - a0;
+
+	let denominator = r.wedge(s);
+	let scale = r.length() * s.length();
+
+	let project = |point: Vec2, origin: Vec2, direction: Vec2| (point - origin).dot(direction) / direction.square_length();
+
+	if denominator.abs() <= EPSILON * scale {
+		// Parallel, so only collinear segments can touch
+		if offset.wedge(r).abs() > EPSILON * r.length() * offset.length().max(r.length()) {
+			return Vec::new();
+		}
+
+		let mut contacts = Vec::new();
+		for (point, tb) in [(b0, 0.0), (b1, 1.0)] {
+			let t = project(point, a0, r);
+			if t > EPSILON && t < 1.0 - EPSILON {
+				contacts.push((point, t, tb));
+			}
+		}
+
+		for (point, ta) in [(a0, 0.0), (a1, 1.0)] {
+			let t = project(point, b0, s);
+			if t > EPSILON && t < 1.0 - EPSILON {
+				contacts.push((point, ta, t));
+			}
+		}
+
+		return contacts;
+	}
+
+	let ta = offset.wedge(s) / denominator;
+	let tb = offset.wedge(r) / denominator;
+
+	let valid_range = -EPSILON ..= 1.0 + EPSILON;
+	if !valid_range.contains(&ta) || !valid_range.contains(&tb) {
+		return Vec::new();
+	}
+
+	let snap = |t: f32| if t < EPSILON { 0.0 } else if t > 1.0 - EPSILON { 1.0 } else { t };
+
+	let point = match (snap(ta), snap(tb)) {
+		(0.0, _) => a0,
+		(1.0, _) => a1,
+		(_, 0.0) => b0,
+		(_, 1.0) => b1,
+		_ => a0 + r * ta,
+	};
+
+	// The parameters aren't snapped, so that a segment passing just short of the other's end point is still split there
+	vec![(point, ta, tb)]
+}
+
+// Links directed edges into closed contours. Where several edges meet, contours turn as far left as possible
+// to hug the region on their left, so that regions touching at a point get separate contours.
+fn connect_edges(edges: &[(Vec2, Vec2)]) -> Vec<Polygon2> {
+	let mut outgoing: HashMap<PointKey, Vec<usize>> = HashMap::new();
+	for (index, &(start, _)) in edges.iter().enumerate() {
+		outgoing.entry(PointKey::new(start)).or_default().push(index);
+	}
+
+	let mut used = vec![false; edges.len()];
+	let mut contours = Vec::new();
+
+	for first in 0..edges.len() {
+		if used[first] {
+			continue
+		}
+
+		let start_key = PointKey::new(edges[first].0);
+		let mut points = vec![edges[first].0];
+		let mut current = first;
+		used[first] = true;
+
+		loop {
+			let (from, to) = edges[current];
+			let to_key = PointKey::new(to);
+			if to_key == start_key {
+				break
+			}
+
+			points.push(to);
+
+			let incoming = to - from;
+			let next = outgoing.get(&to_key)
+				.into_iter()
+				.flatten()
+				.copied()
+				.filter(|&index| !used[index])
+				.max_by(|&x, &y| {
+					let turn = |index: usize| {
+						let outgoing = edges[index].1 - edges[index].0;
+						incoming.wedge(outgoing).atan2(incoming.dot(outgoing))
+					};
+
+					turn(x).total_cmp(&turn(y))
+				});
+
+			let Some(next) = next else { break };
+			used[next] = true;
+			current = next;
+		}
+
+		if let Some(contour) = clean_contour(points) {
+			contours.push(contour);
+		}
+	}
+
+	contours
+}
+
+// Removes collinear points left over from splitting, and discards contours with no area.
+fn clean_contour(mut points: Vec<Vec2>) -> Option<Polygon2> {
+	let mut index = 0;
+	while points.len() >= 3 && index < points.len() {
+		let count = points.len();
+		let previous = points[(index + count - 1) % count];
+		let next = points[(index + 1) % count];
+		let point = points[index];
+
+		let incoming = point - previous;
+		let outgoing = next - point;
+
+		if incoming.wedge(outgoing).abs() <= 1.0e-6 * incoming.length() * outgoing.length() {
+			points.remove(index);
+			index = index.saturating_sub(1);
+		} else {
+			index += 1;
+		}
+	}
+
+	let polygon = Polygon2::new(points);
+	(polygon.len() >= 3 && polygon.area() > 1.0e-9).then_some(polygon)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+	use rand::{Rng, SeedableRng};
+	use rand::rngs::StdRng;
+
+	fn rect(min: Vec2, max: Vec2) -> PolygonSet2 {
+		Polygon2::from_aabb(Aabb2::new(min, max)).into()
+	}
+
+	#[test]
+	fn test_rectangles() {
+		let a = rect(Vec2::zero(), Vec2::splat(2.0));
+		let b = rect(Vec2::one(), Vec2::splat(3.0));
+
+		assert_almost_eq!(a.union(&b).area(), 7.0);
+		assert_almost_eq!(a.intersection(&b).area(), 1.0);
+		assert_almost_eq!(a.difference(&b).area(), 3.0);
+		assert_almost_eq!(a.xor(&b).area(), 6.0);
+
+		assert_eq!(a.union(&b).contours[0].len(), 8);
+		assert_eq!(a.intersection(&b).contours[0].len(), 4);
+
+		// Disjoint
+		let c = rect(Vec2::splat(5.0), Vec2::splat(6.0));
+		assert_eq!(a.union(&c).contours.len(), 2);
+		assert!(a.intersection(&c).is_empty());
+	}
+
+	#[test]
+	fn test_coincident_edges() {
+		// Sharing an edge
+		let a = rect(Vec2::zero(), Vec2::one());
+		let b = rect(Vec2::from_x(1.0), Vec2::new(2.0, 1.0));
+
+		let union = a.union(&b);
+		assert_eq!(union.contours.len(), 1);
+		assert_eq!(union.contours[0].len(), 4);
+		assert_almost_eq!(union.area(), 2.0);
+		assert!(a.intersection(&b).is_empty());
+
+		// Identical
+		assert_almost_eq!(a.union(&a).area(), 1.0);
+		assert_almost_eq!(a.intersection(&a).area(), 1.0);
+		assert!(a.difference(&a).is_empty());
+		assert!(a.xor(&a).is_empty());
+
+		// Partially overlapping edges
+		let c = rect(Vec2::new(0.5, 0.0), Vec2::new(1.5, 0.5));
+		assert_almost_eq!(a.union(&c).area(), 1.25);
+		assert_almost_eq!(a.intersection(&c).area(), 0.25);
+	}
+
+	#[test]
+	fn test_holes() {
+		let outer = rect(Vec2::zero(), Vec2::splat(4.0));
+		let hole = rect(Vec2::one(), Vec2::splat(3.0));
+
+		let frame = outer.difference(&hole);
+		assert_almost_eq!(frame.area(), 12.0);
+		assert_eq!(frame.contours.len(), 2);
+
+		let regions = frame.regions();
+		assert_eq!(regions.len(), 1);
+		assert_eq!(regions[0].1.len(), 1);
+
+		assert!(frame.contains_point(Vec2::splat(0.5)));
+		assert!(!frame.contains_point(Vec2::splat(2.0)));
+
+		// Filling the hole back in
+		let filled = frame.union(&rect(Vec2::splat(0.5), Vec2::splat(3.5)));
+		assert_almost_eq!(filled.area(), 16.0);
+		assert_eq!(filled.contours.len(), 1);
+
+		// Holes given with either winding
+		let reversed = PolygonSet2::with_holes(
+			Polygon2::from_aabb(Aabb2::new(Vec2::zero(), Vec2::splat(4.0))),
+			[Polygon2::from_aabb(Aabb2::new(Vec2::one(), Vec2::splat(3.0)))],
+		);
+		assert_almost_eq!(reversed.simplify(FillRule::NonZero).area(), 12.0);
+	}
+
+	#[test]
+	fn test_simplify() {
+		// A figure eight, with one lobe wound each way
+		let figure_eight = PolygonSet2::from(Polygon2::new(vec![
+			Vec2::new(0.0, 0.0), Vec2::new(2.0, 2.0), Vec2::new(2.0, 0.0), Vec2::new(0.0, 2.0),
+		]));
+
+		let simplified = figure_eight.simplify(FillRule::NonZero);
+		assert_eq!(simplified.contours.len(), 2);
+		assert_almost_eq!(simplified.area(), 2.0);
+		assert_almost_eq!(figure_eight.simplify(FillRule::Positive).area(), 1.0);
+
+		// Overlapping contours
+		let overlapping: PolygonSet2 = [rect(Vec2::zero(), Vec2::splat(2.0)), rect(Vec2::one(), Vec2::splat(3.0))]
+			.into_iter()
+			.flat_map(|set| set.contours)
+			.collect();
+
+		assert_almost_eq!(overlapping.simplify(FillRule::NonZero).area(), 7.0);
+		assert_almost_eq!(overlapping.simplify(FillRule::EvenOdd).area(), 6.0);
+	}
+
+	#[test]
+	fn test_large() {
+		// Quadratic edge splitting would take far too long here
+		let circle = |center: Vec2| -> PolygonSet2 {
+			Polygon2::new((0..20000).map(|i| center + Vec2::from_angle(i as f32 / 20000.0 * TAU) * 2.0).collect()).into()
+		};
+
+		let (a, b) = (circle(Vec2::zero()), circle(Vec2::new(1.0, 0.0)));
+		let union = a.union(&b);
+		let intersection = a.intersection(&b);
+
+		assert_eq!(union.contours.len(), 1);
+		assert_eq!(intersection.contours.len(), 1);
+		assert!((union.area() + intersection.area() - a.area() - b.area()).abs() < 0.01);
+	}
+
+	fn random_polygon(rng: &mut StdRng, snap: bool) -> PolygonSet2 {
+		let center = Vec2::new(rng.random_range(-2.0..2.0), rng.random_range(-2.0..2.0));
+		let sides = rng.random_range(3..12);
+
+		// Star shaped, so always simple
+		let mut points: Vec<Vec2> = (0..sides)
+			.map(|i| {
+				let angle = (i as f32 + rng.random_range(0.0..0.8)) / sides as f32 * TAU;
+				let point = center + Vec2::from_angle(angle) * rng.random_range(0.5..3.0);
+				if snap { (point * 2.0).floor() / 2.0 } else { point }
+			})
+			.collect();
+
+		points.dedup_by(|a, b| a.x == b.x && a.y == b.y);
+		Polygon2::new(points).into()
+	}
+
+	#[test]
+	fn test_fuzz() {
+		let mut rng = StdRng::seed_from_u64(12345);
+
+		for iteration in 0..200 {
+			// Snapping to a grid produces lots of coincident edges and vertices
+			let snap = iteration % 2 == 1;
+			let a = random_polygon(&mut rng, snap).simplify(FillRule::NonZero);
+			let b = random_polygon(&mut rng, snap).simplify(FillRule::NonZero);
+
+			let union = a.union(&b);
+			let intersection = a.intersection(&b);
+			let difference = a.difference(&b);
+			let xor = a.xor(&b);
+
+			let tolerance = 0.001 * (a.area() + b.area()).max(1.0);
+			let context = format!("iteration {}: {:?} {:?}", iteration, a, b);
+
+			assert!((union.area() + intersection.area() - a.area() - b.area()).abs() < tolerance, "{}", context);
+			assert!((difference.area() - (a.area() - intersection.area())).abs() < tolerance, "{}", context);
+			assert!((xor.area() - (union.area() - intersection.area())).abs() < tolerance, "{}", context);
+
+			for result in [&union, &intersection, &difference, &xor] {
+				assert!(result.contours.iter().all(|contour| contour.len() >= 3), "{}", context);
+			}
+
+			// Sampled points agree with the operation, away from boundaries
+			for _ in 0..50 {
+				let point = Vec2::new(rng.random_range(-5.0..5.0), rng.random_range(-5.0..5.0));
+				let near_boundary = [&a, &b].iter()
+					.flat_map(|set| set.contours.iter())
+					.flat_map(|contour| contour.edges())
+					.any(|(start, end)| {
+						let t = ((point - start).dot(end - start) / (end - start).square_length()).clamp(0.0, 1.0);
+						(start + (end - start) * t - point).length() < 0.01
+					});
+
+				if near_boundary {
+					continue
+				}
+
+				let (in_a, in_b) = (a.contains_point(point), b.contains_point(point));
+				assert_eq!(union.contains_point(point), in_a || in_b, "{}", context);
+				assert_eq!(intersection.contains_point(point), in_a && in_b, "{}", context);
+				assert_eq!(difference.contains_point(point), in_a && !in_b, "{}", context);
+				assert_eq!(xor.contains_point(point), in_a != in_b, "{}", context);
+			}
+
+			// Deterministic
+			let again = a.union(&b);
+			assert_eq!(format!("{:?}", again), format!("{:?}", union));
+		}
+	}
+}
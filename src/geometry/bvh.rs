@@ -0,0 +1,684 @@
+use crate::math::*;
+use crate::geometry::shapes::*;
+
+
+const BINS: usize = 12;
+const MAX_LEAF_SIZE: usize = 4;
+const STACK_SIZE: usize = 64;
+
+// Deeper nodes are made into leaves, so that traversal never needs more than `STACK_SIZE` entries.
+const MAX_DEPTH: usize = STACK_SIZE - 1;
+
+
+/// An axis aligned box that the spatial indices such as [`Bvh`] and [`LooseTree`] can be built over.
+pub trait BoundingBox: Copy {
+	type Vector: FloatVector;
+	const AXES: usize;
+
+	fn empty() -> Self;
+	fn union(&self, other: &Self) -> Self;
+	fn overlaps(&self, other: &Self) -> bool;
+
+	/// Whether `other` is entirely inside the box.
+	fn contains(&self, other: &Self) -> bool;
+
+	/// One of the `2^AXES` equal parts of the box, with bit n of `index` selecting the upper half along axis n.
+	fn part(&self, index: usize) -> Self;
+
+	/// The box grown about its center to `factor` times its size.
+	fn scale_about_center(&self, factor: f32) -> Self;
+
+	/// The square of the distance from `point` to the box, which is infinite if the box is empty.
+	fn square_distance_to(&self, point: Self::Vector) -> f32;
+
+	/// Proportional to the chance of a random ray hitting the box, which is the surface area in 3D and the perimeter in 2D.
+	fn hit_cost(&self) -> f32;
+	fn center_on(&self, axis: usize) -> f32;
+
+	/// How far along a ray from `origin` it enters the box, given the reciprocal of each component of its direction.
+	/// Returns `None` if the ray misses or only enters after `max_time`.
+	fn ray_entry(&self, origin: Self::Vector, inverse_direction: Self::Vector, max_time: f32) -> Option<f32>;
+	fn inverse_direction(direction: Self::Vector) -> Self::Vector;
+}
+
+macro_rules! impl_bounding_box {
+	($ty:ident, $vec:ident, $axes:expr, $union:ident, $contains:ident, |$bounds:ident| $hit_cost:expr) => {
+		impl BoundingBox for $ty {
+			type Vector = $vec;
+			const AXES: usize = $axes;
+
+			fn empty() -> $ty {
+				$ty::empty()
+			}
+
+			fn union(&self, other: &$ty) -> $ty {
+				self.$union(*other)
+			}
+
+			fn overlaps(&self, other: &$ty) -> bool {
+				let (min, max) = (self.min.to_array(), self.max.to_array());
+				let (other_min, other_max) = (other.min.to_array(), other.max.to_array());
+				(0..$axes).all(|axis| min[axis] <= other_max[axis] && other_min[axis] <= max[axis])
+			}
+
+			fn contains(&self, other: &$ty) -> bool {
+				self.$contains(*other)
+			}
+
+			fn part(&self, index: usize) -> $ty {
+				let (mut min, mut max, center) = (self.min.to_array(), self.max.to_array(), self.center().to_array());
+				for axis in 0..$axes {
+					if index & (1 << axis) != 0 {
+						min[axis] = center[axis];
+					} else {
+						max[axis] = center[axis];
+					}
+				}
+
+				$ty::new($vec::from_slice(&min), $vec::from_slice(&max))
+			}
+
+			fn scale_about_center(&self, factor: f32) -> $ty {
+				self.grow(self.extents() * (factor - 1.0))
+			}
+
+			fn square_distance_to(&self, point: $vec) -> f32 {
+				if self.is_empty() {
+					return f32::INFINITY;
+				}
+				(self.closest_point(point) - point).square_length()
+			}
+
+			fn hit_cost(&self) -> f32 {
+				let $bounds = self;
+				$hit_cost
+			}
+
+			fn center_on(&self, axis: usize) -> f32 {
+				self.center().to_array()[axis]
+			}
+
+			fn ray_entry(&self, origin: $vec, inverse_direction: $vec, max_time: f32) -> Option<f32> {
+				let (min, max) = (self.min.to_array(), self.max.to_array());
+				let (origin, inverse_direction) = (origin.to_array(), inverse_direction.to_array());
+				let (mut enter, mut exit) = (0.0f32, max_time);
+
+				for axis in 0..$axes {
+					// Parallel to the slab, where the products below would be NaN for an origin on either face
+					if inverse_direction[axis].is_infinite() {
+						if origin[axis] < min[axis] || origin[axis] > max[axis] {
+							return None;
+						}
+						continue
+					}
+
+					let near = (min[axis] - origin[axis]) * inverse_direction[axis];
+					let far = (max[axis] - origin[axis]) * inverse_direction[axis];
+					enter = enter.max(near.min(far));
+					exit = exit.min(near.max(far));
+				}
+
+				(enter <= exit).then_some(enter)
+			}
+
+			fn inverse_direction(direction: $vec) -> $vec {
+				$vec::splat(1.0) / direction
+			}
+		}
+	}
+}
+
+impl_bounding_box!(Aabb2, Vec2, 2, include_rect, contains_rect, |bounds| bounds.width().max(0.0) + bounds.height().max(0.0));
+impl_bounding_box!(Aabb3, Vec3, 3, include_box, contains_box, |bounds| bounds.surface_area());
+
+
+/// A static bounding volume hierarchy over items with an axis aligned box each, for finding what a ray hits
+/// or what overlaps a region without testing every item.
+///
+/// Built using the surface area heuristic. Queries are allocation free, and the tree can be refit
+/// to follow moving items, although it gets slower the further they move from where they were when it was built.
+#[derive(Clone, Debug)]
+pub struct Bvh<T, B = Aabb3> {
+	nodes: Vec<Node<B>>,
+	items: Vec<T>,
+	bounds: Vec<B>,
+}
+
+pub type Bvh2<T> = Bvh<T, Aabb2>;
+pub type Bvh3<T> = Bvh<T, Aabb3>;
+
+// Leaves cover `count` items from `first`, while branches have a `count` of zero and their children at `first` and `first + 1`.
+#[derive(Copy, Clone, Debug)]
+struct Node<B> {
+	bounds: B,
+	first: u32,
+	count: u32,
+}
+
+/// Constructors
+impl<T, B: BoundingBox> Bvh<T, B> {
+	pub fn new(items: Vec<T>, bounds_of: impl Fn(&T) -> B) -> Bvh<T, B> {
+		if items.is_empty() {
+			return Bvh { nodes: Vec::new(), items, bounds: Vec::new() };
+		}
+
+		let bounds: Vec<B> = items.iter().map(bounds_of).collect();
+		let mut builder = Builder {
+			nodes: Vec::with_capacity(2 * items.len()),
+			order: (0..items.len() as u32).collect(),
+			bounds: &bounds,
+		};
+
+		builder.add_leaf(0, items.len());
+		builder.subdivide(0, 0);
+
+		// Store items in tree order, so that each leaf covers a contiguous range
+		let Builder { nodes, order, .. } = builder;
+		let mut slots: Vec<Option<T>> = items.into_iter().map(Some).collect();
+		let items = order.iter().map(|&index| slots[index as usize].take().unwrap()).collect();
+		let bounds = order.iter().map(|&index| bounds[index as usize]).collect();
+
+		Bvh { nodes, items, bounds }
+	}
+}
+
+/// Properties
+impl<T, B: BoundingBox> Bvh<T, B> {
+	pub fn len(&self) -> usize {
+		self.items.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.items.is_empty()
+	}
+
+	/// The bounds of every item together.
+	pub fn bounds(&self) -> B {
+		self.nodes.first().map_or(B::empty(), |node| node.bounds)
+	}
+
+	/// The items, in the order they are stored in the tree rather than the order they were added.
+	pub fn items(&self) -> &[T] {
+		&self.items
+	}
+
+	/// Changing items may move them, in which case the tree should be [refit](Bvh::refit).
+	pub fn items_mut(&mut self) -> &mut [T] {
+		&mut self.items
+	}
+}
+
+/// Queries
+impl<T, B: BoundingBox> Bvh<T, B> {
+	/// Visits each item whose bounds pass `overlaps`, which must also pass for any box containing those bounds.
+	pub fn query(&self, mut overlaps: impl FnMut(&B) -> bool, mut visit: impl FnMut(&T)) {
+		if self.nodes.is_empty() {
+			return;
+		}
+
+		let mut stack = Stack::new(0);
+		stack.push(0);
+
+		while let Some(index) = stack.pop() {
+			let node = &self.nodes[index as usize];
+			if !overlaps(&node.bounds) {
+				continue;
+			}
+
+			if node.count == 0 {
+				stack.push(node.first);
+				stack.push(node.first + 1);
+				continue;
+			}
+
+			let range = node.first as usize..(node.first + node.count) as usize;
+			for (item, bounds) in self.items[range.clone()].iter().zip(&self.bounds[range]) {
+				if node.count == 1 || overlaps(bounds) {
+					visit(item);
+				}
+			}
+		}
+	}
+
+	/// Visits each item whose bounds overlap `region`.
+	pub fn query_box(&self, region: &B, visit: impl FnMut(&T)) {
+		self.query(|bounds| bounds.overlaps(region), visit);
+	}
+
+	/// Finds the closest item hit by a ray from `origin` along `direction`, up to `max_time` times the length of `direction`.
+	///
+	/// `hit` is called for items whose bounds the ray passes through, nearest first where possible, and returns
+	/// the time at which the ray hits the item itself, if it does. Returns the closest item hit and the time it was hit.
+	pub fn ray_cast(&self, origin: B::Vector, direction: B::Vector, max_time: f32, mut hit: impl FnMut(&T) -> Option<f32>) -> Option<(&T, f32)> {
+		let root = self.nodes.first()?;
+		let inverse_direction = B::inverse_direction(direction);
+		let mut closest: Option<(&T, f32)> = None;
+		let mut max_time = max_time;
+
+		let mut stack = Stack::new((0, 0.0));
+		stack.push((0, root.bounds.ray_entry(origin, inverse_direction, max_time)?));
+
+		while let Some((index, entry)) = stack.pop() {
+			// The closest hit may have moved closer since the node was pushed
+			if entry > max_time {
+				continue;
+			}
+
+			let node = &self.nodes[index as usize];
+			if node.count == 0 {
+				let near = (node.first, self.nodes[node.first as usize].bounds.ray_entry(origin, inverse_direction, max_time));
+				let far = (node.first + 1, self.nodes[node.first as usize + 1].bounds.ray_entry(origin, inverse_direction, max_time));
+				let (near, far) = if far.1.unwrap_or(f32::INFINITY) < near.1.unwrap_or(f32::INFINITY) { (far, near) } else { (near, far) };
+
+				// Push the far child first so that the near one is visited first
+				for (child, entry) in [far, near] {
+					if let Some(entry) = entry {
+						stack.push((child, entry));
+					}
+				}
+				continue;
+			}
+
+			let range = node.first as usize..(node.first + node.count) as usize;
+			for (item, bounds) in self.items[range.clone()].iter().zip(&self.bounds[range]) {
+				if bounds.ray_entry(origin, inverse_direction, max_time).is_none() {
+					continue;
+				}
+
+				if let Some(time) = hit(item) {
+					if time >= 0.0 && time <= max_time {
+						closest = Some((item, time));
+						max_time = time;
+					}
+				}
+			}
+		}
+
+		closest
+	}
+}
+
+impl<T> Bvh<T, Aabb2> {
+	/// Visits each item whose bounds overlap `circle`.
+	pub fn query_circle(&self, circle: &Circle, visit: impl FnMut(&T)) {
+		let radius_squared = circle.radius * circle.radius;
+		self.query(|bounds| bounds.square_distance_to(circle.center) <= radius_squared, visit);
+	}
+}
+
+impl<T> Bvh<T, Aabb3> {
+	/// Visits each item whose bounds overlap `sphere`.
+	pub fn query_sphere(&self, sphere: &Sphere, visit: impl FnMut(&T)) {
+		let radius_squared = sphere.radius * sphere.radius;
+		self.query(|bounds| bounds.square_distance_to(sphere.center) <= radius_squared, visit);
+	}
+
+	/// Visits each item whose bounds are at least partly in front of every plane, such as the planes of a view frustum facing inwards.
+	/// Boxes near the frustum's corners may be visited even though they are outside.
+	pub fn query_frustum(&self, planes: &[Plane3], visit: impl FnMut(&T)) {
+		self.query(|bounds| planes.iter().all(|plane| {
+			// The corner furthest in front of the plane
+			let corner = Vec3::new(
+				if plane.normal.x >= 0.0 { bounds.max.x } else { bounds.min.x },
+				if plane.normal.y >= 0.0 { bounds.max.y } else { bounds.min.y },
+				if plane.normal.z >= 0.0 { bounds.max.z } else { bounds.min.z },
+			);
+
+			plane.distance_to(corner) >= 0.0
+		}), visit);
+	}
+}
+
+/// Modification
+impl<T, B: BoundingBox> Bvh<T, B> {
+	/// Updates the bounds of every item and node after items have moved, keeping the structure of the tree.
+	pub fn refit(&mut self, bounds_of: impl Fn(&T) -> B) {
+		for (bounds, item) in self.bounds.iter_mut().zip(&self.items) {
+			*bounds = bounds_of(item);
+		}
+
+		// Children are always stored after their parents
+		for index in (0..self.nodes.len()).rev() {
+			let Node { first, count, .. } = self.nodes[index];
+			let (first, count) = (first as usize, count as usize);
+
+			self.nodes[index].bounds = if count == 0 {
+				self.nodes[first].bounds.union(&self.nodes[first + 1].bounds)
+			} else {
+				self.bounds[first..first + count].iter().fold(B::empty(), |total, bounds| total.union(bounds))
+			};
+		}
+	}
+}
+
+
+struct Builder<'a, B> {
+	nodes: Vec<Node<B>>,
+	order: Vec<u32>,
+	bounds: &'a [B],
+}
+
+#[derive(Copy, Clone)]
+struct Bin<B> {
+	bounds: B,
+	count: usize,
+}
+
+impl<B: BoundingBox> Builder<'_, B> {
+	fn add_leaf(&mut self, first: usize, count: usize) {
+		let bounds = self.order[first..first + count].iter()
+			.fold(B::empty(), |total, &index| total.union(&self.bounds[index as usize]));
+
+		self.nodes.push(Node { bounds, first: first as u32, count: count as u32 });
+	}
+
+	fn subdivide(&mut self, node: usize, depth: usize) {
+		let Node { bounds, first, count } = self.nodes[node];
+		let (first, count) = (first as usize, count as usize);
+		if count <= 1 || depth >= MAX_DEPTH {
+			return;
+		}
+
+		let Some(split) = self.partition(first, count, bounds.hit_cost()) else {
+			return;
+		};
+
+		let children = self.nodes.len();
+		self.add_leaf(first, split - first);
+		self.add_leaf(split, first + count - split);
+		self.nodes[node] = Node { bounds, first: children as u32, count: 0 };
+
+		self.subdivide(children, depth + 1);
+		self.subdivide(children + 1, depth + 1);
+	}
+
+	// Sorts the items into bins along each axis and splits between the bins where the surface area heuristic is lowest.
+	// Returns where the second half starts after partitioning, or `None` if the items are better off left in a leaf.
+	fn partition(&mut self, first: usize, count: usize, hit_cost: f32) -> Option<usize> {
+		let range = first..first + count;
+		let mut best: Option<(f32, usize, usize, f32, f32)> = None;
+
+		for axis in 0..B::AXES {
+			let (min, max) = self.order[range.clone()].iter()
+				.map(|&index| self.bounds[index as usize].center_on(axis))
+				.fold((f32::INFINITY, -f32::INFINITY), |(min, max), center| (min.min(center), max.max(center)));
+
+			if max - min <= f32::EPSILON * max.abs().max(min.abs()) {
+				continue;
+			}
+
+			let bin_of = |center: f32| (((center - min) / (max - min) * BINS as f32) as usize).min(BINS - 1);
+			let mut bins = [Bin { bounds: B::empty(), count: 0 }; BINS];
+			for &index in &self.order[range.clone()] {
+				let bounds = &self.bounds[index as usize];
+				let bin = &mut bins[bin_of(bounds.center_on(axis))];
+				bin.bounds = bin.bounds.union(bounds);
+				bin.count += 1;
+			}
+
+			// The cost of everything right of each split, accumulated from the right
+			let mut right_costs = [0.0; BINS];
+			let mut right = Bin { bounds: B::empty(), count: 0 };
+			for split in (1..BINS).rev() {
+				right.bounds = right.bounds.union(&bins[split].bounds);
+				right.count += bins[split].count;
+				right_costs[split] = right.count as f32 * right.bounds.hit_cost();
+			}
+
+			let mut left = Bin { bounds: B::empty(), count: 0 };
+			for split in 1..BINS {
+				left.bounds = left.bounds.union(&bins[split - 1].bounds);
+				left.count += bins[split - 1].count;
+
+				let cost = left.count as f32 * left.bounds.hit_cost() + right_costs[split];
+				if left.count > 0 && left.count < count && best.is_none_or(|(best_cost, ..)| cost < best_cost) {
+					best = Some((cost, axis, split, min, max));
+				}
+			}
+		}
+
+		let split = match best {
+			Some((cost, axis, split, min, max)) if cost < count as f32 * hit_cost || count > MAX_LEAF_SIZE => {
+				let bin_of = |center: f32| (((center - min) / (max - min) * BINS as f32) as usize).min(BINS - 1);
+				let bounds = self.bounds;
+				first + partition_in_place(&mut self.order[range], |&index| bin_of(bounds[index as usize].center_on(axis)) < split)
+			}
+
+			// Items all at the same place can't be told apart, so split them evenly to limit the size of leaves
+			None if count > MAX_LEAF_SIZE => first + count / 2,
+			_ => return None,
+		};
+
+		Some(split)
+	}
+}
+
+// Moves the elements passing `predicate` to the front, returning how many there are.
+fn partition_in_place<E>(elements: &mut [E], mut predicate: impl FnMut(&E) -> bool) -> usize {
+	let mut passed = 0;
+	for index in 0..elements.len() {
+		if predicate(&elements[index]) {
+			elements.swap(passed, index);
+			passed += 1;
+		}
+	}
+
+	passed
+}
+
+
+// A fixed size stack for traversing the tree, which is never deeper than `MAX_DEPTH`.
+struct Stack<E> {
+	entries: [E; STACK_SIZE],
+	len: usize,
+}
+
+impl<E: Copy> Stack<E> {
+	fn new(fill: E) -> Stack<E> {
+		Stack { entries: [fill; STACK_SIZE], len: 0 }
+	}
+
+	fn push(&mut self, entry: E) {
+		self.entries[self.len] = entry;
+		self.len += 1;
+	}
+
+	fn pop(&mut self) -> Option<E> {
+		self.len = self.len.checked_sub(1)?;
+		Some(self.entries[self.len])
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+	use rand::{Rng, SeedableRng};
+	use rand::rngs::StdRng;
+
+	fn random_spheres(count: usize) -> Vec<Sphere> {
+		let mut rng = StdRng::seed_from_u64(3);
+		(0..count)
+			.map(|_| {
+				let center = Vec3::new(rng.random_range(-20.0..20.0), rng.random_range(-20.0..20.0), rng.random_range(-20.0..20.0));
+				Sphere::new(center, rng.random_range(0.1..1.5))
+			})
+			.collect()
+	}
+
+	fn sphere_bounds(sphere: &Sphere) -> Aabb3 {
+		Aabb3::from_center_extents(sphere.center, sphere.radius)
+	}
+
+	// The time at which a ray along a unit `direction` hits `sphere`.
+	fn ray_sphere(origin: Vec3, direction: Vec3, sphere: &Sphere) -> Option<f32> {
+		let offset = origin - sphere.center;
+		let b = offset.dot(direction);
+		let discriminant = b * b - offset.square_length() + sphere.radius * sphere.radius;
+		if discriminant < 0.0 {
+			return None;
+		}
+
+		let time = -b - discriminant.sqrt();
+		(time >= 0.0).then_some(time)
+	}
+
+	fn sorted(centers: Vec<Vec3>) -> Vec<[f32; 3]> {
+		let mut centers: Vec<[f32; 3]> = centers.into_iter().map(|center| center.to_array()).collect();
+		centers.sort_by(|a, b| a.partial_cmp(b).unwrap());
+		centers
+	}
+
+	#[test]
+	fn test_ray_cast() {
+		let spheres = random_spheres(500);
+		let bvh = Bvh::new(spheres.clone(), sphere_bounds);
+		assert_eq!(bvh.len(), 500);
+
+		let mut rng = StdRng::seed_from_u64(4);
+		for _ in 0..200 {
+			let origin = Vec3::new(rng.random_range(-25.0..25.0), rng.random_range(-25.0..25.0), rng.random_range(-25.0..25.0));
+			let direction = Vec3::new(rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0)).normalize();
+
+			let expected = spheres.iter()
+				.filter_map(|sphere| ray_sphere(origin, direction, sphere).filter(|&time| time <= 30.0))
+				.min_by(f32::total_cmp);
+
+			let mut tested = 0;
+			let hit = bvh.ray_cast(origin, direction, 30.0, |sphere| {
+				tested += 1;
+				ray_sphere(origin, direction, sphere)
+			});
+
+			assert_eq!(hit.is_some(), expected.is_some());
+			if let (Some((sphere, time)), Some(expected)) = (hit, expected) {
+				assert_almost_eq!(time, expected);
+				assert_almost_eq!(ray_sphere(origin, direction, sphere).unwrap(), expected);
+			}
+
+			assert!(tested < 100);
+		}
+
+		// Axis aligned rays have infinite components in their inverse direction
+		let target = spheres[0];
+		let origin = target.center - Vec3::from_x(50.0);
+		let (sphere, time) = bvh.ray_cast(origin, Vec3::from_x(1.0), f32::INFINITY, |sphere| ray_sphere(origin, Vec3::from_x(1.0), sphere)).unwrap();
+		assert!(time <= 50.0 - target.radius + 0.001);
+		assert_almost_eq!(ray_sphere(origin, Vec3::from_x(1.0), sphere).unwrap(), time);
+	}
+
+	#[test]
+	fn test_queries() {
+		let spheres = random_spheres(300);
+		let bvh = Bvh::new(spheres.clone(), sphere_bounds);
+
+		let region = Aabb3::new(Vec3::new(-5.0, -10.0, 0.0), Vec3::new(8.0, 3.0, 12.0));
+		let mut found = Vec::new();
+		bvh.query_box(&region, |sphere| found.push(sphere.center));
+		let expected: Vec<Vec3> = spheres.iter()
+			.filter(|sphere| sphere_bounds(sphere).overlaps(&region))
+			.map(|sphere| sphere.center)
+			.collect();
+		assert!(!found.is_empty());
+		assert_eq!(sorted(found), sorted(expected));
+
+		let query = Sphere::new(Vec3::new(3.0, -2.0, 1.0), 9.0);
+		let mut found = Vec::new();
+		bvh.query_sphere(&query, |sphere| found.push(sphere.center));
+		let expected: Vec<Vec3> = spheres.iter()
+			.filter(|sphere| sphere_bounds(sphere).closest_point(query.center).distance(query.center) <= query.radius)
+			.map(|sphere| sphere.center)
+			.collect();
+		assert!(!expected.is_empty());
+		assert_eq!(sorted(found), sorted(expected));
+
+		// A box shaped frustum from x = 0 to 10, y = -4 to 4 and z = -4 to 4
+		let planes = [
+			Plane3::new(Vec3::from_x(1.0), 0.0),
+			Plane3::new(Vec3::from_x(-1.0), -10.0),
+			Plane3::new(Vec3::from_y(1.0), -4.0),
+			Plane3::new(Vec3::from_y(-1.0), -4.0),
+			Plane3::new(Vec3::from_z(1.0), -4.0),
+			Plane3::new(Vec3::from_z(-1.0), -4.0),
+		];
+
+		let frustum = Aabb3::new(Vec3::new(0.0, -4.0, -4.0), Vec3::new(10.0, 4.0, 4.0));
+		let mut found = Vec::new();
+		bvh.query_frustum(&planes, |sphere| found.push(sphere.center));
+		let expected: Vec<Vec3> = spheres.iter()
+			.filter(|sphere| sphere_bounds(sphere).overlaps(&frustum))
+			.map(|sphere| sphere.center)
+			.collect();
+		assert!(!expected.is_empty());
+		assert_eq!(sorted(found), sorted(expected));
+	}
+
+	#[test]
+	fn test_refit() {
+		let spheres = random_spheres(200);
+		let mut bvh = Bvh::new(spheres, sphere_bounds);
+
+		for sphere in bvh.items_mut() {
+			sphere.center = sphere.center * 0.5 + Vec3::from_y(100.0);
+		}
+		bvh.refit(sphere_bounds);
+
+		let bounds = bvh.items().iter().fold(Aabb3::empty(), |total, sphere| total.include_box(sphere_bounds(sphere)));
+		assert_vec_eq!(bvh.bounds().min, bounds.min);
+		assert_vec_eq!(bvh.bounds().max, bounds.max);
+
+		let mut found = 0;
+		bvh.query_sphere(&Sphere::new(Vec3::from_y(100.0), 100.0), |_| found += 1);
+		assert_eq!(found, 200);
+
+		let target = bvh.items()[17];
+		let origin = target.center + Vec3::from_z(-50.0);
+		let (_, time) = bvh.ray_cast(origin, Vec3::from_z(1.0), 100.0, |sphere| ray_sphere(origin, Vec3::from_z(1.0), sphere)).unwrap();
+		assert!(time <= 50.0 - target.radius + 0.001);
+	}
+
+	#[test]
+	fn test_bvh2() {
+		let squares: Vec<Aabb2> = (0..20)
+			.flat_map(|x| (0..20).map(move |y| Aabb2::from_center_extents(Vec2::new(x as f32, y as f32) * 2.0, 0.5)))
+			.collect();
+		let bvh = Bvh2::new(squares, |&square| square);
+
+		let mut found = 0;
+		bvh.query_box(&Aabb2::new(Vec2::new(1.0, 1.0), Vec2::new(5.5, 5.0)), |_| found += 1);
+		assert_eq!(found, 6);
+
+		let mut found = 0;
+		bvh.query_circle(&Circle::new(Vec2::new(10.0, 10.0), 1.6), |_| found += 1);
+		assert_eq!(found, 5);
+
+		let origin = Vec2::new(-5.0, 6.1);
+		let (square, time) = bvh.ray_cast(origin, Vec2::from_x(2.0), 10.0, |square| square.ray_entry(origin, Vec2::new(0.5, f32::INFINITY), 10.0)).unwrap();
+		assert_vec_eq!(square.center(), Vec2::new(0.0, 6.0));
+		assert_almost_eq!(time, 2.25);
+
+		assert!(bvh.ray_cast(origin, Vec2::from_x(-1.0), 10.0, |_| Some(0.0)).is_none());
+
+		// Straight down the face shared by two boxes
+		let pair = Bvh2::new(vec![Aabb2::new(Vec2::zero(), Vec2::one()), Aabb2::new(Vec2::from_x(1.0), Vec2::new(2.0, 1.0))], |&square| square);
+		let mut tested = 0;
+		pair.ray_cast(Vec2::new(1.0, 5.0), Vec2::from_y(-1.0), 10.0, |_| { tested += 1; None });
+		assert_eq!(tested, 2);
+
+		// Empty bounds are never near anything
+		let mut found = 0;
+		Bvh2::new(vec![Aabb2::empty()], |&square| square).query_circle(&Circle::new(Vec2::zero(), 1.0), |_| found += 1);
+		assert_eq!(found, 0);
+
+		// Many items in the same place
+		let bvh = Bvh2::new(vec![Aabb2::from_center_extents(Vec2::one(), 1.0); 50], |&square| square);
+		let mut found = 0;
+		bvh.query_box(&Aabb2::from_point(Vec2::zero()), |_| found += 1);
+		assert_eq!(found, 50);
+
+		let empty = Bvh2::new(Vec::<Aabb2>::new(), |&square| square);
+		assert!(empty.is_empty());
+		assert!(empty.ray_cast(origin, Vec2::from_x(1.0), 10.0, |_| Some(0.0)).is_none());
+	}
+}
@@ -0,0 +1,637 @@
+use crate::math::*;
+use std::collections::{HashMap, HashSet};
+
+
+/// A Delaunay triangulation of a set of points, optionally with constrained edges.
+///
+/// Each triangle has three half-edges, numbered `3 * triangle + i`, which run from vertex `i` of the triangle to the next.
+/// Edges between two triangles are made of a pair of opposite half-edges.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Delaunay2 {
+	pub points: Vec<Vec2>,
+
+	/// Counter-clockwise triangles as indices into `points`.
+	pub triangles: Vec<[u32; 3]>,
+
+	/// The opposite of each half-edge, or `None` for edges on the convex hull.
+	pub half_edges: Vec<Option<u32>>,
+
+	/// Whether each half-edge is part of a constrained edge.
+	pub constrained: Vec<bool>,
+}
+
+/// Constructors
+impl Delaunay2 {
+	/// Triangulates `points` with the Bowyer-Watson algorithm.
+	/// Duplicate points are left out of the triangulation, and no triangles are produced if all points are collinear.
+	pub fn new(points: Vec<Vec2>) -> Delaunay2 {
+		Delaunay2::with_constraints(points, &[])
+	}
+
+	/// Triangulates `points` such that each pair of indices in `constraints` is joined by an edge.
+	/// Constrained edges are split at any points they pass through, and should not cross each other.
+	pub fn with_constraints(points: Vec<Vec2>, constraints: &[[u32; 2]]) -> Delaunay2 {
+		let mut builder = Builder::new(&points);
+
+		if builder.insert_points() {
+			for &[a, b] in constraints {
+				builder.insert_constraint(a, b);
+			}
+		}
+
+		let (triangles, half_edges, constrained) = builder.build();
+		Delaunay2 { points, triangles, half_edges, constrained }
+	}
+}
+
+/// Half-edges
+impl Delaunay2 {
+	pub fn triangle_of_half_edge(half_edge: u32) -> u32 {
+		half_edge / 3
+	}
+
+	/// The next half-edge counter-clockwise around the same triangle.
+	pub fn next_half_edge(half_edge: u32) -> u32 {
+		if half_edge % 3 == 2 { half_edge - 2 } else { half_edge + 1 }
+	}
+
+	/// The previous half-edge counter-clockwise around the same triangle.
+	pub fn previous_half_edge(half_edge: u32) -> u32 {
+		if half_edge % 3 == 0 { half_edge + 2 } else { half_edge - 1 }
+	}
+
+	/// The start and end point indices of a half-edge.
+	pub fn half_edge_points(&self, half_edge: u32) -> [u32; 2] {
+		let triangle = self.triangles[half_edge as usize / 3];
+		let i = half_edge as usize % 3;
+		[triangle[i], triangle[(i + 1) % 3]]
+	}
+
+	/// Each edge once, as a half-edge.
+	pub fn edges(&self) -> impl Iterator<Item=u32> + '_ {
+		(0..self.half_edges.len() as u32)
+			.filter(|&half_edge| self.half_edges[half_edge as usize].is_none_or(|opposite| opposite > half_edge))
+	}
+
+	/// Half-edges without an opposite, which together form the convex hull.
+	pub fn hull_half_edges(&self) -> impl Iterator<Item=u32> + '_ {
+		(0..self.half_edges.len() as u32)
+			.filter(|&half_edge| self.half_edges[half_edge as usize].is_none())
+	}
+}
+
+/// Queries
+impl Delaunay2 {
+	/// The triangles across each edge of a triangle.
+	pub fn triangle_neighbors(&self, triangle: u32) -> [Option<u32>; 3] {
+		std::array::from_fn(|i| self.half_edges[triangle as usize * 3 + i].map(Delaunay2::triangle_of_half_edge))
+	}
+
+	/// The points joined by an edge to each point, in ascending order.
+	pub fn point_neighbors(&self) -> Vec<Vec<u32>> {
+		let mut neighbors = vec![Vec::new(); self.points.len()];
+		for half_edge in self.edges() {
+			let [a, b] = self.half_edge_points(half_edge);
+			neighbors[a as usize].push(b);
+			neighbors[b as usize].push(a);
+		}
+
+		for list in neighbors.iter_mut() {
+			list.sort();
+		}
+
+		neighbors
+	}
+
+	pub fn triangle_points(&self, triangle: u32) -> [Vec2; 3] {
+		self.triangles[triangle as usize].map(|i| self.points[i as usize])
+	}
+
+	/// Finds the triangle containing `point`, if any.
+	pub fn find_triangle(&self, point: Vec2) -> Option<u32> {
+		(0..self.triangles.len() as u32).find(|&triangle| {
+			let [a, b, c] = self.triangle_points(triangle);
+			orient(a, b, point) >= 0.0 && orient(b, c, point) >= 0.0 && orient(c, a, point) >= 0.0
+		})
+	}
+}
+
+
+// Stands in for the single vertex at infinity shared by all triangles outside of the convex hull.
+// Using "ghost" triangles rather than a large super triangle keeps the hull exact.
+const GHOST: u32 = u32::MAX;
+
+struct Builder<'p> {
+	points: &'p [Vec2],
+	triangles: Vec<[u32; 3]>,
+	alive: Vec<bool>,
+
+	// Maps each directed edge to the triangle it belongs to
+	edges: HashMap<(u32, u32), u32>,
+
+	// A triangle touching each point, and the point each duplicate was merged into
+	vertex_triangle: Vec<u32>,
+	representative: Vec<u32>,
+
+	constraints: HashSet<(u32, u32)>,
+	last: u32,
+}
+
+impl<'p> Builder<'p> {
+	fn new(points: &'p [Vec2]) -> Builder<'p> {
+		Builder {
+			points,
+			triangles: Vec::new(),
+			alive: Vec::new(),
+			edges: HashMap::new(),
+			vertex_triangle: vec![GHOST; points.len()],
+			representative: (0..points.len() as u32).collect(),
+			constraints: HashSet::new(),
+			last: 0,
+		}
+	}
+
+	fn position(&self, index: u32) -> Vec2 {
+		self.points[index as usize]
+	}
+
+	fn add_triangle(&mut self, vertices: [u32; 3]) -> u32 {
+		// Ghost vertices always go last
+		let vertices = match vertices.iter().position(|&v| v == GHOST) {
+			Some(0) => [vertices[1], vertices[2], GHOST],
+			Some(1) => [vertices[2], vertices[0], GHOST],
+			_ => vertices,
+		};
+
+		let triangle = self.triangles.len() as u32;
+		self.triangles.push(vertices);
+		self.alive.push(true);
+
+		for i in 0..3 {
+			self.edges.insert((vertices[i], vertices[(i + 1) % 3]), triangle);
+			if vertices[i] != GHOST {
+				self.vertex_triangle[vertices[i] as usize] = triangle;
+			}
+		}
+
+		self.last = triangle;
+		triangle
+	}
+
+	fn remove_triangle(&mut self, triangle: u32) {
+		let vertices = self.triangles[triangle as usize];
+		self.alive[triangle as usize] = false;
+
+		for i in 0..3 {
+			self.edges.remove(&(vertices[i], vertices[(i + 1) % 3]));
+		}
+	}
+
+	fn neighbor(&self, triangle: u32, edge: usize) -> u32 {
+		let vertices = self.triangles[triangle as usize];
+		self.edges[&(vertices[(edge + 1) % 3], vertices[edge])]
+	}
+
+	// Whether `point` is strictly inside the circumcircle of a triangle.
+	// For ghost triangles the circumcircle degenerates to the open half-plane outside their hull edge.
+	fn in_circumcircle(&self, triangle: u32, point: Vec2) -> bool {
+		let [a, b, c] = self.triangles[triangle as usize];
+		let (a, b) = (self.position(a), self.position(b));
+
+		if c == GHOST {
+			let side = orient(a, b, point);
+			return side > 0.0 || side == 0.0 && (point - a).dot(point - b) < 0.0;
+		}
+
+		in_circle(a, b, self.position(c), point)
+	}
+
+	// Returns false if the points are all collinear.
+	fn insert_points(&mut self) -> bool {
+		let count = self.points.len() as u32;
+
+		// Start from the first triangle of distinct, non-collinear points
+		let Some(second) = (1..count).find(|&i| !same_point(self.position(i), self.position(0))) else {
+			return false;
+		};
+
+		let Some(third) = (second + 1..count).find(|&i| orient(self.position(0), self.position(second), self.position(i)) != 0.0) else {
+			return false;
+		};
+
+		let [a, b, c] = if orient(self.position(0), self.position(second), self.position(third)) > 0.0 {
+			[0, second, third]
+		} else {
+			[0, third, second]
+		};
+
+		self.add_triangle([b, a, GHOST]);
+		self.add_triangle([c, b, GHOST]);
+		self.add_triangle([a, c, GHOST]);
+		self.add_triangle([a, b, c]);
+
+		for index in 0..count {
+			if index != second && index != third && index != 0 {
+				self.insert_point(index);
+			}
+		}
+
+		true
+	}
+
+	fn insert_point(&mut self, index: u32) {
+		let point = self.position(index);
+		let start = self.locate(point);
+
+		let duplicate = self.triangles[start as usize].into_iter()
+			.find(|&v| v != GHOST && same_point(self.position(v), point));
+
+		if let Some(duplicate) = duplicate {
+			self.representative[index as usize] = duplicate;
+			return;
+		}
+
+		// The cavity of triangles whose circumcircles contain the point is connected and star shaped around it
+		let mut cavity = vec![start];
+		let mut in_cavity = HashSet::from([start]);
+		let mut boundary = Vec::new();
+		let mut next = 0;
+
+		while next < cavity.len() {
+			let triangle = cavity[next];
+			next += 1;
+
+			for edge in 0..3 {
+				let neighbor = self.neighbor(triangle, edge);
+				if in_cavity.contains(&neighbor) {
+					continue
+				}
+
+				if self.in_circumcircle(neighbor, point) {
+					in_cavity.insert(neighbor);
+					cavity.push(neighbor);
+				} else {
+					let vertices = self.triangles[triangle as usize];
+					boundary.push((vertices[edge], vertices[(edge + 1) % 3]));
+				}
+			}
+		}
+
+		for &triangle in cavity.iter() {
+			self.remove_triangle(triangle);
+		}
+
+		for (a, b) in boundary {
+			self.add_triangle([a, b, index]);
+		}
+	}
+
+	// Finds a triangle whose circumcircle contains the point, by walking towards it from the last triangle.
+	fn locate(&self, point: Vec2) -> u32 {
+		let contains = |triangle: u32| {
+			let vertices = self.triangles[triangle as usize];
+			if vertices[2] == GHOST {
+				return self.in_circumcircle(triangle, point);
+			}
+
+			(0..3).all(|i| orient(self.position(vertices[i]), self.position(vertices[(i + 1) % 3]), point) >= 0.0)
+		};
+
+		let mut triangle = self.last;
+		for _ in 0..self.triangles.len() {
+			let vertices = self.triangles[triangle as usize];
+
+			let edge = if vertices[2] == GHOST {
+				// Step back inside the hull
+				(!contains(triangle)).then_some(0)
+			} else {
+				(0..3).find(|&i| orient(self.position(vertices[i]), self.position(vertices[(i + 1) % 3]), point) < 0.0)
+			};
+
+			match edge {
+				Some(edge) => triangle = self.neighbor(triangle, edge),
+				None => return triangle,
+			}
+		}
+
+		// Walks can cycle on degenerate input, so fall back to checking every triangle
+		(0..self.triangles.len() as u32)
+			.find(|&triangle| self.alive[triangle as usize] && contains(triangle))
+			.unwrap()
+	}
+
+	fn insert_constraint(&mut self, a: u32, b: u32) {
+		let (mut from, to) = (self.representative[a as usize], self.representative[b as usize]);
+
+		// The constraint is split at any points it passes through
+		while from != to {
+			from = self.insert_constraint_segment(from, to);
+		}
+	}
+
+	// Inserts as much of the edge from `a` to `b` as possible, returning where it stopped.
+	fn insert_constraint_segment(&mut self, a: u32, b: u32) -> u32 {
+		let (start, end) = (self.position(a), self.position(b));
+		let on_segment = |v: u32| v != GHOST
+			&& orient(start, end, self.position(v)) == 0.0
+			&& (self.position(v) - start).dot(end - start) > 0.0;
+
+		// Find the triangle around `a` that the edge leaves through
+		let first = self.vertex_triangle[a as usize];
+		let mut triangle = first;
+		let (right, left) = loop {
+			let vertices = self.triangles[triangle as usize];
+			let i = vertices.iter().position(|&v| v == a).unwrap();
+			let (v1, v2) = (vertices[(i + 1) % 3], vertices[(i + 2) % 3]);
+
+			for v in [v1, v2] {
+				if v == b || on_segment(v) {
+					self.constraints.insert(edge_key(a, v));
+					return v;
+				}
+			}
+
+			if v1 != GHOST && v2 != GHOST
+				&& orient(start, end, self.position(v1)) < 0.0
+				&& orient(start, end, self.position(v2)) > 0.0 {
+				break (v1, v2);
+			}
+
+			triangle = self.edges[&(a, v2)];
+			if triangle == first {
+				// Unreachable for valid triangulations
+				return b;
+			}
+		};
+
+		// Walk through the triangles crossed by the edge, collecting the points on either side
+		let mut crossed = vec![triangle];
+		let mut right_chain = vec![right];
+		let mut left_chain = vec![left];
+		let (mut right, mut left) = (right, left);
+
+		let reached = loop {
+			let next = self.edges[&(left, right)];
+			crossed.push(next);
+
+			let vertices = self.triangles[next as usize];
+			let w = *vertices.iter().find(|&&v| v != left && v != right).unwrap();
+
+			if w == b || on_segment(w) {
+				break w;
+			}
+
+			if orient(start, end, self.position(w)) < 0.0 {
+				right_chain.push(w);
+				right = w;
+			} else {
+				left_chain.push(w);
+				left = w;
+			}
+		};
+
+		for &triangle in crossed.iter() {
+			self.remove_triangle(triangle);
+		}
+
+		right_chain.reverse();
+		self.triangulate_pseudo_polygon(a, reached, &left_chain);
+		self.triangulate_pseudo_polygon(reached, a, &right_chain);
+
+		self.constraints.insert(edge_key(a, reached));
+		reached
+	}
+
+	// Triangulates the polygon formed by the edge from `a` to `b` and a chain of points on its left from `a` to `b`.
+	// https://doi.org/10.1016/S0167-8396(03)00019-2
+	fn triangulate_pseudo_polygon(&mut self, a: u32, b: u32, chain: &[u32]) {
+		if chain.is_empty() {
+			return;
+		}
+
+		let (start, end) = (self.position(a), self.position(b));
+		let mut split = 0;
+		for i in 1..chain.len() {
+			if in_circle(start, end, self.position(chain[split]), self.position(chain[i])) {
+				split = i;
+			}
+		}
+
+		let c = chain[split];
+		self.add_triangle([a, b, c]);
+		self.triangulate_pseudo_polygon(a, c, &chain[..split]);
+		self.triangulate_pseudo_polygon(c, b, &chain[split + 1..]);
+	}
+
+	// Collects the real triangles with their half-edge opposites and constraint flags.
+	fn build(self) -> (Vec<[u32; 3]>, Vec<Option<u32>>, Vec<bool>) {
+		let triangles: Vec<[u32; 3]> = self.triangles.iter().zip(self.alive.iter())
+			.filter(|&(vertices, &alive)| alive && vertices[2] != GHOST)
+			.map(|(&vertices, _)| vertices)
+			.collect();
+
+		let half_edge_index: HashMap<(u32, u32), u32> = triangles.iter().enumerate()
+			.flat_map(|(triangle, vertices)| (0..3).map(move |i| ((vertices[i], vertices[(i + 1) % 3]), triangle as u32 * 3 + i as u32)))
+			.collect();
+
+		let mut half_edges = Vec::with_capacity(triangles.len() * 3);
+		let mut constrained = Vec::with_capacity(triangles.len() * 3);
+
+		for vertices in triangles.iter() {
+			for i in 0..3 {
+				let (a, b) = (vertices[i], vertices[(i + 1) % 3]);
+				half_edges.push(half_edge_index.get(&(b, a)).copied());
+				constrained.push(self.constraints.contains(&edge_key(a, b)));
+			}
+		}
+
+		(triangles, half_edges, constrained)
+	}
+}
+
+
+fn edge_key(a: u32, b: u32) -> (u32, u32) {
+	(a.min(b), a.max(b))
+}
+
+fn same_point(a: Vec2, b: Vec2) -> bool {
+	a.x == b.x && a.y == b.y
+}
+
+// Positive when `c` is to the left of the line from `a` to `b`. Computed in f64 so that the sign is reliable.
+fn orient(a: Vec2, b: Vec2, c: Vec2) -> f64 {
+	let (abx, aby) = (b.x as f64 - a.x as f64, b.y as f64 - a.y as f64);
+	let (acx, acy) = (c.x as f64 - a.x as f64, c.y as f64 - a.y as f64);
+	abx * acy - aby * acx
+}
+
+// Whether `d` is strictly inside the circumcircle of the counter-clockwise triangle `a`, `b`, `c`.
+fn in_circle(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+	let relative = |p: Vec2| {
+		let (x, y) = (p.x as f64 - d.x as f64, p.y as f64 - d.y as f64);
+		(x, y, x * x + y * y)
+	};
+
+	let (ax, ay, a2) = relative(a);
+	let (bx, by, b2) = relative(b);
+	let (cx, cy, c2) = relative(c);
+
+	let determinant = ax * (by * c2 - b2 * cy)
+		- ay * (bx * c2 - b2 * cx)
+		+ a2 * (bx * cy - by * cx);
+
+	determinant > 0.0
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+	use rand::{Rng, SeedableRng};
+	use rand::rngs::StdRng;
+
+	fn random_points(rng: &mut StdRng, count: usize) -> Vec<Vec2> {
+		(0..count).map(|_| Vec2::new(rng.random_range(-10.0..10.0), rng.random_range(-10.0..10.0))).collect()
+	}
+
+	fn total_area(delaunay: &Delaunay2) -> f32 {
+		(0..delaunay.triangles.len() as u32)
+			.map(|triangle| {
+				let [a, b, c] = delaunay.triangle_points(triangle);
+				(b - a).wedge(c - a) / 2.0
+			})
+			.sum()
+	}
+
+	fn assert_valid(delaunay: &Delaunay2) {
+		assert_eq!(delaunay.half_edges.len(), delaunay.triangles.len() * 3);
+
+		for (half_edge, &opposite) in delaunay.half_edges.iter().enumerate() {
+			let [a, b] = delaunay.half_edge_points(half_edge as u32);
+			if let Some(opposite) = opposite {
+				assert_eq!(delaunay.half_edges[opposite as usize], Some(half_edge as u32));
+				assert_eq!(delaunay.half_edge_points(opposite), [b, a]);
+				assert_eq!(delaunay.constrained[opposite as usize], delaunay.constrained[half_edge]);
+			}
+		}
+
+		for triangle in 0..delaunay.triangles.len() as u32 {
+			let [a, b, c] = delaunay.triangle_points(triangle);
+			assert!((b - a).wedge(c - a) > 0.0);
+		}
+
+		// The triangles cover the convex hull of the points
+		let hull = convex_hull_2d(&delaunay.points);
+		assert!((total_area(delaunay) - hull.area()).abs() < hull.area() * 1.0e-4);
+	}
+
+	#[test]
+	fn test_square() {
+		let points = vec![Vec2::zero(), Vec2::from_x(2.0), Vec2::splat(2.0), Vec2::from_y(2.0), Vec2::one()];
+		let delaunay = Delaunay2::new(points);
+
+		assert_valid(&delaunay);
+		assert_eq!(delaunay.triangles.len(), 4);
+		assert_eq!(delaunay.hull_half_edges().count(), 4);
+		assert_eq!(delaunay.edges().count(), 8);
+		assert_eq!(delaunay.point_neighbors()[4], [0, 1, 2, 3]);
+
+		let triangle = delaunay.find_triangle(Vec2::new(1.0, 0.5)).unwrap();
+		assert_eq!(delaunay.triangle_neighbors(triangle).iter().filter(|n| n.is_some()).count(), 2);
+		assert!(delaunay.find_triangle(Vec2::splat(3.0)).is_none());
+	}
+
+	#[test]
+	fn test_empty_circumcircles() {
+		let mut rng = StdRng::seed_from_u64(3);
+
+		for count in [3, 10, 100, 500] {
+			let points = random_points(&mut rng, count);
+			let delaunay = Delaunay2::new(points.clone());
+			assert_valid(&delaunay);
+
+			// Euler's formula for a triangulated point set
+			let hull_count = delaunay.hull_half_edges().count();
+			assert_eq!(delaunay.triangles.len(), 2 * count - 2 - hull_count);
+
+			for triangle in delaunay.triangles.iter() {
+				let [a, b, c] = triangle.map(|i| points[i as usize]);
+				let d = 2.0 * (a.x * (b.y - c.y) + b.x * (c.y - a.y) + c.x * (a.y - b.y));
+				let center = Vec2::new(
+					(a.square_length() * (b.y - c.y) + b.square_length() * (c.y - a.y) + c.square_length() * (a.y - b.y)) / d,
+					(a.square_length() * (c.x - b.x) + b.square_length() * (a.x - c.x) + c.square_length() * (b.x - a.x)) / d,
+				);
+
+				let radius = (a - center).length();
+				for &point in points.iter() {
+					assert!((point - center).length() > radius - 1.0e-3);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_degenerate() {
+		// Collinear points have no triangles
+		let line: Vec<Vec2> = (0..5).map(|i| Vec2::splat(i as f32)).collect();
+		assert!(Delaunay2::new(line).triangles.is_empty());
+		assert!(Delaunay2::new(Vec::new()).triangles.is_empty());
+
+		// Duplicates are skipped
+		let points = vec![Vec2::zero(), Vec2::zero(), Vec2::from_x(1.0), Vec2::from_y(1.0), Vec2::from_x(1.0), Vec2::one()];
+		let delaunay = Delaunay2::new(points);
+		assert_valid(&delaunay);
+		assert_eq!(delaunay.triangles.len(), 2);
+		assert!(delaunay.point_neighbors()[1].is_empty());
+
+		// Grids are full of cocircular and collinear points
+		let grid: Vec<Vec2> = (0..100).map(|i| Vec2::new((i % 10) as f32, (i / 10) as f32)).collect();
+		let delaunay = Delaunay2::new(grid);
+		assert_valid(&delaunay);
+		assert_eq!(delaunay.triangles.len(), 162);
+
+		// Collinear points along the hull
+		let mut points: Vec<Vec2> = (0..10).map(|i| Vec2::from_x(i as f32)).collect();
+		points.push(Vec2::new(4.5, 3.0));
+		points.push(Vec2::from_x(-3.0));
+		let delaunay = Delaunay2::new(points);
+		assert_valid(&delaunay);
+		assert_eq!(delaunay.triangles.len(), 10);
+	}
+
+	#[test]
+	fn test_constraints() {
+		// The Delaunay diagonal of this quad is between the close points
+		let points = vec![Vec2::zero(), Vec2::new(2.0, -0.2), Vec2::from_x(4.0), Vec2::new(2.0, 0.2)];
+		assert_eq!(Delaunay2::new(points.clone()).point_neighbors()[0], [1, 3]);
+
+		let delaunay = Delaunay2::with_constraints(points, &[[0, 2]]);
+		assert_valid(&delaunay);
+		assert_eq!(delaunay.point_neighbors()[0], [1, 2, 3]);
+		assert_eq!(delaunay.constrained.iter().filter(|&&c| c).count(), 2);
+
+		let mut rng = StdRng::seed_from_u64(4);
+		for _ in 0..20 {
+			let mut points = random_points(&mut rng, 200);
+			points.extend([Vec2::new(-10.0, 0.0), Vec2::new(10.0, 0.0), Vec2::new(0.0, -10.0), Vec2::new(0.0, 10.0)]);
+
+			// Constraints are split at the points they pass through
+			points.push(Vec2::zero());
+			let constraints = [[200, 201], [202, 203]];
+
+			let delaunay = Delaunay2::with_constraints(points.clone(), &constraints);
+			assert_valid(&delaunay);
+
+			let constrained_edges: Vec<[u32; 2]> = delaunay.edges()
+				.filter(|&half_edge| delaunay.constrained[half_edge as usize])
+				.map(|half_edge| delaunay.half_edge_points(half_edge))
+				.collect();
+
+			let has_edge = |a: u32, b: u32| constrained_edges.iter().any(|&edge| edge == [a, b] || edge == [b, a]);
+			assert!(has_edge(200, 204) && has_edge(204, 201));
+			assert!(has_edge(202, 204) && has_edge(204, 203));
+		}
+	}
+}
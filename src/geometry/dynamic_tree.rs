@@ -0,0 +1,600 @@
+use crate::math::*;
+use crate::geometry::bvh::BoundingBox;
+use crate::geometry::slot_map::{SlotKey, SlotMap};
+
+
+// Marks a missing parent or child.
+const NULL: u32 = u32::MAX;
+
+// How far ahead of a moving item its bounds are stretched, in multiples of its displacement.
+const DISPLACEMENT_MULTIPLIER: f32 = 2.0;
+
+
+/// Identifies an item in a [`DynamicTree`]. Handles of removed items stay invalid, even once their node is reused.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ProxyHandle(SlotKey);
+
+/// A bounding volume hierarchy over 2D boxes that can be changed cheaply, for finding overlaps between moving objects.
+///
+/// Each item is stored with its box grown by a margin, so that small movements don't change the tree.
+/// The tree is kept balanced by rotating nodes as items are inserted and removed.
+#[derive(Clone, Debug)]
+pub struct DynamicTree<T> {
+	nodes: SlotMap<TreeNode<T>>,
+	root: u32,
+	margin: f32,
+	len: usize,
+}
+
+// Leaves have no children and hold an item, branches have two children.
+#[derive(Clone, Debug)]
+struct TreeNode<T> {
+	bounds: Aabb2,
+	parent: u32,
+	children: [u32; 2],
+	height: u32,
+	item: Option<T>,
+}
+
+impl<T> TreeNode<T> {
+	fn is_leaf(&self) -> bool {
+		self.children[0] == NULL
+	}
+}
+
+/// Constructors
+impl<T> DynamicTree<T> {
+	/// Creates a tree that grows the box of each item by `margin` in every direction.
+	pub fn new(margin: f32) -> DynamicTree<T> {
+		DynamicTree {
+			nodes: SlotMap::new(),
+			root: NULL,
+			margin,
+			len: 0,
+		}
+	}
+}
+
+/// Properties
+impl<T> DynamicTree<T> {
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	pub fn margin(&self) -> f32 {
+		self.margin
+	}
+
+	/// The number of branches between the root and the deepest item.
+	pub fn height(&self) -> u32 {
+		self.node(self.root).map_or(0, |node| node.height)
+	}
+
+	pub fn get(&self, handle: ProxyHandle) -> Option<&T> {
+		self.nodes.get(handle.0)?.item.as_ref()
+	}
+
+	pub fn get_mut(&mut self, handle: ProxyHandle) -> Option<&mut T> {
+		self.nodes.get_mut(handle.0)?.item.as_mut()
+	}
+
+	/// The grown box stored for an item, which contains the box it was last inserted or moved with.
+	pub fn fat_bounds(&self, handle: ProxyHandle) -> Option<Aabb2> {
+		let node = self.nodes.get(handle.0)?;
+		node.item.as_ref().map(|_| node.bounds)
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item=(ProxyHandle, &T)> + '_ {
+		self.nodes.iter().filter_map(|(key, node)| Some((ProxyHandle(key), node.item.as_ref()?)))
+	}
+}
+
+/// Queries
+impl<T> DynamicTree<T> {
+	/// Visits each item whose grown box overlaps `region`.
+	pub fn query(&self, region: &Aabb2, mut visit: impl FnMut(ProxyHandle, &T)) {
+		self.traverse(|bounds| bounds.overlaps(region), |leaf| visit(self.handle(leaf), self.nodes[leaf].item.as_ref().unwrap()));
+	}
+
+	/// Finds the closest item hit by a ray from `origin` along `direction`, up to `max_time` times the length of `direction`.
+	///
+	/// `hit` is called for items whose grown box the ray passes through, and returns the time at which
+	/// the ray hits the item itself, if it does. Returns the closest item hit and the time it was hit.
+	pub fn ray_cast(&self, origin: Vec2, direction: Vec2, max_time: f32, mut hit: impl FnMut(ProxyHandle, &T) -> Option<f32>) -> Option<(ProxyHandle, f32)> {
+		let inverse_direction = Aabb2::inverse_direction(direction);
+		let mut closest = None;
+		let mut max_time = max_time;
+
+		let mut stack = vec![self.root];
+		while let Some(index) = stack.pop() {
+			let Some(node) = self.node(index) else { continue };
+			if node.bounds.ray_entry(origin, inverse_direction, max_time).is_none() {
+				continue;
+			}
+
+			match &node.item {
+				Some(item) => if let Some(time) = hit(self.handle(index), item) {
+					if time >= 0.0 && time <= max_time {
+						closest = Some((self.handle(index), time));
+						max_time = time;
+					}
+				}
+
+				None => stack.extend(node.children),
+			}
+		}
+
+		closest
+	}
+
+	/// Visits each pair of items whose grown boxes overlap, once per pair.
+	pub fn overlapping_pairs(&self, mut visit: impl FnMut(ProxyHandle, ProxyHandle)) {
+		for (key, node) in self.nodes.iter() {
+			if node.item.is_none() {
+				continue;
+			}
+
+			let index = key.index();
+			self.traverse(|bounds| bounds.overlaps(&node.bounds), |other| if other > index {
+				visit(ProxyHandle(key), self.handle(other));
+			});
+		}
+	}
+
+	// Visits each leaf that passes `overlaps`, skipping branches that don't pass.
+	fn traverse(&self, mut overlaps: impl FnMut(&Aabb2) -> bool, mut visit: impl FnMut(u32)) {
+		let mut stack = vec![self.root];
+		while let Some(index) = stack.pop() {
+			let Some(node) = self.node(index) else { continue };
+			if !overlaps(&node.bounds) {
+				continue;
+			}
+
+			if node.is_leaf() {
+				visit(index);
+			} else {
+				stack.extend(node.children);
+			}
+		}
+	}
+
+	fn node(&self, index: u32) -> Option<&TreeNode<T>> {
+		self.nodes.at(index)
+	}
+
+	fn handle(&self, leaf: u32) -> ProxyHandle {
+		ProxyHandle(self.nodes.key(leaf))
+	}
+}
+
+/// Modification
+impl<T> DynamicTree<T> {
+	pub fn insert(&mut self, bounds: Aabb2, item: T) -> ProxyHandle {
+		let leaf = self.allocate(TreeNode {
+			bounds: bounds.grow(self.margin),
+			parent: NULL,
+			children: [NULL; 2],
+			height: 0,
+			item: Some(item),
+		});
+
+		self.insert_leaf(leaf);
+		self.len += 1;
+		self.handle(leaf)
+	}
+
+	/// Removes an item, returning it if the handle was valid.
+	pub fn remove(&mut self, handle: ProxyHandle) -> Option<T> {
+		// Only leaves are handed out, so a valid handle is always one
+		self.nodes.get(handle.0)?;
+		self.remove_leaf(handle.0.index());
+		self.len -= 1;
+		self.nodes.remove(handle.0)?.item
+	}
+
+	/// Updates the box of an item that has moved by `displacement`, returning whether the tree had to change.
+	/// Nothing changes while the new box fits within the grown one. Otherwise the grown box is stretched
+	/// in the direction of motion, so that items keeping on the same path don't need updating every time.
+	pub fn move_proxy(&mut self, handle: ProxyHandle, bounds: Aabb2, displacement: Vec2) -> bool {
+		let Some(fat_bounds) = self.fat_bounds(handle) else {
+			return false;
+		};
+
+		if fat_bounds.contains_rect(bounds) {
+			return false;
+		}
+
+		let grown = bounds.grow(self.margin);
+		let leaf = handle.0.index();
+		self.remove_leaf(leaf);
+		self.nodes[leaf].bounds = grown.include_rect(grown.translate(displacement * DISPLACEMENT_MULTIPLIER));
+		self.insert_leaf(leaf);
+		true
+	}
+
+	fn allocate(&mut self, node: TreeNode<T>) -> u32 {
+		self.nodes.insert(node).index()
+	}
+
+	// Pairs the leaf with the sibling that grows the total perimeter of the tree's boxes the least, from Box2D.
+	fn insert_leaf(&mut self, leaf: u32) {
+		if self.root == NULL {
+			self.root = leaf;
+			self.nodes[leaf].parent = NULL;
+			return;
+		}
+
+		let bounds = self.nodes[leaf].bounds;
+		let mut sibling = self.root;
+		while !self.nodes[sibling].is_leaf() {
+			let node = &self.nodes[sibling];
+			let combined = perimeter(node.bounds.include_rect(bounds));
+
+			// Pairing with this node makes a new parent, while descending grows this node's box
+			let cost = 2.0 * combined;
+			let inherited = 2.0 * (combined - perimeter(node.bounds));
+
+			let child_cost = |child: u32| {
+				let child = &self.nodes[child];
+				let growth = perimeter(child.bounds.include_rect(bounds));
+				if child.is_leaf() { growth + inherited } else { growth - perimeter(child.bounds) + inherited }
+			};
+
+			let [first, second] = node.children;
+			let (first_cost, second_cost) = (child_cost(first), child_cost(second));
+			if cost < first_cost && cost < second_cost {
+				break;
+			}
+
+			sibling = if first_cost < second_cost { first } else { second };
+		}
+
+		let old_parent = self.nodes[sibling].parent;
+		let parent = self.allocate(TreeNode {
+			bounds: bounds.include_rect(self.nodes[sibling].bounds),
+			parent: old_parent,
+			children: [sibling, leaf],
+			height: self.nodes[sibling].height + 1,
+			item: None,
+		});
+
+		self.replace_child(old_parent, sibling, parent);
+		self.nodes[sibling].parent = parent;
+		self.nodes[leaf].parent = parent;
+		self.refit_ancestors(parent);
+	}
+
+	fn remove_leaf(&mut self, leaf: u32) {
+		if leaf == self.root {
+			self.root = NULL;
+			return;
+		}
+
+		let parent = self.nodes[leaf].parent;
+		let [first, second] = self.nodes[parent].children;
+		let sibling = if first == leaf { second } else { first };
+
+		let grandparent = self.nodes[parent].parent;
+		self.replace_child(grandparent, parent, sibling);
+		self.nodes[sibling].parent = grandparent;
+		self.nodes.remove_at(parent);
+
+		if grandparent != NULL {
+			self.refit_ancestors(grandparent);
+		}
+	}
+
+	// Points `parent` at `new` instead of `old`, where a `parent` of `NULL` means the root.
+	fn replace_child(&mut self, parent: u32, old: u32, new: u32) {
+		if parent == NULL {
+			self.root = new;
+			return;
+		}
+
+		for child in &mut self.nodes[parent].children {
+			if *child == old {
+				*child = new;
+			}
+		}
+	}
+
+	// Rebalances and recomputes the boxes and heights of `index` and everything above it.
+	fn refit_ancestors(&mut self, index: u32) {
+		let mut index = index;
+		while index != NULL {
+			index = self.balance(index);
+			self.refresh(index);
+			index = self.nodes[index].parent;
+		}
+	}
+
+	fn refresh(&mut self, index: u32) {
+		let [first, second] = self.nodes[index].children;
+		let (first, second) = (&self.nodes[first], &self.nodes[second]);
+		let (bounds, height) = (first.bounds.include_rect(second.bounds), first.height.max(second.height) + 1);
+
+		let node = &mut self.nodes[index];
+		node.bounds = bounds;
+		node.height = height;
+	}
+
+	// If one child of the branch is more than one level taller than the other, rotates the taller child up to take its place
+	// and hands the shorter of its children down to the branch, until the heights of every node's children differ by at
+	// most one. Returns the node now in the branch's place.
+	fn balance(&mut self, branch: u32) -> u32 {
+		let node = &self.nodes[branch];
+		if node.is_leaf() || node.height < 2 {
+			return branch;
+		}
+
+		let heights = node.children.map(|child| self.nodes[child].height);
+		let side = match heights[1] as i64 - heights[0] as i64 {
+			2.. => 1,
+			..=-2 => 0,
+			_ => return branch,
+		};
+
+		let taller = node.children[side];
+		let [first, second] = self.nodes[taller].children;
+		let (kept, handed_down) = if self.nodes[first].height > self.nodes[second].height {
+			(first, second)
+		} else {
+			(second, first)
+		};
+
+		let parent = self.nodes[branch].parent;
+		self.replace_child(parent, branch, taller);
+		self.nodes[taller].parent = parent;
+		self.nodes[taller].children = [branch, kept];
+		self.nodes[branch].parent = taller;
+		self.nodes[branch].children[side] = handed_down;
+		self.nodes[handed_down].parent = branch;
+
+		// Leaves paired high up in the tree can leave the branch lopsided by more than one level, so the branch
+		// may need balancing in turn
+		self.refresh(branch);
+		self.balance(branch);
+		self.refresh(taller);
+		taller
+	}
+}
+
+
+fn perimeter(bounds: Aabb2) -> f32 {
+	let size = bounds.size();
+	2.0 * (size.x + size.y)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+	use rand::{Rng, SeedableRng};
+	use rand::rngs::StdRng;
+
+	fn random_box(rng: &mut StdRng) -> Aabb2 {
+		let center = Vec2::new(rng.random_range(-50.0..50.0), rng.random_range(-50.0..50.0));
+		Aabb2::from_center_extents(center, Vec2::new(rng.random_range(0.1..2.0), rng.random_range(0.1..2.0)))
+	}
+
+	fn overlaps(a: &Aabb2, b: &Aabb2) -> bool {
+		a.min.x <= b.max.x && b.min.x <= a.max.x && a.min.y <= b.max.y && b.min.y <= a.max.y
+	}
+
+	// Checks the links, boxes and heights of every node, and that the tree is balanced.
+	fn validate<T>(tree: &DynamicTree<T>) {
+		fn visit<T>(tree: &DynamicTree<T>, index: u32) -> u32 {
+			let node = &tree.nodes[index];
+			if node.is_leaf() {
+				assert!(node.item.is_some());
+				return 0;
+			}
+
+			let [first, second] = node.children;
+			assert_eq!(tree.nodes[first].parent, index);
+			assert_eq!(tree.nodes[second].parent, index);
+			assert!(node.bounds.contains_rect(tree.nodes[first].bounds));
+			assert!(node.bounds.contains_rect(tree.nodes[second].bounds));
+
+			let (first, second) = (visit(tree, first), visit(tree, second));
+			assert!(first.abs_diff(second) <= 1);
+			assert_eq!(node.height, first.max(second) + 1);
+			node.height
+		}
+
+		if tree.is_empty() {
+			assert_eq!(tree.height(), 0);
+		} else {
+			assert_eq!(tree.nodes[tree.root].parent, u32::MAX);
+			visit(tree, tree.root);
+			assert_eq!(tree.iter().count(), tree.len());
+		}
+	}
+
+	#[test]
+	fn test_insert_remove() {
+		let mut rng = StdRng::seed_from_u64(5);
+		let mut tree = DynamicTree::new(0.5);
+		let mut handles = Vec::new();
+
+		for index in 0..500 {
+			let bounds = random_box(&mut rng);
+			handles.push((tree.insert(bounds, index), bounds));
+		}
+
+		validate(&tree);
+		assert_eq!(tree.len(), 500);
+		assert!(tree.height() <= 18);
+
+		let (handle, bounds) = handles[42];
+		assert_eq!(tree.get(handle), Some(&42));
+		assert!(tree.fat_bounds(handle).unwrap().contains_rect(bounds.grow(0.5)));
+
+		let removed: Vec<ProxyHandle> = handles.drain(..250).map(|(handle, _)| handle).collect();
+		for &handle in &removed {
+			assert!(tree.remove(handle).is_some());
+			assert!(tree.remove(handle).is_none());
+			assert!(tree.get(handle).is_none());
+		}
+
+		validate(&tree);
+		assert_eq!(tree.len(), 250);
+
+		// Freed nodes are reused
+		let capacity = tree.nodes.capacity();
+		for index in 0..100 {
+			tree.insert(random_box(&mut rng), 1000 + index);
+		}
+
+		validate(&tree);
+		assert_eq!(tree.nodes.capacity(), capacity);
+
+		// Handles of removed items don't reach the items now in their nodes
+		for &handle in &removed {
+			assert!(tree.get(handle).is_none());
+			assert!(tree.fat_bounds(handle).is_none());
+			assert!(!tree.move_proxy(handle, Aabb2::zero(), Vec2::zero()));
+		}
+
+		let remaining: Vec<ProxyHandle> = tree.iter().map(|(handle, _)| handle).collect();
+		for handle in remaining {
+			tree.remove(handle);
+		}
+
+		validate(&tree);
+		assert!(tree.is_empty());
+	}
+
+	// Random inserts, removals and moves, where pairing leaves high up in the tree leaves it lopsided
+	#[test]
+	fn test_stays_balanced() {
+		let mut rng = StdRng::seed_from_u64(8);
+		let mut tree = DynamicTree::new(0.1);
+		let mut handles = Vec::new();
+
+		for step in 0..20000 {
+			match rng.random_range(0..4) {
+				0 | 1 => handles.push(tree.insert(random_box(&mut rng), step)),
+				2 if !handles.is_empty() => {
+					let handle = handles.swap_remove(rng.random_range(0..handles.len()));
+					tree.remove(handle);
+				}
+				_ if !handles.is_empty() => {
+					let handle = handles[rng.random_range(0..handles.len())];
+					tree.move_proxy(handle, random_box(&mut rng), Vec2::zero());
+				}
+				_ => {}
+			}
+
+			if step % 100 == 0 {
+				validate(&tree);
+			}
+		}
+
+		validate(&tree);
+	}
+
+	#[test]
+	fn test_move() {
+		let mut rng = StdRng::seed_from_u64(6);
+		let mut tree = DynamicTree::new(1.0);
+		let mut items: Vec<(ProxyHandle, Aabb2)> = (0..200)
+			.map(|index| {
+				let bounds = random_box(&mut rng);
+				(tree.insert(bounds, index), bounds)
+			})
+			.collect();
+
+		// Small moves stay within the grown box
+		let (handle, bounds) = items[0];
+		assert!(!tree.move_proxy(handle, bounds.translate(Vec2::new(0.5, -0.5)), Vec2::new(0.5, -0.5)));
+
+		// Larger moves stretch the grown box ahead of the motion
+		let moved = bounds.translate(Vec2::from_x(3.0));
+		assert!(tree.move_proxy(handle, moved, Vec2::from_x(3.0)));
+		let fat_bounds = tree.fat_bounds(handle).unwrap();
+		assert!(fat_bounds.contains_rect(moved.translate(Vec2::from_x(6.0))));
+		assert_almost_eq!(fat_bounds.min.x, moved.min.x - 1.0);
+		items[0].1 = moved;
+
+		for _ in 0..20 {
+			for (handle, bounds) in &mut items {
+				let displacement = Vec2::new(rng.random_range(-3.0..3.0), rng.random_range(-3.0..3.0));
+				*bounds = bounds.translate(displacement);
+				tree.move_proxy(*handle, *bounds, displacement);
+				assert!(tree.fat_bounds(*handle).unwrap().contains_rect(*bounds));
+			}
+
+			validate(&tree);
+		}
+	}
+
+	#[test]
+	fn test_queries() {
+		let mut rng = StdRng::seed_from_u64(7);
+		let mut tree = DynamicTree::new(0.2);
+		for index in 0..300 {
+			tree.insert(random_box(&mut rng), index);
+		}
+
+		let fat: Vec<(ProxyHandle, Aabb2)> = tree.iter().map(|(handle, _)| (handle, tree.fat_bounds(handle).unwrap())).collect();
+
+		let region = Aabb2::new(Vec2::new(-10.0, -20.0), Vec2::new(15.0, 5.0));
+		let mut found = Vec::new();
+		tree.query(&region, |handle, _| found.push(handle));
+		found.sort();
+		let expected: Vec<ProxyHandle> = fat.iter().filter(|(_, bounds)| overlaps(bounds, &region)).map(|&(handle, _)| handle).collect();
+		assert!(!expected.is_empty());
+		assert_eq!(found, expected);
+
+		let mut pairs = Vec::new();
+		tree.overlapping_pairs(|a, b| pairs.push((a.min(b), a.max(b))));
+		pairs.sort();
+		let mut expected = Vec::new();
+		for (i, (a, a_bounds)) in fat.iter().enumerate() {
+			for (b, b_bounds) in &fat[i + 1..] {
+				if overlaps(a_bounds, b_bounds) {
+					expected.push((*a.min(b), *a.max(b)));
+				}
+			}
+		}
+		expected.sort();
+		assert!(!expected.is_empty());
+		assert_eq!(pairs, expected);
+
+		// Ray casts against the grown boxes, which are exact here
+		for _ in 0..50 {
+			let origin = Vec2::new(rng.random_range(-60.0..60.0), rng.random_range(-60.0..60.0));
+			let direction = Vec2::new(rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0));
+			let inverse_direction = Aabb2::inverse_direction(direction);
+
+			let expected = fat.iter()
+				.filter_map(|(_, bounds)| bounds.ray_entry(origin, inverse_direction, 100.0))
+				.min_by(f32::total_cmp);
+
+			let hit = tree.ray_cast(origin, direction, 100.0, |handle, _| tree.fat_bounds(handle).unwrap().ray_entry(origin, inverse_direction, 100.0));
+			assert_eq!(hit.is_some(), expected.is_some());
+			if let (Some((_, time)), Some(expected)) = (hit, expected) {
+				assert_almost_eq!(time, expected);
+			}
+		}
+	}
+
+	#[test]
+	fn test_ray_along_face() {
+		// Straight down the face shared by two boxes, which touches both
+		let mut tree = DynamicTree::new(0.0);
+		tree.insert(Aabb2::new(Vec2::zero(), Vec2::one()), 0);
+		tree.insert(Aabb2::new(Vec2::from_x(1.0), Vec2::new(2.0, 1.0)), 1);
+
+		let mut tested = Vec::new();
+		tree.ray_cast(Vec2::new(1.0, 5.0), Vec2::from_y(-1.0), 10.0, |_, &item| { tested.push(item); None });
+		tested.sort();
+		assert_eq!(tested, [0, 1]);
+	}
+}
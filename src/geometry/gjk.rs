@@ -0,0 +1,955 @@
+use crate::math::*;
+use crate::geometry::shapes::*;
+use crate::geometry::polygon::Polygon2;
+use crate::geometry::hull::ConvexHull3;
+use crate::geometry::intersect::Contact;
+
+
+/// Vectors that GJK and EPA can work in.
+pub trait SupportVector: FloatVector + 'static {
+	/// The unit vector along each axis.
+	const AXES: &'static [Self];
+}
+
+impl SupportVector for Vec2 {
+	const AXES: &'static [Vec2] = &[Vec2::from_x(1.0), Vec2::from_y(1.0)];
+}
+
+impl SupportVector for Vec3 {
+	const AXES: &'static [Vec3] = &[Vec3::from_x(1.0), Vec3::from_y(1.0), Vec3::from_z(1.0)];
+}
+
+
+/// A convex shape described by its furthest point in any direction, for use with `gjk_distance` and `epa_contact`.
+///
+/// Point sets stand in for their convex hulls. Rounded shapes can also describe the core they are rounded from,
+/// which GJK converges on much faster than on curved surfaces.
+pub trait SupportMap {
+	type Vector: SupportVector;
+
+	/// The point of the shape furthest along `direction`, which need not be normalized.
+	fn support(&self, direction: Self::Vector) -> Self::Vector;
+
+	/// The point of the shape's core furthest along `direction`. The shape is its core grown by `radius`.
+	fn core_support(&self, direction: Self::Vector) -> Self::Vector {
+		self.support(direction)
+	}
+
+	fn radius(&self) -> f32 {
+		0.0
+	}
+}
+
+impl<S: SupportMap + ?Sized> SupportMap for &S {
+	type Vector = S::Vector;
+
+	fn support(&self, direction: S::Vector) -> S::Vector {
+		(**self).support(direction)
+	}
+
+	fn core_support(&self, direction: S::Vector) -> S::Vector {
+		(**self).core_support(direction)
+	}
+
+	fn radius(&self) -> f32 {
+		(**self).radius()
+	}
+}
+
+
+/// The Minkowski sum of two shapes, such as a box and a sphere for a box with rounded corners.
+#[derive(Copy, Clone, Debug)]
+pub struct MinkowskiSum<A, B> {
+	pub a: A,
+	pub b: B,
+}
+
+impl<A, B> MinkowskiSum<A, B> {
+	pub fn new(a: A, b: B) -> MinkowskiSum<A, B> {
+		MinkowskiSum { a, b }
+	}
+}
+
+impl<A, B> SupportMap for MinkowskiSum<A, B>
+	where A: SupportMap, B: SupportMap<Vector=A::Vector>
+{
+	type Vector = A::Vector;
+
+	fn support(&self, direction: A::Vector) -> A::Vector {
+		self.a.support(direction) + self.b.support(direction)
+	}
+
+	fn core_support(&self, direction: A::Vector) -> A::Vector {
+		self.a.core_support(direction) + self.b.core_support(direction)
+	}
+
+	fn radius(&self) -> f32 {
+		self.a.radius() + self.b.radius()
+	}
+}
+
+
+/// A shape moved into place by an affine transform, either a `Mat2x3` for 2D shapes or a `Mat3x4` for 3D shapes.
+///
+/// Since transforms can scale unevenly, rounded shapes are treated as the whole of their surface.
+#[derive(Copy, Clone, Debug)]
+pub struct Transformed<S, M> {
+	pub shape: S,
+	pub transform: M,
+}
+
+impl<S, M> Transformed<S, M> {
+	pub fn new(shape: S, transform: M) -> Transformed<S, M> {
+		Transformed { shape, transform }
+	}
+}
+
+impl<S: SupportMap<Vector=Vec2>> SupportMap for Transformed<S, Mat2x3> {
+	type Vector = Vec2;
+
+	fn support(&self, direction: Vec2) -> Vec2 {
+		// Directions transform by the transpose of the linear part
+		let [x, y, _] = self.transform.columns();
+		self.transform * self.shape.support(Vec2::new(x.dot(direction), y.dot(direction)))
+	}
+}
+
+impl<S: SupportMap<Vector=Vec3>> SupportMap for Transformed<S, Mat3x4> {
+	type Vector = Vec3;
+
+	fn support(&self, direction: Vec3) -> Vec3 {
+		let [x, y, z, _] = self.transform.columns();
+		self.transform * self.shape.support(Vec3::new(x.dot(direction), y.dot(direction), z.dot(direction)))
+	}
+}
+
+
+/// A `ConvexHull3` along with the points it was built from.
+#[derive(Copy, Clone, Debug)]
+pub struct HullPoints<'a> {
+	pub hull: &'a ConvexHull3,
+	pub points: &'a [Vec3],
+}
+
+impl ConvexHull3 {
+	/// Pairs the hull with the points it was built from, so it can be used as a `SupportMap`.
+	pub fn with_points<'a>(&'a self, points: &'a [Vec3]) -> HullPoints<'a> {
+		HullPoints { hull: self, points }
+	}
+}
+
+impl SupportMap for HullPoints<'_> {
+	type Vector = Vec3;
+
+	fn support(&self, direction: Vec3) -> Vec3 {
+		furthest(self.hull.faces.iter().flatten().map(|&index| self.points[index as usize]), direction)
+	}
+}
+
+
+macro_rules! impl_support_map {
+	($vector:ident, $circle:ty, $capsule:ty, $segment:ty, $triangle:ty, $obb:ty) => {
+		impl SupportMap for $vector {
+			type Vector = $vector;
+
+			fn support(&self, _: $vector) -> $vector {
+				*self
+			}
+		}
+
+		impl SupportMap for [$vector] {
+			type Vector = $vector;
+
+			fn support(&self, direction: $vector) -> $vector {
+				furthest(self.iter().copied(), direction)
+			}
+		}
+
+		impl SupportMap for $circle {
+			type Vector = $vector;
+
+			fn support(&self, direction: $vector) -> $vector {
+				self.center + normalize_or_zero(direction) * self.radius
+			}
+
+			fn core_support(&self, _: $vector) -> $vector {
+				self.center
+			}
+
+			fn radius(&self) -> f32 {
+				self.radius
+			}
+		}
+
+		impl SupportMap for $capsule {
+			type Vector = $vector;
+
+			fn support(&self, direction: $vector) -> $vector {
+				self.segment().support(direction) + normalize_or_zero(direction) * self.radius
+			}
+
+			fn core_support(&self, direction: $vector) -> $vector {
+				self.segment().support(direction)
+			}
+
+			fn radius(&self) -> f32 {
+				self.radius
+			}
+		}
+
+		impl SupportMap for $segment {
+			type Vector = $vector;
+
+			fn support(&self, direction: $vector) -> $vector {
+				if (self.end - self.start).dot(direction) > 0.0 { self.end } else { self.start }
+			}
+		}
+
+		impl SupportMap for $triangle {
+			type Vector = $vector;
+
+			fn support(&self, direction: $vector) -> $vector {
+				furthest(self.points(), direction)
+			}
+		}
+
+		impl SupportMap for $obb {
+			type Vector = $vector;
+
+			fn support(&self, direction: $vector) -> $vector {
+				self.axes().into_iter()
+					.zip(self.extents.to_array())
+					.fold(self.center, |point, (axis, extent)| point + axis * extent.copysign(axis.dot(direction)))
+			}
+		}
+	}
+}
+
+impl_support_map!(Vec2, Circle, Capsule2, Segment2, Triangle2, Obb2);
+impl_support_map!(Vec3, Sphere, Capsule3, Segment3, Triangle3, Obb3);
+
+impl SupportMap for Aabb2 {
+	type Vector = Vec2;
+
+	fn support(&self, direction: Vec2) -> Vec2 {
+		Vec2::new(
+			if direction.x < 0.0 { self.min.x } else { self.max.x },
+			if direction.y < 0.0 { self.min.y } else { self.max.y },
+		)
+	}
+}
+
+impl SupportMap for Aabb3 {
+	type Vector = Vec3;
+
+	fn support(&self, direction: Vec3) -> Vec3 {
+		Vec3::new(
+			if direction.x < 0.0 { self.min.x } else { self.max.x },
+			if direction.y < 0.0 { self.min.y } else { self.max.y },
+			if direction.z < 0.0 { self.min.z } else { self.max.z },
+		)
+	}
+}
+
+impl SupportMap for Polygon2 {
+	type Vector = Vec2;
+
+	fn support(&self, direction: Vec2) -> Vec2 {
+		self.points.support(direction)
+	}
+}
+
+
+/// The closest points between two separated shapes.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Separation<V> {
+	pub distance: f32,
+
+	/// The unit direction from the first shape towards the second.
+	pub normal: V,
+
+	/// The point on the first shape closest to the second.
+	pub point_a: V,
+
+	/// The point on the second shape closest to the first.
+	pub point_b: V,
+}
+
+/// Uses GJK to find the closest points between two convex shapes, or `None` if they touch or overlap.
+pub fn gjk_distance<A, B>(a: &A, b: &B) -> Option<Separation<A::Vector>>
+	where A: SupportMap + ?Sized, B: SupportMap<Vector=A::Vector> + ?Sized
+{
+	let Gjk::Separated(simplex) = gjk(a, b) else {
+		return None;
+	};
+
+	// Move the closest points of the cores out to the surfaces
+	let (point_a, point_b) = simplex.witnesses();
+	let distance = (point_b - point_a).length();
+	let radius = a.radius() + b.radius();
+	if distance <= radius {
+		return None;
+	}
+
+	let normal = (point_b - point_a) / distance;
+	Some(Separation {
+		distance: distance - radius,
+		normal,
+		point_a: point_a + normal * a.radius(),
+		point_b: point_b - normal * b.radius(),
+	})
+}
+
+/// Uses GJK to test whether two convex shapes touch or overlap.
+pub fn gjk_intersects<A, B>(a: &A, b: &B) -> bool
+	where A: SupportMap + ?Sized, B: SupportMap<Vector=A::Vector> + ?Sized
+{
+	match gjk(a, b) {
+		Gjk::Separated(simplex) => {
+			let (point_a, point_b) = simplex.witnesses();
+			(point_b - point_a).length() <= a.radius() + b.radius()
+		}
+		Gjk::Overlapping(_) => true,
+	}
+}
+
+/// Uses GJK and EPA to find how deeply two convex shapes overlap, or `None` if they are separated.
+///
+/// Shapes that are flat in the same line or plane give a contact with zero depth.
+pub fn epa_contact<A, B>(a: &A, b: &B) -> Option<Contact<A::Vector>>
+	where A: SupportMap + ?Sized, B: SupportMap<Vector=A::Vector> + ?Sized
+{
+	let radius = a.radius() + b.radius();
+	let surface_point = |normal: A::Vector, point_a: A::Vector, point_b: A::Vector| {
+		(point_a + normal * a.radius() + point_b - normal * b.radius()) / 2.0
+	};
+
+	let mut simplex = match gjk(a, b) {
+		Gjk::Overlapping(simplex) => simplex,
+		Gjk::Separated(simplex) => {
+			// Only the rounding overlaps, so the cores' closest points give the contact directly
+			let (point_a, point_b) = simplex.witnesses();
+			let distance = (point_b - point_a).length();
+			if distance > radius {
+				return None;
+			}
+
+			// Unless the cores touch, leaving no reliable direction between them, which is then found as for overlaps
+			if distance * distance > GJK_TOLERANCE * simplex.scale() {
+				let normal = (point_b - point_a) / distance;
+				return Some(Contact {
+					normal,
+					depth: radius - distance,
+					point: surface_point(normal, point_a, point_b),
+				});
+			}
+
+			simplex
+		}
+	};
+
+	// EPA needs a full-dimensional simplex to start from, so add points until it spans every axis
+	let dimension = A::Vector::AXES.len();
+	let origin = simplex.vertices[0].point;
+	let mut basis = Basis::default();
+	let vertices = simplex.vertices;
+	simplex.retain(|index| index == 0 || basis.push(vertices[index].point - origin));
+
+	for &axis in A::Vector::AXES.iter() {
+		for direction in [axis, -axis] {
+			if simplex.len <= dimension {
+				let vertex = support_vertex(a, b, direction);
+				if basis.push(vertex.point - origin) {
+					simplex.push(vertex);
+				}
+			}
+		}
+	}
+
+	let (normal, depth, (point_a, point_b)) = if simplex.len <= dimension {
+		(basis.orthogonal(), 0.0, simplex.witnesses())
+	} else {
+		Polytope::new(&simplex.vertices[..simplex.len]).expand(a, b)
+	};
+
+	Some(Contact {
+		normal,
+		depth: depth + radius,
+		point: surface_point(normal, point_a, point_b),
+	})
+}
+
+
+const GJK_ITERATIONS: usize = 64;
+const EPA_ITERATIONS: usize = 128;
+
+// Relative tolerances, scaled by the size of the Minkowski difference
+const GJK_TOLERANCE: f32 = 1.0e-6;
+const EPA_TOLERANCE: f32 = 1.0e-6;
+const OVERLAP_TOLERANCE: f32 = 1.0e-10;
+
+// Relative to square lengths, and well above rounding error so that nearly flat simplices are skipped
+const DEGENERATE_TOLERANCE: f32 = 1.0e-5;
+
+// A point of the Minkowski difference `a - b` along with the points of each shape it came from.
+#[derive(Copy, Clone, Debug, Default)]
+struct Vertex<V> {
+	point: V,
+	a: V,
+	b: V,
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct Simplex<V> {
+	vertices: [Vertex<V>; 4],
+	weights: [f32; 4],
+	len: usize,
+}
+
+impl<V: SupportVector> Simplex<V> {
+	fn push(&mut self, vertex: Vertex<V>) {
+		self.vertices[self.len] = vertex;
+		self.len += 1;
+	}
+
+	// Keeps the vertices whose index passes `keep`.
+	fn retain(&mut self, mut keep: impl FnMut(usize) -> bool) {
+		let mut len = 0;
+		for i in 0..self.len {
+			if keep(i) {
+				self.vertices[len] = self.vertices[i];
+				self.weights[len] = self.weights[i];
+				len += 1;
+			}
+		}
+		self.len = len;
+	}
+
+	fn contains(&self, point: V) -> bool {
+		self.vertices[..self.len].iter().any(|vertex| (vertex.point - point).square_length() == 0.0)
+	}
+
+	fn scale(&self) -> f32 {
+		self.vertices[..self.len].iter()
+			.map(|vertex| vertex.point.square_length())
+			.fold(0.0, f32::max)
+	}
+
+	// The closest points on each shape, from the weights of the simplex's closest point to the origin
+	fn witnesses(&self) -> (V, V) {
+		(0..self.len)
+			.map(|i| (self.vertices[i].a * self.weights[i], self.vertices[i].b * self.weights[i]))
+			.fold((V::default(), V::default()), |(a, b), (next_a, next_b)| (a + next_a, b + next_b))
+	}
+}
+
+enum Gjk<V> {
+	Separated(Simplex<V>),
+	Overlapping(Simplex<V>),
+}
+
+// Finds the closest point of the Minkowski difference `a - b` to the origin, reducing the simplex to the vertices
+// that closest point lies between.
+fn gjk<A, B>(a: &A, b: &B) -> Gjk<A::Vector>
+	where A: SupportMap + ?Sized, B: SupportMap<Vector=A::Vector> + ?Sized
+{
+	let dimension = A::Vector::AXES.len();
+	let mut simplex = Simplex::default();
+	simplex.push(support_vertex(a, b, A::Vector::AXES[0]));
+	simplex.weights[0] = 1.0;
+
+	let mut closest = simplex.vertices[0].point;
+
+	for _ in 0..GJK_ITERATIONS {
+		let square_distance = closest.square_length();
+		if square_distance <= OVERLAP_TOLERANCE * simplex.scale() {
+			return Gjk::Overlapping(simplex);
+		}
+
+		// Stop once the furthest point back towards the origin gets no closer
+		let vertex = support_vertex(a, b, -closest);
+		if square_distance - closest.dot(vertex.point) <= GJK_TOLERANCE * square_distance || simplex.contains(vertex.point) {
+			break
+		}
+
+		let previous = simplex;
+		simplex.push(vertex);
+
+		let points = simplex.vertices.map(|vertex| vertex.point);
+		let (weights, next) = closest_to_origin(&points[..simplex.len]);
+		simplex.weights = weights;
+		simplex.retain(|index| weights[index] > 0.0);
+
+		// Rounding can stop progress before the tolerance is reached
+		if next.square_length() >= square_distance {
+			simplex = previous;
+			break
+		}
+
+		closest = next;
+		if simplex.len > dimension {
+			return Gjk::Overlapping(simplex);
+		}
+	}
+
+	Gjk::Separated(simplex)
+}
+
+// The closest point to the origin within the convex hull of up to four points, along with its weight for each point.
+fn closest_to_origin<V: SupportVector>(points: &[V]) -> ([f32; 4], V) {
+	let mut best: Option<(f32, u32, [f32; 4], V)> = None;
+
+	// The closest point is the projection onto the affine hull of one of the subsets, so try each subset whose
+	// projection lies inside it
+	for mask in 1..(1u32 << points.len()) {
+		let mut subset = [V::default(); 4];
+		let mut indices = [0; 4];
+		let mut len = 0;
+		for (i, &point) in points.iter().enumerate() {
+			if mask & (1 << i) != 0 {
+				subset[len] = point;
+				indices[len] = i;
+				len += 1;
+			}
+		}
+
+		let Some((subset_weights, point)) = project_origin(&subset[..len]) else { continue };
+		if subset_weights[..len].iter().any(|&weight| weight < 0.0) {
+			continue
+		}
+
+		let square_distance = point.square_length();
+		if best.is_none_or(|(best_distance, best_count, _, _)| square_distance < best_distance || (square_distance == best_distance && mask.count_ones() < best_count)) {
+			let mut weights = [0.0; 4];
+			for i in 0..len {
+				weights[indices[i]] = subset_weights[i];
+			}
+			best = Some((square_distance, mask.count_ones(), weights, point));
+		}
+	}
+
+	// Single points always project onto themselves
+	let (_, _, weights, point) = best.unwrap();
+	(weights, point)
+}
+
+// The projection of the origin onto the affine hull of up to four points, and its affine weights.
+// Returns `None` if the points are affinely dependent.
+fn project_origin<V: SupportVector>(points: &[V]) -> Option<([f32; 4], V)> {
+	let origin = points[0];
+	let size = points.len() - 1;
+
+	let mut edges = [V::default(); 3];
+	for i in 0..size {
+		edges[i] = points[i + 1] - origin;
+	}
+
+	// Solve the normal equations for the position within the affine hull closest to the origin
+	let mut matrix = [[0.0f64; 4]; 3];
+	for i in 0..size {
+		for j in 0..size {
+			matrix[i][j] = edges[i].dot(edges[j]) as f64;
+		}
+		matrix[i][3] = -edges[i].dot(origin) as f64;
+	}
+
+	let scale = (0..size).map(|i| matrix[i][i]).fold(0.0, f64::max);
+	for column in 0..size {
+		let pivot = (column..size).max_by(|&i, &j| matrix[i][column].abs().total_cmp(&matrix[j][column].abs()))?;
+		if matrix[pivot][column].abs() <= DEGENERATE_TOLERANCE as f64 * scale {
+			return None;
+		}
+
+		matrix.swap(column, pivot);
+		let pivot_row = matrix[column];
+		for (row, values) in matrix[..size].iter_mut().enumerate() {
+			if row != column {
+				let factor = values[column] / pivot_row[column];
+				for (value, pivot_value) in values.iter_mut().zip(pivot_row).skip(column) {
+					*value -= factor * pivot_value;
+				}
+			}
+		}
+	}
+
+	let mut weights = [0.0; 4];
+	let mut point = origin;
+	for i in 0..size {
+		let weight = (matrix[i][3] / matrix[i][i]) as f32;
+		weights[i + 1] = weight;
+		point += edges[i] * weight;
+	}
+	weights[0] = 1.0 - weights[1..].iter().sum::<f32>();
+
+	Some((weights, point))
+}
+
+
+// An orthonormal basis built up one direction at a time.
+#[derive(Copy, Clone, Debug, Default)]
+struct Basis<V> {
+	directions: [V; 3],
+	len: usize,
+}
+
+impl<V: SupportVector> Basis<V> {
+	// The part of `vector` that is orthogonal to the basis.
+	fn reject(&self, mut vector: V) -> V {
+		for &direction in self.directions[..self.len].iter() {
+			vector -= direction * vector.dot(direction);
+		}
+		vector
+	}
+
+	// Adds the direction of `vector` if it's independent of the basis.
+	fn push(&mut self, vector: V) -> bool {
+		let rejected = self.reject(vector);
+		let square_length = rejected.square_length();
+		if self.len >= 3 || square_length <= DEGENERATE_TOLERANCE * vector.square_length() || square_length == 0.0 {
+			return false;
+		}
+
+		self.directions[self.len] = rejected / square_length.sqrt();
+		self.len += 1;
+		true
+	}
+
+	// A unit vector orthogonal to the basis, which must not span every axis.
+	fn orthogonal(&self) -> V {
+		V::AXES.iter()
+			.map(|&axis| self.reject(axis))
+			.reduce(|best, next| if next.square_length() > best.square_length() { next } else { best })
+			.unwrap()
+			.normalize()
+	}
+}
+
+
+#[derive(Copy, Clone, Debug)]
+struct Face<V> {
+	vertices: [usize; 3],
+	normal: V,
+	distance: f32,
+}
+
+// The expanding polytope, with faces of `dimension` vertices each.
+struct Polytope<V> {
+	vertices: Vec<Vertex<V>>,
+	faces: Vec<Face<V>>,
+	interior: V,
+}
+
+impl<V: SupportVector> Polytope<V> {
+	fn new(simplex: &[Vertex<V>]) -> Polytope<V> {
+		let mut polytope = Polytope {
+			vertices: simplex.to_vec(),
+			faces: Vec::new(),
+			interior: simplex.iter().map(|vertex| vertex.point).sum::<V>() / simplex.len() as f32,
+		};
+
+		// Each face leaves out one vertex of the simplex
+		for skipped in 0..simplex.len() {
+			let mut vertices = [0; 3];
+			for (slot, index) in (0..simplex.len()).filter(|&index| index != skipped).enumerate() {
+				vertices[slot] = index;
+			}
+			polytope.add_face(vertices);
+		}
+
+		polytope
+	}
+
+	fn dimension(&self) -> usize {
+		V::AXES.len()
+	}
+
+	// Adds a face with its normal facing away from the interior, returning false if it's degenerate.
+	fn add_face(&mut self, vertices: [usize; 3]) -> bool {
+		let dimension = self.dimension();
+		let origin = self.vertices[vertices[0]].point;
+
+		let mut basis = Basis::default();
+		for &index in vertices[1..dimension].iter() {
+			if !basis.push(self.vertices[index].point - origin) {
+				return false;
+			}
+		}
+
+		let mut normal = basis.orthogonal();
+		if normal.dot(origin - self.interior) < 0.0 {
+			normal = -normal;
+		}
+
+		self.faces.push(Face { vertices, normal, distance: normal.dot(origin) });
+		true
+	}
+
+	// Grows the polytope towards the boundary of the Minkowski difference until the face closest to the origin is on it,
+	// returning its normal and distance along with the points on each shape behind the origin's projection onto it.
+	fn expand<A, B>(mut self, a: &A, b: &B) -> (V, f32, (V, V))
+		where A: SupportMap<Vector=V> + ?Sized, B: SupportMap<Vector=V> + ?Sized
+	{
+		let dimension = self.dimension();
+		let mut closest = self.closest_face();
+
+		for _ in 0..EPA_ITERATIONS {
+			let face = self.faces[closest];
+			let vertex = support_vertex(a, b, face.normal);
+			let scale = vertex.point.length().max(1.0);
+			if vertex.point.dot(face.normal) - face.distance <= EPA_TOLERANCE * scale {
+				break
+			}
+
+			// Remove every face the new point can see, keeping the ridges around the hole they leave
+			let mut horizon: Vec<([usize; 2], u32)> = Vec::new();
+			let mut index = 0;
+			while index < self.faces.len() {
+				let visible = &self.faces[index];
+				let origin = self.vertices[visible.vertices[0]].point;
+				if visible.normal.dot(vertex.point - origin) <= 0.0 {
+					index += 1;
+					continue
+				}
+
+				for skipped in 0..dimension {
+					let mut ridge = [usize::MAX; 2];
+					let others = (0..dimension).filter(|&slot| slot != skipped);
+					for (slot, other) in others.enumerate() {
+						ridge[slot] = visible.vertices[other];
+					}
+					ridge.sort();
+
+					match horizon.iter_mut().find(|(other, _)| *other == ridge) {
+						Some((_, count)) => *count += 1,
+						None => horizon.push((ridge, 1)),
+					}
+				}
+
+				self.faces.swap_remove(index);
+			}
+
+			let new_index = self.vertices.len();
+			self.vertices.push(vertex);
+
+			let mut degenerate = false;
+			for &(ridge, _) in horizon.iter().filter(|(_, count)| *count == 1) {
+				let mut vertices = [new_index; 3];
+				vertices[..dimension - 1].copy_from_slice(&ridge[..dimension - 1]);
+				degenerate |= !self.add_face(vertices);
+			}
+
+			if degenerate || self.faces.is_empty() {
+				// The polytope can no longer be closed, so settle for the best face found before
+				self.faces.push(face);
+				closest = self.faces.len() - 1;
+				break
+			}
+
+			closest = self.closest_face();
+		}
+
+		let face = self.faces[closest];
+
+		// Project the origin onto the face to find where on each shape the contact is
+		let projection = face.normal * face.distance;
+		let mut points = [V::default(); 3];
+		for (slot, &index) in face.vertices[..dimension].iter().enumerate() {
+			points[slot] = self.vertices[index].point - projection;
+		}
+
+		let weights = project_origin(&points[..dimension]).map_or([1.0, 0.0, 0.0, 0.0], |(weights, _)| weights);
+		let points = face.vertices[..dimension].iter()
+			.zip(weights)
+			.map(|(&index, weight)| (self.vertices[index].a * weight, self.vertices[index].b * weight))
+			.fold((V::default(), V::default()), |(a, b), (next_a, next_b)| (a + next_a, b + next_b));
+
+		(face.normal, face.distance.max(0.0), points)
+	}
+
+	fn closest_face(&self) -> usize {
+		(0..self.faces.len())
+			.min_by(|&i, &j| self.faces[i].distance.total_cmp(&self.faces[j].distance))
+			.unwrap()
+	}
+}
+
+fn support_vertex<A, B>(a: &A, b: &B, direction: A::Vector) -> Vertex<A::Vector>
+	where A: SupportMap + ?Sized, B: SupportMap<Vector=A::Vector> + ?Sized
+{
+	let (a, b) = (a.core_support(direction), b.core_support(-direction));
+	Vertex { point: a - b, a, b }
+}
+
+fn furthest<V: FloatVector>(points: impl IntoIterator<Item=V>, direction: V) -> V {
+	points.into_iter()
+		.map(|point| (point.dot(direction), point))
+		.reduce(|best, next| if next.0 > best.0 { next } else { best })
+		.map(|(_, point)| point)
+		.unwrap_or_default()
+}
+
+fn normalize_or_zero<V: FloatVector>(direction: V) -> V {
+	let length = direction.length();
+	if length > 0.0 { direction / length } else { V::default() }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+	use rand::{Rng, SeedableRng};
+	use rand::rngs::StdRng;
+
+	fn random_vec2(rng: &mut StdRng, range: f32) -> Vec2 {
+		Vec2::new(rng.random_range(-range..range), rng.random_range(-range..range))
+	}
+
+	fn random_vec3(rng: &mut StdRng, range: f32) -> Vec3 {
+		Vec3::new(rng.random_range(-range..range), rng.random_range(-range..range), rng.random_range(-range..range))
+	}
+
+	fn random_obb3(rng: &mut StdRng) -> Obb3 {
+		let orientation = Quat::from_axis_angle(random_vec3(rng, 1.0).normalize(), rng.random_range(0.0..PI));
+		Obb3::new(random_vec3(rng, 1.5), orientation, Vec3::new(rng.random_range(0.2..1.0), rng.random_range(0.2..1.0), rng.random_range(0.2..1.0)))
+	}
+
+	#[test]
+	fn test_distance() {
+		let separation = gjk_distance(&Circle::new(Vec2::zero(), 1.0), &Circle::new(Vec2::new(3.0, 4.0), 2.0)).unwrap();
+		assert_almost_eq!(separation.distance, 2.0);
+		assert_vec_eq!(separation.point_a, Vec2::new(0.6, 0.8));
+		assert_vec_eq!(separation.point_b, Vec2::new(1.8, 2.4));
+		assert_vec_eq!(separation.normal, Vec2::new(0.6, 0.8));
+
+		let a = Capsule3::new(Vec3::zero(), Vec3::from_x(4.0), 0.5);
+		let b = Capsule3::new(Vec3::new(1.0, 2.0, 0.0), Vec3::new(3.0, 2.0, 0.0), 0.25);
+		assert_almost_eq!(gjk_distance(&a, &b).unwrap().distance, 1.25);
+		assert!(gjk_distance(&a, &Sphere::new(Vec3::new(2.0, 0.5, 0.0), 0.1)).is_none());
+
+		let triangle = Triangle2::new(Vec2::zero(), Vec2::from_x(2.0), Vec2::from_y(2.0));
+		let separation = gjk_distance(&triangle, &Vec2::splat(2.0)).unwrap();
+		assert_vec_eq!(separation.point_a, Vec2::one());
+
+		// Points against boxes, checked against the box's own closest point
+		let mut rng = StdRng::seed_from_u64(7);
+		let aabb = Aabb3::new(Vec3::new(-1.0, -0.5, 0.0), Vec3::new(1.0, 0.5, 2.0));
+		for _ in 0..100 {
+			let point = random_vec3(&mut rng, 4.0);
+			let closest = aabb.closest_point(point);
+
+			match gjk_distance(&aabb, &point) {
+				Some(separation) => {
+					assert_vec_eq!(separation.point_a, closest);
+					assert_almost_eq!(separation.distance, (point - closest).length());
+				}
+				None => assert!(aabb.contains_point(point)),
+			}
+		}
+	}
+
+	#[test]
+	fn test_support_maps() {
+		let mut rng = StdRng::seed_from_u64(8);
+
+		let obb = random_obb3(&mut rng);
+		let transformed = Transformed::new(Aabb3::from_center_extents(Vec3::zero(), obb.extents), Mat3x4::scale_rotate_translate(1.0, obb.orientation, obb.center));
+
+		let obb2 = Obb2::from_angle(Vec2::new(1.0, 2.0), 0.7, Vec2::new(0.5, 1.5));
+		let transformed2 = Transformed::new(Aabb2::from_center_extents(Vec2::zero(), obb2.extents), Mat2x3::rotate_translate(0.7, obb2.center));
+
+		let capsule = Capsule3::new(Vec3::from_y(1.0), Vec3::new(2.0, 0.0, 1.0), 0.5);
+		let sum = MinkowskiSum::new(capsule.segment(), Sphere::new(Vec3::zero(), 0.5));
+
+		let points: Vec<Vec3> = (0..50).map(|_| random_vec3(&mut rng, 1.0)).collect();
+		let hull = convex_hull_3d(&points).unwrap();
+
+		for _ in 0..50 {
+			let direction = random_vec3(&mut rng, 1.0);
+			assert_vec_eq!(transformed.support(direction), obb.support(direction));
+			assert_vec_eq!(sum.support(direction), capsule.support(direction));
+			assert_vec_eq!(hull.with_points(&points).support(direction), points.support(direction));
+
+			let direction = random_vec2(&mut rng, 1.0);
+			assert_vec_eq!(transformed2.support(direction), obb2.support(direction));
+		}
+	}
+
+	// Polytopes have an exact penetration depth, which separating axes also find
+	#[test]
+	fn test_epa_polytopes() {
+		let mut rng = StdRng::seed_from_u64(9);
+
+		for _ in 0..200 {
+			let (a, b) = (random_obb3(&mut rng), random_obb3(&mut rng));
+			let triangle = Triangle3::new(random_vec3(&mut rng, 1.5), random_vec3(&mut rng, 1.5), random_vec3(&mut rng, 1.5));
+
+			for (expected, contact) in [
+				(contact(&a, &b), epa_contact(&a, &b)),
+				(contact(&a, &triangle), epa_contact(&a, &triangle)),
+			] {
+				assert_eq!(expected.is_some(), contact.is_some());
+				if let (Some(expected), Some(contact)) = (expected, contact) {
+					assert_almost_eq!(contact.depth, expected.depth);
+					assert_almost_eq!(contact.normal.length(), 1.0);
+				}
+			}
+
+			let a = Obb2::from_angle(random_vec2(&mut rng, 1.5), rng.random_range(0.0..PI), Vec2::new(rng.random_range(0.2..1.0), rng.random_range(0.2..1.0)));
+			let b = Triangle2::new(random_vec2(&mut rng, 1.5), random_vec2(&mut rng, 1.5), random_vec2(&mut rng, 1.5));
+			let expected = contact(&a, &b);
+			let contact = epa_contact(&a, &b);
+			assert_eq!(expected.is_some(), contact.is_some());
+			if let (Some(expected), Some(contact)) = (expected, contact) {
+				assert_almost_eq!(contact.depth, expected.depth);
+			}
+		}
+	}
+
+	#[test]
+	fn test_epa_rounded() {
+		let contact = epa_contact(&Sphere::new(Vec3::zero(), 1.0), &Sphere::new(Vec3::new(1.0, 1.0, 0.5), 1.0)).unwrap();
+		assert_almost_eq!(contact.depth, 0.5);
+		assert_vec_eq!(contact.normal, Vec3::new(2.0, 2.0, 1.0) / 3.0);
+
+		let contact = epa_contact(&Circle::new(Vec2::zero(), 1.0), &Capsule2::new(Vec2::new(-2.0, 1.25), Vec2::new(2.0, 1.25), 0.5)).unwrap();
+		assert_almost_eq!(contact.depth, 0.25);
+		assert_vec_eq!(contact.normal, Vec2::from_y(1.0));
+		assert_vec_eq!(contact.point, Vec2::from_y(0.875));
+
+		// Capsules whose cores cross, far from the origin so their closest points are lost to rounding
+		let a = Capsule3::new(Vec3::new(999.9972, 1000.01996, 1000.0111), Vec3::new(999.9932, 1000.00354, 999.99347), 0.00467);
+		let b = Capsule3::new(Vec3::new(1000.01794, 999.98114, 1000.0189), Vec3::new(999.9912, 1000.00854, 999.99347), 3.96e-5);
+		let contact = epa_contact(&a, &b).unwrap();
+		assert_almost_eq!(contact.depth, 0.0047);
+		assert_almost_eq!(contact.normal.length(), 1.0);
+		assert!(contact.point.distance(Vec3::splat(1000.0)) < 0.1);
+
+		// Coplanar triangles have no volume to push apart
+		let a = Triangle3::new(Vec3::zero(), Vec3::from_x(1.0), Vec3::from_y(1.0));
+		let b = Triangle3::new(Vec3::new(0.25, 0.25, 0.0), Vec3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 2.0, 0.0));
+		let contact = epa_contact(&a, &b).unwrap();
+		assert_almost_eq!(contact.depth, 0.0);
+		assert_almost_eq!(contact.normal.z.abs(), 1.0);
+	}
+
+	// Moving the second shape out along the contact normal separates the pair
+	#[test]
+	fn test_separation() {
+		let mut rng = StdRng::seed_from_u64(10);
+
+		for _ in 0..200 {
+			let a = MinkowskiSum::new(random_obb3(&mut rng), Sphere::new(Vec3::zero(), 0.25));
+			let b = Capsule3::new(random_vec3(&mut rng, 1.5), random_vec3(&mut rng, 1.5), 0.5);
+
+			if let Some(contact) = epa_contact(&a, &b) {
+				let offset = contact.normal * (contact.depth + 0.001);
+				let moved = Capsule3::new(b.start + offset, b.end + offset, b.radius);
+				assert!(!gjk_intersects(&a, &moved), "{:?} {:?} {:?}", a, b, contact);
+			} else {
+				assert!(gjk_distance(&a, &b).unwrap().distance > 0.0);
+			}
+		}
+	}
+}
@@ -0,0 +1,523 @@
+use crate::math::*;
+use crate::geometry::polygon::Polygon2;
+use std::collections::HashMap;
+
+
+/// Computes the convex hull of `points` with Andrew's monotone chain algorithm, as indices into `points`.
+/// The hull is counter-clockwise and starts from the lowest, leftmost point. Collinear points along edges are excluded.
+pub fn convex_hull_2d_indices(points: &[Vec2]) -> Vec<usize> {
+	let mut order: Vec<usize> = (0..points.len()).collect();
+	order.sort_by(|&a, &b| {
+		let (a, b) = (points[a], points[b]);
+		a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+	});
+
+	order.dedup_by(|a, b| points[*a].x == points[*b].x && points[*a].y == points[*b].y);
+
+	if order.len() < 3 {
+		return order;
+	}
+
+	let turns_left = |hull: &[usize], next: usize| {
+		let [a, b] = [hull[hull.len() - 2], hull[hull.len() - 1]].map(|i| points[i]);
+		(b - a).wedge(points[next] - a) > 0.0
+	};
+
+	let mut hull: Vec<usize> = Vec::with_capacity(order.len() + 1);
+
+	// Lower hull, left to right
+	for &index in order.iter() {
+		while hull.len() >= 2 && !turns_left(&hull, index) {
+			hull.pop();
+		}
+		hull.push(index);
+	}
+
+	// Upper hull, right to left
+	let lower_len = hull.len() + 1;
+	for &index in order.iter().rev().skip(1) {
+		while hull.len() >= lower_len && !turns_left(&hull, index) {
+			hull.pop();
+		}
+		hull.push(index);
+	}
+
+	// The last point is the first again
+	hull.pop();
+
+	// Start from the lowest point rather than the leftmost
+	let lowest = (0..hull.len())
+		.min_by(|&a, &b| {
+			let (a, b) = (points[hull[a]], points[hull[b]]);
+			a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+		})
+		.unwrap();
+
+	hull.rotate_left(lowest);
+	hull
+}
+
+/// Computes the counter-clockwise convex hull of `points`. See `convex_hull_2d_indices`.
+pub fn convex_hull_2d(points: &[Vec2]) -> Polygon2 {
+	convex_hull_2d_indices(points).into_iter()
+		.map(|index| points[index])
+		.collect()
+}
+
+impl Polygon2 {
+	/// The smallest convex polygon containing this one, wound counter-clockwise.
+	pub fn convex_hull(&self) -> Polygon2 {
+		convex_hull_2d(&self.points)
+	}
+}
+
+
+/// A closed convex polyhedron computed by `convex_hull_3d`.
+#[derive(Clone, Debug)]
+pub struct ConvexHull3 {
+	/// Triangles as indices into the points the hull was built from, wound counter-clockwise when viewed from outside.
+	pub faces: Vec<[u32; 3]>,
+
+	/// The plane of each face, with normals facing outwards.
+	pub planes: Vec<Plane3>,
+}
+
+impl ConvexHull3 {
+	/// Indices of the points on the hull, in ascending order.
+	pub fn vertex_indices(&self) -> Vec<u32> {
+		let mut indices: Vec<u32> = self.faces.iter().flatten().copied().collect();
+		indices.sort();
+		indices.dedup();
+		indices
+	}
+
+	/// Whether `point` is within `tolerance` of the inside of the hull.
+	pub fn contains_point(&self, point: Vec3, tolerance: f32) -> bool {
+		self.planes.iter().all(|plane| plane.distance_to(point) <= tolerance)
+	}
+
+	/// The volume of the hull, given the points it was built from.
+	pub fn volume(&self, points: &[Vec3]) -> f32 {
+		let Some(&[origin, ..]) = self.faces.first() else {
+			return 0.0;
+		};
+
+		let origin = points[origin as usize];
+		self.faces.iter()
+			.map(|face| {
+				let [a, b, c] = face.map(|i| points[i as usize] - origin);
+				a.dot(b.cross(c)) / 6.0
+			})
+			.sum()
+	}
+}
+
+
+/// Computes the convex hull of `points` with the quickhull algorithm.
+/// Returns `None` if fewer than four points are given, or if they all lie on a plane.
+// https://www.cs.ubc.ca/~lloyd/java/quickhull3d.html
+pub fn convex_hull_3d(points: &[Vec3]) -> Option<ConvexHull3> {
+	if points.len() < 4 {
+		return None;
+	}
+
+	let bounds_size = points.iter().fold(Vec3::zero(), |size, p| Vec3::new(
+		size.x.max(p.x.abs()),
+		size.y.max(p.y.abs()),
+		size.z.max(p.z.abs()),
+	));
+
+	let epsilon = 3.0 * f32::EPSILON * (bounds_size.x + bounds_size.y + bounds_size.z).max(1.0) * 8.0;
+
+	let mut faces = initial_simplex(points, epsilon)?;
+
+	// Each point outside the hull is assigned to one face it is in front of
+	let all_indices: Vec<u32> = (0..points.len() as u32).collect();
+	assign_outside_points(points, &mut faces, 0..4, &all_indices, epsilon);
+
+	let mut pending: Vec<u32> = (0..4).collect();
+	let mut visible = Vec::new();
+	let mut horizon = Vec::new();
+	let mut orphans = Vec::new();
+
+	while let Some(face_index) = pending.pop() {
+		let face = &faces[face_index as usize];
+		if !face.alive || face.outside.is_empty() {
+			continue;
+		}
+
+		// The point furthest in front of a face is certainly on the hull
+		let centroid = face.vertices.iter().fold(Vec3::zero(), |sum, &i| sum + points[i as usize]) / 3.0;
+		let eye = furthest_point(points, face.outside.iter().copied(), |p| face.plane.distance_to(p), centroid, epsilon);
+		let eye_position = points[eye as usize];
+
+		// The faces the eye can see form a connected patch around the face, found by flooding out from it.
+		// The horizon is made of the edges between the patch and the rest of the hull.
+		visible.clear();
+		horizon.clear();
+		visible.push(face_index);
+		faces[face_index as usize].alive = false;
+
+		let mut next = 0;
+		while next < visible.len() {
+			let index = visible[next];
+			next += 1;
+
+			for edge in 0..3 {
+				let neighbor = faces[index as usize].neighbors[edge];
+				let other = &faces[neighbor as usize];
+
+				// Removed faces aren't linked to, so a face that isn't alive is already visible
+				if !other.alive {
+					continue;
+				}
+
+				// Faces nearly level with the eye are also replaced if keeping them would fold the hull inwards
+				let distance = other.plane.distance_to(eye_position);
+				let replace = distance > epsilon || distance > -epsilon && {
+					let [a, b] = [faces[index as usize].vertices[edge], faces[index as usize].vertices[(edge + 1) % 3]];
+					folds(points, [a, b, eye], other, epsilon)
+				};
+
+				if replace {
+					faces[neighbor as usize].alive = false;
+					visible.push(neighbor);
+				} else {
+					horizon.push((index, edge));
+				}
+			}
+		}
+
+		// A face kept when reached across one edge may still be replaced when reached across another
+		horizon.retain(|&(index, edge)| faces[faces[index as usize].neighbors[edge] as usize].alive);
+
+		orphans.clear();
+		for &index in &visible {
+			orphans.append(&mut faces[index as usize].outside);
+		}
+
+		// Each horizon edge makes a new face with the eye, joined to the new faces either side of it
+		let first_new_face = faces.len() as u32;
+		let mut by_start = HashMap::with_capacity(horizon.len());
+		for (offset, &(index, edge)) in horizon.iter().enumerate() {
+			let [a, b] = [faces[index as usize].vertices[edge], faces[index as usize].vertices[(edge + 1) % 3]];
+			let neighbor = faces[index as usize].neighbors[edge];
+			let new_face = first_new_face + offset as u32;
+
+			let mut face = HullFace::new(points, [a, b, eye]);
+			face.neighbors[0] = neighbor;
+			let back = faces[neighbor as usize].neighbors.iter().position(|&other| other == index).unwrap();
+			faces[neighbor as usize].neighbors[back] = new_face;
+
+			faces.push(face);
+			by_start.insert(a, new_face);
+		}
+
+		for new_face in first_new_face..faces.len() as u32 {
+			let after = by_start[&faces[new_face as usize].vertices[1]];
+			faces[new_face as usize].neighbors[1] = after;
+			faces[after as usize].neighbors[2] = new_face;
+		}
+
+		orphans.retain(|&index| index != eye);
+		let new_faces = first_new_face as usize..faces.len();
+		assign_outside_points(points, &mut faces, new_faces.clone(), &orphans, epsilon);
+		pending.extend(new_faces.map(|index| index as u32));
+	}
+
+	let (faces, planes) = faces.into_iter()
+		.filter(|face| face.alive)
+		.map(|face| (face.vertices, face.plane))
+		.unzip();
+
+	Some(ConvexHull3 { faces, planes })
+}
+
+
+struct HullFace {
+	vertices: [u32; 3],
+	plane: Plane3,
+
+	// The face across each edge, starting with the one from the first vertex to the second
+	neighbors: [u32; 3],
+	outside: Vec<u32>,
+	alive: bool,
+}
+
+impl HullFace {
+	fn new(points: &[Vec3], vertices: [u32; 3]) -> HullFace {
+		let [a, b, c] = vertices.map(|i| points[i as usize]);
+
+		HullFace {
+			vertices,
+			plane: face_plane(a, b, c),
+			neighbors: [u32::MAX; 3],
+			outside: Vec::new(),
+			alive: true,
+		}
+	}
+}
+
+// The plane through a triangle, with the normal found in f64 as many hull faces are slivers,
+// for which rounding would tilt the plane enough to make the hull concave.
+fn face_plane(a: Vec3, b: Vec3, c: Vec3) -> Plane3 {
+	let [ab, ac] = [b, c].map(|p| [p.x as f64 - a.x as f64, p.y as f64 - a.y as f64, p.z as f64 - a.z as f64]);
+	let normal = [ab[1] * ac[2] - ab[2] * ac[1], ab[2] * ac[0] - ab[0] * ac[2], ab[0] * ac[1] - ab[1] * ac[0]];
+	let length = normal.iter().map(|n| n * n).sum::<f64>().sqrt();
+
+	let normal = Vec3::new((normal[0] / length) as f32, (normal[1] / length) as f32, (normal[2] / length) as f32);
+	Plane3::new(normal, normal.dot(a))
+}
+
+// Whether a new face would be too thin to have a reliable plane, or would fold inwards or back over the neighbor
+// across its first edge.
+fn folds(points: &[Vec3], vertices: [u32; 3], neighbor: &HullFace, epsilon: f32) -> bool {
+	let opposite = neighbor.vertices.into_iter().find(|&vertex| vertex != vertices[0] && vertex != vertices[1]).unwrap();
+	let [a, b, c] = vertices.map(|i| points[i as usize]);
+	let normal = (b - a).cross(c - a);
+	if normal.length() <= epsilon * (b - a).length() {
+		return true;
+	}
+
+	normal.dot(neighbor.plane.normal) < 0.0 || face_plane(a, b, c).distance_to(points[opposite as usize]) > 0.0
+}
+
+// Finds a tetrahedron from extreme points, wound so that its faces point outwards.
+fn initial_simplex(points: &[Vec3], epsilon: f32) -> Option<Vec<HullFace>> {
+	let position = |index: usize| points[index];
+	let centroid = points.iter().fold(Vec3::zero(), |sum, &p| sum + p) / points.len() as f32;
+	let furthest = |distance: &dyn Fn(Vec3) -> f32| {
+		furthest_point(points, 0..points.len() as u32, distance, centroid, epsilon) as usize
+	};
+
+	// The most distant pair of extreme points along the axes
+	let mut extremes = Vec::with_capacity(6);
+	for axis in [Vec3::from_x(1.0), Vec3::from_y(1.0), Vec3::from_z(1.0)] {
+		extremes.push(furthest(&|p| p.dot(axis)));
+		extremes.push(furthest(&|p| -p.dot(axis)));
+	}
+
+	let (a, b) = extremes.chunks(2)
+		.map(|pair| (pair[0], pair[1]))
+		.max_by(|&(a0, b0), &(a1, b1)| {
+			(position(a0) - position(b0)).square_length().total_cmp(&(position(a1) - position(b1)).square_length())
+		})
+		.unwrap();
+
+	let axis = position(b) - position(a);
+	if axis.length() <= epsilon {
+		return None;
+	}
+
+	let c = furthest(&|p| axis.cross(p - position(a)).length());
+	if axis.cross(position(c) - position(a)).length() / axis.length() <= epsilon {
+		return None;
+	}
+
+	let base = Plane3::from_points(position(a), position(b), position(c));
+	let d = furthest(&|p| base.distance_to(p).abs());
+	if base.distance_to(position(d)).abs() <= epsilon {
+		return None;
+	}
+
+	let [a, b, c, d] = [a, b, c, d].map(|i| i as u32);
+
+	// Wind the base away from the apex
+	let (b, c) = if base.distance_to(position(d as usize)) > 0.0 { (c, b) } else { (b, c) };
+
+	let mut faces = vec![
+		HullFace::new(points, [a, b, c]),
+		HullFace::new(points, [a, d, b]),
+		HullFace::new(points, [b, d, c]),
+		HullFace::new(points, [c, d, a]),
+	];
+
+	// Each edge of a face is shared, in the opposite direction, with exactly one other face
+	for face in 0..4 {
+		for edge in 0..3 {
+			let [start, end] = [faces[face].vertices[edge], faces[face].vertices[(edge + 1) % 3]];
+			faces[face].neighbors[edge] = (0..4)
+				.find(|&other| (0..3).any(|i| faces[other].vertices[i] == end && faces[other].vertices[(i + 1) % 3] == start))
+				.unwrap() as u32;
+		}
+	}
+
+	Some(faces)
+}
+
+// Finds the index with the greatest `distance`.
+// Among points equally far, the one furthest from `origin` is a corner rather than on an edge or face of the hull.
+fn furthest_point(points: &[Vec3], indices: impl Iterator<Item = u32>, distance: impl Fn(Vec3) -> f32, origin: Vec3, epsilon: f32) -> u32 {
+	indices
+		.map(|index| (index, distance(points[index as usize])))
+		.max_by(|&(a, distance_a), &(b, distance_b)| {
+			if (distance_a - distance_b).abs() > epsilon {
+				distance_a.total_cmp(&distance_b)
+			} else {
+				let (a, b) = (points[a as usize], points[b as usize]);
+				(a - origin).square_length().total_cmp(&(b - origin).square_length())
+			}
+		})
+		.unwrap()
+		.0
+}
+
+fn assign_outside_points(points: &[Vec3], faces: &mut [HullFace], candidates: std::ops::Range<usize>, indices: &[u32], epsilon: f32) {
+	for &index in indices {
+		let point = points[index as usize];
+
+		let owner = candidates.clone()
+			.find(|&face| faces[face].plane.distance_to(point) > epsilon);
+
+		if let Some(face) = owner {
+			faces[face].outside.push(index);
+		}
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+	use rand::{Rng, SeedableRng};
+	use rand::rngs::StdRng;
+
+	#[test]
+	fn test_hull_2d() {
+		let points = [
+			Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(2.0, 0.0),
+			Vec2::new(0.5, 0.5), Vec2::new(2.0, 2.0), Vec2::new(1.0, 1.0),
+			Vec2::new(0.0, 2.0), Vec2::new(0.0, 2.0), Vec2::new(1.0, 2.0),
+		];
+
+		assert_eq!(convex_hull_2d_indices(&points), [0, 2, 4, 6]);
+
+		let hull = convex_hull_2d(&points);
+		assert!(hull.is_convex());
+		assert_eq!(hull.winding(), Winding::CounterClockwise);
+
+		// Degenerate inputs
+		assert_eq!(convex_hull_2d_indices(&[Vec2::one(), Vec2::one()]).len(), 1);
+		assert_eq!(convex_hull_2d_indices(&[Vec2::zero(), Vec2::one(), Vec2::splat(2.0)]).len(), 2);
+		assert!(convex_hull_2d(&[]).is_empty());
+	}
+
+	#[test]
+	fn test_hull_2d_random() {
+		let mut rng = StdRng::seed_from_u64(1);
+		let points: Vec<Vec2> = (0..500).map(|_| Vec2::new(rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0))).collect();
+		let hull = convex_hull_2d(&points);
+
+		assert!(hull.is_convex());
+		for &point in points.iter() {
+			assert!(hull.edges().all(|(a, b)| (b - a).wedge(point - a) >= -1.0e-5));
+		}
+	}
+
+	#[test]
+	fn test_hull_3d_cube() {
+		let mut points: Vec<Vec3> = (0..8)
+			.map(|i| Vec3::new((i & 1) as f32, ((i >> 1) & 1) as f32, ((i >> 2) & 1) as f32))
+			.collect();
+
+		// Interior and face points are excluded
+		points.extend([Vec3::splat(0.5), Vec3::new(0.5, 0.5, 1.0), Vec3::new(0.25, 0.75, 0.5)]);
+
+		let hull = convex_hull_3d(&points).unwrap();
+		assert_eq!(hull.faces.len(), 12);
+		assert_eq!(hull.vertex_indices(), (0..8).collect::<Vec<u32>>());
+		assert_almost_eq!(hull.volume(&points), 1.0);
+
+		for (face, plane) in hull.faces.iter().zip(hull.planes.iter()) {
+			// Normals face away from the center
+			assert!(plane.distance_to(Vec3::splat(0.5)) < 0.0);
+
+			// And agree with the face winding
+			let [a, b, c] = face.map(|i| points[i as usize]);
+			assert!((b - a).cross(c - a).dot(plane.normal) > 0.0);
+		}
+
+		assert!(hull.contains_point(Vec3::splat(0.9), 0.0));
+		assert!(!hull.contains_point(Vec3::splat(1.1), 0.0));
+	}
+
+	#[test]
+	fn test_hull_3d_random() {
+		let mut rng = StdRng::seed_from_u64(2);
+
+		for _ in 0..20 {
+			let points: Vec<Vec3> = (0..200)
+				.map(|_| Vec3::new(rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0)))
+				.collect();
+
+			let hull = convex_hull_3d(&points).unwrap();
+
+			for &point in points.iter() {
+				assert!(hull.contains_point(point, 1.0e-4));
+			}
+
+			// A closed triangle mesh satisfies Euler's formula, and every edge has a twin
+			let vertex_count = hull.vertex_indices().len();
+			assert_eq!(vertex_count + hull.faces.len() - hull.faces.len() * 3 / 2, 2);
+
+			let edges: std::collections::HashSet<(u32, u32)> = hull.faces.iter()
+				.flat_map(|&[a, b, c]| [(a, b), (b, c), (c, a)])
+				.collect();
+
+			assert!(edges.iter().all(|&(a, b)| edges.contains(&(b, a))));
+		}
+
+		// Points on a sphere are all on the hull
+		let sphere: Vec<Vec3> = (0..100)
+			.map(|_| Vec3::new(rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0)).normalize())
+			.collect();
+
+		assert_eq!(convex_hull_3d(&sphere).unwrap().vertex_indices().len(), 100);
+	}
+
+	#[test]
+	fn test_hull_3d_large() {
+		let check = |points: &[Vec3]| {
+			let hull = convex_hull_3d(points).unwrap();
+			assert!(points.iter().all(|&point| hull.contains_point(point, 1.0e-4)));
+
+			let edges: std::collections::HashSet<(u32, u32)> = hull.faces.iter()
+				.flat_map(|&[a, b, c]| [(a, b), (b, c), (c, a)])
+				.collect();
+
+			assert_eq!(edges.len(), hull.faces.len() * 3);
+			assert!(edges.iter().all(|&(a, b)| edges.contains(&(b, a))));
+			assert_eq!(hull.vertex_indices().len() + hull.faces.len() - edges.len() / 2, 2);
+		};
+
+		let mut rng = StdRng::seed_from_u64(6);
+		for _ in 0..4 {
+			let sphere: Vec<Vec3> = (0..5000)
+				.map(|_| Vec3::new(rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0)).normalize())
+				.collect();
+
+			check(&sphere);
+		}
+
+		// Many points of a grid are coplanar with the faces around them, or on their edges
+		for _ in 0..10 {
+			let grid: Vec<Vec3> = (0..200)
+				.map(|_| Vec3::new(rng.random_range(-3..=3) as f32, rng.random_range(-3..=3) as f32, rng.random_range(-3..=3) as f32))
+				.collect();
+
+			check(&grid);
+		}
+	}
+
+	#[test]
+	fn test_hull_3d_degenerate() {
+		assert!(convex_hull_3d(&[Vec3::zero(), Vec3::one(), Vec3::from_x(1.0)]).is_none());
+
+		let coplanar: Vec<Vec3> = (0..10).map(|i| Vec3::new(i as f32, (i * i) as f32, 0.0)).collect();
+		assert!(convex_hull_3d(&coplanar).is_none());
+
+		let collinear: Vec<Vec3> = (0..10).map(|i| Vec3::splat(i as f32)).collect();
+		assert!(convex_hull_3d(&collinear).is_none());
+	}
+}
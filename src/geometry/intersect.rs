@@ -0,0 +1,675 @@
+use crate::math::*;
+use crate::geometry::shapes::*;
+
+
+/// How two overlapping shapes touch.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Contact<V> {
+	/// The unit direction from the first shape towards the second.
+	/// Moving the second shape by `normal * depth` separates them.
+	pub normal: V,
+
+	/// How far the shapes overlap along `normal`.
+	pub depth: f32,
+
+	/// A representative point in the overlapping region.
+	pub point: V,
+}
+
+pub type Contact2 = Contact<Vec2>;
+pub type Contact3 = Contact<Vec3>;
+
+impl<V: FloatVector> Contact<V> {
+	/// The same contact as seen from the other shape.
+	pub fn flip(self) -> Contact<V> {
+		Contact { normal: -self.normal, ..self }
+	}
+}
+
+
+/// Overlap tests between pairs of shapes.
+///
+/// Every pair of 2D shapes and every pair of 3D shapes is supported, as well as `Shape2` and `Shape3` for when
+/// the types of the shapes are only known at runtime. Shapes are treated as solid, and planes as the half-space behind them.
+pub trait Intersect<Rhs: ?Sized = Self> {
+	type Vector;
+
+	fn contact(&self, other: &Rhs) -> Option<Contact<Self::Vector>>;
+
+	fn intersects(&self, other: &Rhs) -> bool {
+		self.contact(other).is_some()
+	}
+}
+
+pub fn intersects<A, B>(a: &A, b: &B) -> bool
+	where A: Intersect<B>
+{
+	a.intersects(b)
+}
+
+pub fn contact<A, B>(a: &A, b: &B) -> Option<Contact<A::Vector>>
+	where A: Intersect<B>
+{
+	a.contact(b)
+}
+
+
+/// Any 2D shape.
+#[derive(Copy, Clone, Debug)]
+pub enum Shape2 {
+	Circle(Circle),
+	Capsule(Capsule2),
+	Segment(Segment2),
+	Aabb(Aabb2),
+	Obb(Obb2),
+	Triangle(Triangle2),
+	Plane(Plane2),
+}
+
+/// Any 3D shape.
+#[derive(Copy, Clone, Debug)]
+pub enum Shape3 {
+	Sphere(Sphere),
+	Capsule(Capsule3),
+	Segment(Segment3),
+	Aabb(Aabb3),
+	Obb(Obb3),
+	Triangle(Triangle3),
+	Plane(Plane3),
+}
+
+// Dispatches on the type of both shapes, first on `self` and then on `other` in `contact_from`.
+macro_rules! impl_shape_dispatch {
+	($shape:ident, $vector:ident, [$($variant:ident($ty:ty)),+]) => {
+		$(
+			impl From<$ty> for $shape {
+				fn from(shape: $ty) -> $shape {
+					$shape::$variant(shape)
+				}
+			}
+		)+
+
+		impl $shape {
+			fn contact_from<A>(&self, a: &A) -> Option<Contact<$vector>>
+				where A: $(Intersect<$ty, Vector=$vector> +)+
+			{
+				match self {
+					$($shape::$variant(b) => a.contact(b),)+
+				}
+			}
+		}
+
+		impl Intersect for $shape {
+			type Vector = $vector;
+
+			fn contact(&self, other: &$shape) -> Option<Contact<$vector>> {
+				match self {
+					$($shape::$variant(a) => other.contact_from(a),)+
+				}
+			}
+		}
+	}
+}
+
+impl_shape_dispatch!(Shape2, Vec2, [
+	Circle(Circle), Capsule(Capsule2), Segment(Segment2), Aabb(Aabb2), Obb(Obb2), Triangle(Triangle2), Plane(Plane2)
+]);
+
+impl_shape_dispatch!(Shape3, Vec3, [
+	Sphere(Sphere), Capsule(Capsule3), Segment(Segment3), Aabb(Aabb3), Obb(Obb3), Triangle(Triangle3), Plane(Plane3)
+]);
+
+
+// Implements `Intersect` between every pair of the listed types, by converting both to rounded convex shapes.
+macro_rules! impl_intersect_pairs {
+	($vector:ident, $contact:ident, [$($ty:ty),+]) => {
+		impl_intersect_pairs!(@each $vector, $contact, [$($ty),+], [$($ty),+]);
+	};
+
+	(@each $vector:ident, $contact:ident, [$($a:ty),+], $all:tt) => {
+		$( impl_intersect_pairs!(@one $vector, $contact, $a, $all); )+
+	};
+
+	(@one $vector:ident, $contact:ident, $a:ty, [$($b:ty),+]) => {
+		$(
+			impl Intersect<$b> for $a {
+				type Vector = $vector;
+
+				fn contact(&self, other: &$b) -> Option<Contact<$vector>> {
+					$contact(&self.to_rounded(), &other.to_rounded())
+				}
+			}
+		)+
+	};
+}
+
+impl_intersect_pairs!(Vec2, contact_2d, [Circle, Capsule2, Segment2, Aabb2, Obb2, Triangle2, Plane2]);
+impl_intersect_pairs!(Vec3, contact_3d, [Sphere, Capsule3, Segment3, Aabb3, Obb3, Triangle3, Plane3]);
+
+
+// Every shape is represented as a convex core expanded by a radius, or as a half-space.
+#[derive(Copy, Clone)]
+enum Core<P, S, T, B> {
+	Point(P),
+	Segment(S),
+	Triangle(T),
+	Box(B),
+}
+
+type Core2 = Core<Vec2, Segment2, Triangle2, Obb2>;
+type Core3 = Core<Vec3, Segment3, Triangle3, Obb3>;
+
+#[derive(Copy, Clone)]
+enum Rounded<C, V> {
+	Convex { core: C, radius: f32 },
+	HalfSpace { normal: V, distance: f32 },
+}
+
+trait ToRounded<C, V> {
+	fn to_rounded(&self) -> Rounded<C, V>;
+}
+
+macro_rules! impl_to_rounded {
+	($ty:ty, $core:ident, $vector:ident, |$shape:ident| $body:expr) => {
+		impl ToRounded<$core, $vector> for $ty {
+			fn to_rounded(&self) -> Rounded<$core, $vector> {
+				let $shape = self;
+				$body
+			}
+		}
+	}
+}
+
+impl_to_rounded!(Circle, Core2, Vec2, |s| Rounded::Convex { core: Core::Point(s.center), radius: s.radius });
+impl_to_rounded!(Capsule2, Core2, Vec2, |s| Rounded::Convex { core: Core::Segment(s.segment()), radius: s.radius });
+impl_to_rounded!(Segment2, Core2, Vec2, |s| Rounded::Convex { core: Core::Segment(*s), radius: 0.0 });
+impl_to_rounded!(Aabb2, Core2, Vec2, |s| Rounded::Convex { core: Core::Box(Obb2::from_aabb(*s)), radius: 0.0 });
+impl_to_rounded!(Obb2, Core2, Vec2, |s| Rounded::Convex { core: Core::Box(*s), radius: 0.0 });
+impl_to_rounded!(Triangle2, Core2, Vec2, |s| Rounded::Convex { core: Core::Triangle(*s), radius: 0.0 });
+impl_to_rounded!(Plane2, Core2, Vec2, |s| Rounded::HalfSpace { normal: s.normal, distance: s.distance });
+
+impl_to_rounded!(Sphere, Core3, Vec3, |s| Rounded::Convex { core: Core::Point(s.center), radius: s.radius });
+impl_to_rounded!(Capsule3, Core3, Vec3, |s| Rounded::Convex { core: Core::Segment(s.segment()), radius: s.radius });
+impl_to_rounded!(Segment3, Core3, Vec3, |s| Rounded::Convex { core: Core::Segment(*s), radius: 0.0 });
+impl_to_rounded!(Aabb3, Core3, Vec3, |s| Rounded::Convex { core: Core::Box(Obb3::from_aabb(*s)), radius: 0.0 });
+impl_to_rounded!(Obb3, Core3, Vec3, |s| Rounded::Convex { core: Core::Box(*s), radius: 0.0 });
+impl_to_rounded!(Triangle3, Core3, Vec3, |s| Rounded::Convex { core: Core::Triangle(*s), radius: 0.0 });
+impl_to_rounded!(Plane3, Core3, Vec3, |s| Rounded::HalfSpace { normal: s.normal, distance: s.distance });
+
+
+// A small fixed capacity list, to avoid allocating in overlap tests.
+#[derive(Copy, Clone)]
+struct List<V, const N: usize> {
+	items: [V; N],
+	len: usize,
+}
+
+impl<V: Copy + Default, const N: usize> List<V, N> {
+	fn new() -> Self {
+		List { items: [V::default(); N], len: 0 }
+	}
+
+	fn push(&mut self, item: V) {
+		self.items[self.len] = item;
+		self.len += 1;
+	}
+
+	fn as_slice(&self) -> &[V] {
+		&self.items[..self.len]
+	}
+}
+
+
+impl Core2 {
+	fn vertices(&self) -> List<Vec2, 4> {
+		let mut vertices = List::new();
+		match *self {
+			Core::Point(point) => vertices.push(point),
+			Core::Segment(segment) => [segment.start, segment.end].into_iter().for_each(|v| vertices.push(v)),
+			Core::Triangle(triangle) => triangle.points().into_iter().for_each(|v| vertices.push(v)),
+			Core::Box(obb) => obb.corners().into_iter().for_each(|v| vertices.push(v)),
+		}
+		vertices
+	}
+
+	fn closest_point(&self, point: Vec2) -> Vec2 {
+		match self {
+			Core::Point(core) => *core,
+			Core::Segment(segment) => segment.closest_point(point),
+			Core::Triangle(triangle) => triangle.closest_point(point),
+			Core::Box(obb) => obb.closest_point(point),
+		}
+	}
+
+	fn push_edge_normals(&self, axes: &mut List<Vec2, AXIS_CAPACITY>) {
+		let vertices = self.vertices();
+		let vertices = vertices.as_slice();
+
+		match vertices.len() {
+			1 => {}
+			2 => axes.push((vertices[1] - vertices[0]).perp()),
+			// Opposite sides of boxes share normals
+			count => for i in 0..count.min(3) {
+				axes.push((vertices[(i + 1) % count] - vertices[i]).perp());
+			}
+		}
+	}
+}
+
+impl Core3 {
+	fn vertices(&self) -> List<Vec3, 8> {
+		let mut vertices = List::new();
+		match *self {
+			Core::Point(point) => vertices.push(point),
+			Core::Segment(segment) => [segment.start, segment.end].into_iter().for_each(|v| vertices.push(v)),
+			Core::Triangle(triangle) => triangle.points().into_iter().for_each(|v| vertices.push(v)),
+			Core::Box(obb) => obb.corners().into_iter().for_each(|v| vertices.push(v)),
+		}
+		vertices
+	}
+
+	fn closest_point(&self, point: Vec3) -> Vec3 {
+		match self {
+			Core::Point(core) => *core,
+			Core::Segment(segment) => segment.closest_point(point),
+			Core::Triangle(triangle) => triangle.closest_point(point),
+			Core::Box(obb) => obb.closest_point(point),
+		}
+	}
+
+	fn edge_directions(&self) -> List<Vec3, 3> {
+		let mut directions = List::new();
+		match *self {
+			Core::Point(_) => {}
+			Core::Segment(segment) => directions.push(segment.direction()),
+			Core::Triangle(Triangle3 { a, b, c }) => [b - a, c - b, a - c].into_iter().for_each(|v| directions.push(v)),
+			Core::Box(obb) => obb.axes().into_iter().for_each(|v| directions.push(v)),
+		}
+		directions
+	}
+
+	fn push_face_normals(&self, axes: &mut List<Vec3, AXIS_CAPACITY>) {
+		match *self {
+			Core::Point(_) | Core::Segment(_) => {}
+			Core::Box(obb) => obb.axes().into_iter().for_each(|v| axes.push(v)),
+
+			// Flat triangles can also be separated by directions within their plane
+			Core::Triangle(triangle) => {
+				let normal = (triangle.b - triangle.a).cross(triangle.c - triangle.a);
+				axes.push(normal);
+				for edge in self.edge_directions().as_slice() {
+					axes.push(normal.cross(*edge));
+				}
+			}
+		}
+	}
+}
+
+
+const AXIS_CAPACITY: usize = 48;
+
+fn contact_2d(a: &Rounded<Core2, Vec2>, b: &Rounded<Core2, Vec2>) -> Option<Contact2> {
+	let (a_core, a_radius, b_core, b_radius) = match (*a, *b) {
+		(Rounded::Convex { core: a_core, radius: a_radius }, Rounded::Convex { core: b_core, radius: b_radius }) => {
+			(a_core, a_radius, b_core, b_radius)
+		}
+
+		(Rounded::HalfSpace { normal, distance }, Rounded::Convex { core, radius }) => {
+			return half_space_contact(normal, distance, core.vertices().as_slice(), radius);
+		}
+
+		(Rounded::Convex { .. }, Rounded::HalfSpace { .. }) => return contact_2d(b, a).map(Contact::flip),
+
+		(Rounded::HalfSpace { normal: a_normal, distance: a_distance }, Rounded::HalfSpace { normal: b_normal, distance: b_distance }) => {
+			return half_spaces_contact(a_normal, a_distance, b_normal, b_distance);
+		}
+	};
+
+	let (a_vertices, b_vertices) = (a_core.vertices(), b_core.vertices());
+
+	let mut axes = List::new();
+	a_core.push_edge_normals(&mut axes);
+	b_core.push_edge_normals(&mut axes);
+
+	push_closest_directions(&mut axes, a_vertices.as_slice(), |p| b_core.closest_point(p));
+	push_closest_directions(&mut axes, b_vertices.as_slice(), |p| a_core.closest_point(p));
+
+	separating_axis_contact(a_vertices.as_slice(), a_radius, b_vertices.as_slice(), b_radius, axes.as_slice())
+}
+
+fn contact_3d(a: &Rounded<Core3, Vec3>, b: &Rounded<Core3, Vec3>) -> Option<Contact3> {
+	let (a_core, a_radius, b_core, b_radius) = match (*a, *b) {
+		(Rounded::Convex { core: a_core, radius: a_radius }, Rounded::Convex { core: b_core, radius: b_radius }) => {
+			(a_core, a_radius, b_core, b_radius)
+		}
+
+		(Rounded::HalfSpace { normal, distance }, Rounded::Convex { core, radius }) => {
+			return half_space_contact(normal, distance, core.vertices().as_slice(), radius);
+		}
+
+		(Rounded::Convex { .. }, Rounded::HalfSpace { .. }) => return contact_3d(b, a).map(Contact::flip),
+
+		(Rounded::HalfSpace { normal: a_normal, distance: a_distance }, Rounded::HalfSpace { normal: b_normal, distance: b_distance }) => {
+			return half_spaces_contact(a_normal, a_distance, b_normal, b_distance);
+		}
+	};
+
+	let (a_vertices, b_vertices) = (a_core.vertices(), b_core.vertices());
+
+	let mut axes = List::new();
+	a_core.push_face_normals(&mut axes);
+	b_core.push_face_normals(&mut axes);
+
+	for a_edge in a_core.edge_directions().as_slice() {
+		for b_edge in b_core.edge_directions().as_slice() {
+			axes.push(a_edge.cross(*b_edge));
+		}
+	}
+
+	push_closest_directions(&mut axes, a_vertices.as_slice(), |p| b_core.closest_point(p));
+	push_closest_directions(&mut axes, b_vertices.as_slice(), |p| a_core.closest_point(p));
+
+	separating_axis_contact(a_vertices.as_slice(), a_radius, b_vertices.as_slice(), b_radius, axes.as_slice())
+}
+
+// Rounded shapes, and segments that are parallel, can also be separated along the direction between a vertex and
+// the closest point on the other shape.
+fn push_closest_directions<V: FloatVector>(axes: &mut List<V, AXIS_CAPACITY>, vertices: &[V], closest_point: impl Fn(V) -> V) {
+	for &vertex in vertices {
+		axes.push(vertex - closest_point(vertex));
+	}
+}
+
+// Finds the axis along which the shapes overlap the least, or None if any axis separates them.
+fn separating_axis_contact<V: FloatVector>(a: &[V], a_radius: f32, b: &[V], b_radius: f32, axes: &[V]) -> Option<Contact<V>> {
+	let project = |vertices: &[V], radius: f32, axis: V| {
+		let (min, max) = vertices.iter()
+			.map(|v| v.dot(axis))
+			.fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), d| (min.min(d), max.max(d)));
+
+		(min - radius, max + radius)
+	};
+
+	let mut best: Option<(V, f32)> = None;
+
+	for &axis in axes {
+		let length = axis.length();
+		if length < 1.0e-6 {
+			continue
+		}
+
+		let axis = axis / length;
+		let (a_min, a_max) = project(a, a_radius, axis);
+		let (b_min, b_max) = project(b, b_radius, axis);
+
+		// Moving `b` forwards or backwards along the axis
+		let forward = a_max - b_min;
+		let backward = b_max - a_min;
+		if forward < 0.0 || backward < 0.0 {
+			return None;
+		}
+
+		let (normal, depth) = if forward <= backward { (axis, forward) } else { (-axis, backward) };
+		if best.is_none_or(|(_, best_depth)| depth < best_depth) {
+			best = Some((normal, depth));
+		}
+	}
+
+	// Shapes with no usable axes are coincident points, which have no meaningful normal
+	let (normal, depth) = best.unwrap_or((V::default(), a_radius + b_radius));
+
+	let point = (support_point(a, a_radius, normal) + support_point(b, b_radius, -normal)) / 2.0;
+	Some(Contact { normal, depth, point })
+}
+
+// The furthest point of a rounded shape in `direction`, averaged over vertices that are about as far.
+fn support_point<V: FloatVector>(vertices: &[V], radius: f32, direction: V) -> V {
+	let max = vertices.iter().map(|v| v.dot(direction)).fold(f32::NEG_INFINITY, f32::max);
+	let tolerance = 1.0e-4 * (1.0 + max.abs());
+
+	let (sum, count) = vertices.iter()
+		.filter(|v| v.dot(direction) >= max - tolerance)
+		.fold((V::default(), 0), |(sum, count), &v| (sum + v, count + 1));
+
+	sum / count as f32 + direction * radius
+}
+
+// Contact between the half-space behind a plane and a rounded shape.
+fn half_space_contact<V: FloatVector>(normal: V, distance: f32, vertices: &[V], radius: f32) -> Option<Contact<V>> {
+	let deepest = vertices.iter()
+		.map(|v| v.dot(normal) - distance)
+		.fold(f32::INFINITY, f32::min) - radius;
+
+	if deepest > 0.0 {
+		return None;
+	}
+
+	let depth = -deepest;
+	let point = support_point(vertices, radius, -normal) + normal * (depth / 2.0);
+	Some(Contact { normal, depth, point })
+}
+
+// Half-spaces always overlap without bound unless they face away from each other, which is reported with infinite depth.
+fn half_spaces_contact<V: FloatVector>(a_normal: V, a_distance: f32, b_normal: V, b_distance: f32) -> Option<Contact<V>> {
+	if a_normal.dot(b_normal) > -1.0 + 1.0e-6 {
+		return Some(Contact { normal: a_normal, depth: f32::INFINITY, point: a_normal * a_distance });
+	}
+
+	// The half-spaces overlap between their planes
+	let depth = a_distance + b_distance;
+	if depth < 0.0 {
+		return None;
+	}
+
+	Some(Contact { normal: a_normal, depth, point: a_normal * (a_distance - depth / 2.0) })
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+	use rand::{Rng, SeedableRng};
+	use rand::rngs::StdRng;
+
+	fn shapes_2d() -> Vec<Shape2> {
+		vec![
+			Circle::new(Vec2::zero(), 1.0).into(),
+			Capsule2::new(Vec2::from_y(-0.5), Vec2::from_y(0.5), 0.5).into(),
+			Segment2::new(Vec2::new(-1.0, -0.5), Vec2::new(1.0, 0.5)).into(),
+			Aabb2::new(Vec2::splat(-1.0), Vec2::one()).into(),
+			Obb2::from_angle(Vec2::zero(), 0.5, Vec2::new(1.0, 0.5)).into(),
+			Triangle2::new(Vec2::new(-1.0, -1.0), Vec2::from_x(1.0), Vec2::new(-0.5, 1.0)).into(),
+			Plane2::new(Vec2::new(1.0, 0.5), 0.0).into(),
+		]
+	}
+
+	fn shapes_3d() -> Vec<Shape3> {
+		vec![
+			Sphere::new(Vec3::zero(), 1.0).into(),
+			Capsule3::new(Vec3::from_y(-0.5), Vec3::from_y(0.5), 0.5).into(),
+			Segment3::new(Vec3::new(-1.0, -0.5, 0.2), Vec3::new(1.0, 0.5, -0.2)).into(),
+			Aabb3::new(Vec3::splat(-1.0), Vec3::one()).into(),
+			Obb3::new(Vec3::zero(), Quat::from_axis_angle(Vec3::new(1.0, 2.0, 3.0).normalize(), 0.7), Vec3::new(1.0, 0.5, 0.75)).into(),
+			Triangle3::new(Vec3::new(-1.0, -1.0, 0.0), Vec3::new(1.0, 0.0, 0.5), Vec3::new(-0.5, 1.0, -0.5)).into(),
+			Plane3::new(Vec3::new(1.0, 0.5, 0.25), 0.0).into(),
+		]
+	}
+
+	fn translate_2d(shape: Shape2, offset: Vec2) -> Shape2 {
+		let transform = Mat2x3::translate(offset);
+		match shape {
+			Shape2::Circle(s) => s.transform(&transform).into(),
+			Shape2::Capsule(s) => s.transform(&transform).into(),
+			Shape2::Segment(s) => s.transform(&transform).into(),
+			Shape2::Aabb(s) => s.translate(offset).into(),
+			Shape2::Obb(s) => s.transform(&transform).into(),
+			Shape2::Triangle(s) => s.transform(&transform).into(),
+			Shape2::Plane(s) => Plane2 { normal: s.normal, distance: s.distance + s.normal.dot(offset) }.into(),
+		}
+	}
+
+	fn translate_3d(shape: Shape3, offset: Vec3) -> Shape3 {
+		let transform = Mat3x4::translate(offset);
+		match shape {
+			Shape3::Sphere(s) => s.transform(&transform).into(),
+			Shape3::Capsule(s) => s.transform(&transform).into(),
+			Shape3::Segment(s) => s.transform(&transform).into(),
+			Shape3::Aabb(s) => s.translate(offset).into(),
+			Shape3::Obb(s) => s.transform(&transform).into(),
+			Shape3::Triangle(s) => s.transform(&transform).into(),
+			Shape3::Plane(s) => Plane3 { normal: s.normal, distance: s.distance + s.normal.dot(offset) }.into(),
+		}
+	}
+
+	// Every pair of shapes, each of which fits in a unit circle around the origin
+	#[test]
+	fn test_table_2d() {
+		let shapes = shapes_2d();
+
+		for &a in shapes.iter() {
+			for &b in shapes.iter() {
+				// Overlapping near the origin, avoiding concentric circles which have no meaningful normal
+				let b = translate_2d(b, Vec2::new(0.1, 0.05));
+				let context = format!("{:?} {:?}", a, b);
+				let is_plane = |shape: Shape2| matches!(shape, Shape2::Plane(_));
+
+				// The offset runs along the segment, so its copy only touches it
+				let contact = a.contact(&b).expect(&context);
+				if matches!((a, b), (Shape2::Segment(_), Shape2::Segment(_))) {
+					assert_almost_eq!(contact.depth, 0.0);
+				} else {
+					assert!(contact.depth > 0.0, "{}", context);
+				}
+
+				// Far apart, unless that puts one shape behind a plane
+				let far = translate_2d(b, Vec2::new(5.0, 3.0));
+				assert_eq!(a.intersects(&far), is_plane(b), "{}", context);
+
+				let behind = translate_2d(b, Vec2::new(-5.0, -3.0));
+				assert_eq!(a.intersects(&behind), is_plane(a), "{}", context);
+
+				// The contact is as deep from either side, though the normal may differ where several axes are equally good.
+				// Planes that aren't opposed overlap everywhere.
+				let reverse = b.contact(&a).unwrap();
+				if contact.depth.is_finite() {
+					assert_almost_eq!(contact.depth, reverse.depth);
+				} else {
+					assert!(reverse.depth.is_infinite());
+				}
+				assert_almost_eq!(contact.normal.length(), 1.0);
+			}
+		}
+	}
+
+	#[test]
+	fn test_table_3d() {
+		let shapes = shapes_3d();
+
+		for &a in shapes.iter() {
+			for &b in shapes.iter() {
+				let b = translate_3d(b, Vec3::new(0.1, 0.05, 0.02));
+				let context = format!("{:?} {:?}", a, b);
+				let is_plane = |shape: Shape3| matches!(shape, Shape3::Plane(_));
+
+				// Flat shapes moved out of their own plane can't overlap
+				if matches!((a, b), (Shape3::Segment(_), Shape3::Segment(_)) | (Shape3::Triangle(_), Shape3::Triangle(_))) {
+					assert!(!a.intersects(&b));
+					continue
+				}
+
+				let contact = a.contact(&b).expect(&context);
+				assert!(contact.depth > 0.0, "{}", context);
+
+				let far = translate_3d(b, Vec3::new(5.0, 3.0, 1.0));
+				assert_eq!(a.intersects(&far), is_plane(b), "{}", context);
+
+				let behind = translate_3d(b, Vec3::new(-5.0, -3.0, -1.0));
+				assert_eq!(a.intersects(&behind), is_plane(a), "{}", context);
+
+				// Planes that aren't opposed overlap everywhere
+				let reverse = b.contact(&a).unwrap();
+				if contact.depth.is_finite() {
+					assert_almost_eq!(contact.depth, reverse.depth);
+				} else {
+					assert!(reverse.depth.is_infinite());
+				}
+				assert_almost_eq!(contact.normal.length(), 1.0);
+			}
+		}
+	}
+
+	// Moving the second shape out along the contact normal separates the pair
+	#[test]
+	fn test_separation() {
+		let mut rng = StdRng::seed_from_u64(6);
+
+		for _ in 0..200 {
+			let (shapes_2d, shapes_3d) = (shapes_2d(), shapes_3d());
+
+			for &a in shapes_2d.iter() {
+				for &b in shapes_2d.iter() {
+					let b = translate_2d(b, Vec2::new(rng.random_range(-2.5..2.5), rng.random_range(-2.5..2.5)));
+					let Some(contact) = a.contact(&b) else { continue };
+					if contact.depth.is_finite() {
+						let moved = translate_2d(b, contact.normal * (contact.depth + 0.001));
+						assert!(!a.intersects(&moved), "{:?} {:?} {:?}", a, b, contact);
+					}
+				}
+			}
+
+			for &a in shapes_3d.iter() {
+				for &b in shapes_3d.iter() {
+					let b = translate_3d(b, Vec3::new(rng.random_range(-2.5..2.5), rng.random_range(-2.5..2.5), rng.random_range(-2.5..2.5)));
+					let Some(contact) = a.contact(&b) else { continue };
+					if contact.depth.is_finite() {
+						let moved = translate_3d(b, contact.normal * (contact.depth + 0.001));
+						assert!(!a.intersects(&moved), "{:?} {:?} {:?}", a, b, contact);
+					}
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_known_contacts() {
+		let circle = Circle::new(Vec2::zero(), 1.0);
+		let contact = contact(&circle, &Circle::new(Vec2::from_x(1.5), 1.0)).unwrap();
+		assert_vec_eq!(contact.normal, Vec2::from_x(1.0));
+		assert_almost_eq!(contact.depth, 0.5);
+		assert_vec_eq!(contact.point, Vec2::from_x(0.75));
+
+		// Circles near box corners are separated along the diagonal
+		let aabb = Aabb2::new(Vec2::zero(), Vec2::one());
+		assert!(!intersects(&aabb, &Circle::new(Vec2::splat(1.6), 0.8)));
+		assert!(intersects(&aabb, &Circle::new(Vec2::splat(1.5), 0.8)));
+
+		let contact = crate::contact(&aabb, &Capsule2::new(Vec2::new(0.5, 1.5), Vec2::new(0.5, 3.0), 0.75)).unwrap();
+		assert_vec_eq!(contact.normal, Vec2::from_y(1.0));
+		assert_almost_eq!(contact.depth, 0.25);
+
+		// Separated boxes only touching along an edge direction cross product
+		let a = Obb3::from_aabb(Aabb3::new(Vec3::splat(-1.0), Vec3::one()));
+		let rotation = Quat::from_axis_angle(Vec3::from_y(1.0), PI / 4.0) * Quat::from_axis_angle(Vec3::from_x(1.0), PI / 4.0);
+		let b = Obb3::new(Vec3::new(2.6, 2.0, 0.0), rotation, Vec3::one());
+		assert!(!intersects(&a, &b));
+
+		let ground = Plane3::new(Vec3::from_y(1.0), 0.0);
+		let contact = crate::contact(&ground, &Sphere::new(Vec3::from_y(0.75), 1.0)).unwrap();
+		assert_vec_eq!(contact.normal, Vec3::from_y(1.0));
+		assert_almost_eq!(contact.depth, 0.25);
+		assert_vec_eq!(contact.point, Vec3::from_y(-0.125));
+
+		// Crossing segments
+		let segment = Segment3::new(Vec3::from_x(-1.0), Vec3::from_x(1.0));
+		assert!(intersects(&segment, &Segment3::new(Vec3::new(0.5, -1.0, 0.0), Vec3::new(0.5, 1.0, 0.0))));
+		assert!(!intersects(&segment, &Segment3::new(Vec3::new(0.5, -1.0, 0.01), Vec3::new(0.5, 1.0, 0.01))));
+		assert!(intersects(&Segment2::new(Vec2::zero(), Vec2::one()), &Segment2::new(Vec2::from_x(1.0), Vec2::from_y(1.0))));
+
+		// Coplanar triangles that don't overlap
+		let a = Triangle3::new(Vec3::zero(), Vec3::from_x(1.0), Vec3::from_y(1.0));
+		let b = Triangle3::new(Vec3::splat(1.0).to_xy().extend(0.0), Vec3::new(2.0, 1.0, 0.0), Vec3::new(1.0, 2.0, 0.0));
+		assert!(!intersects(&a, &b));
+	}
+}
@@ -0,0 +1,524 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+use crate::math::*;
+use crate::geometry::bvh::BoundingBox;
+use crate::geometry::slot_map::{SlotKey, SlotMap};
+
+
+// Marks a leaf's missing children and the root's missing parent.
+const NULL: u32 = u32::MAX;
+
+// How much larger each node's loose bounds are than its cell. At 2, any item no larger than a cell
+// fits in the loose bounds of the cell containing its center.
+const LOOSENESS: f32 = 2.0;
+
+
+/// Identifies an item in a [`LooseTree`]. Handles of removed items stay invalid, even once their slot is reused.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LooseHandle(SlotKey);
+
+/// A loose quadtree or octree over items with an axis aligned box each, for sparse worlds where items come and go.
+///
+/// Each node splits its cell into `2^AXES` equal parts once it holds more than `capacity` items, down to `max_depth`.
+/// Items live in the deepest node whose loose bounds, twice the size of its cell, contain them, so
+/// items never need splitting and small movements rarely move an item between nodes.
+/// Items outside the tree's bounds are kept in the root.
+#[derive(Clone, Debug)]
+pub struct LooseTree<T, B = Aabb3> {
+	nodes: Vec<LooseNode<B>>,
+	entries: SlotMap<Entry<T, B>>,
+
+	// The first node of each block of children that has been merged back into its parent
+	free_nodes: Vec<u32>,
+	max_depth: u32,
+	capacity: usize,
+}
+
+pub type Quadtree<T> = LooseTree<T, Aabb2>;
+pub type Octree<T> = LooseTree<T, Aabb3>;
+
+// Children are stored together, starting from `children`, in the order of `BoundingBox::part`.
+#[derive(Clone, Debug)]
+struct LooseNode<B> {
+	cell: B,
+	loose: B,
+	parent: u32,
+	children: u32,
+	depth: u32,
+	entries: Vec<u32>,
+}
+
+#[derive(Clone, Debug)]
+struct Entry<T, B> {
+	bounds: B,
+	item: T,
+	node: u32,
+}
+
+// Orders by distance, for finding nearest items and nodes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Candidate {
+	square_distance: f32,
+	index: u32,
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+	fn partial_cmp(&self, other: &Candidate) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Candidate {
+	fn cmp(&self, other: &Candidate) -> Ordering {
+		self.square_distance.total_cmp(&other.square_distance).then(self.index.cmp(&other.index))
+	}
+}
+
+/// Constructors
+impl<T, B: BoundingBox> LooseTree<T, B> {
+	/// Creates a tree covering `bounds`, whose nodes split once they hold more than `capacity` items,
+	/// and which is no more than `max_depth` levels below the root.
+	pub fn new(bounds: B, max_depth: u32, capacity: usize) -> LooseTree<T, B> {
+		LooseTree {
+			nodes: vec![LooseNode {
+				cell: bounds,
+				loose: bounds.scale_about_center(LOOSENESS),
+				parent: NULL,
+				children: NULL,
+				depth: 0,
+				entries: Vec::new(),
+			}],
+			entries: SlotMap::new(),
+			free_nodes: Vec::new(),
+			max_depth,
+			capacity,
+		}
+	}
+}
+
+/// Properties
+impl<T, B: BoundingBox> LooseTree<T, B> {
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.len() == 0
+	}
+
+	/// The cell of the root node.
+	pub fn bounds(&self) -> B {
+		self.nodes[0].cell
+	}
+
+	pub fn max_depth(&self) -> u32 {
+		self.max_depth
+	}
+
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+
+	pub fn get(&self, handle: LooseHandle) -> Option<&T> {
+		self.entry(handle).map(|entry| &entry.item)
+	}
+
+	pub fn get_mut(&mut self, handle: LooseHandle) -> Option<&mut T> {
+		self.entries.get_mut(handle.0).map(|entry| &mut entry.item)
+	}
+
+	/// The box an item was last inserted or updated with.
+	pub fn item_bounds(&self, handle: LooseHandle) -> Option<B> {
+		self.entry(handle).map(|entry| entry.bounds)
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item=(LooseHandle, &T)> + '_ {
+		self.entries.iter().map(|(key, entry)| (LooseHandle(key), &entry.item))
+	}
+
+	/// The cells of the leaves holding at least one item, such as for drawing the tree.
+	pub fn leaf_bounds(&self) -> impl Iterator<Item=B> + '_ {
+		self.nodes.iter()
+			.filter(|node| node.children == NULL && !node.entries.is_empty())
+			.map(|node| node.cell)
+	}
+
+	fn entry(&self, handle: LooseHandle) -> Option<&Entry<T, B>> {
+		self.entries.get(handle.0)
+	}
+
+	fn handle(&self, entry: u32) -> LooseHandle {
+		LooseHandle(self.entries.key(entry))
+	}
+}
+
+/// Queries
+impl<T, B: BoundingBox> LooseTree<T, B> {
+	/// Visits each item whose box overlaps `region`.
+	pub fn query(&self, region: &B, mut visit: impl FnMut(LooseHandle, &T)) {
+		let mut stack = vec![0];
+		while let Some(index) = stack.pop() {
+			let node = &self.nodes[index as usize];
+
+			// The root also holds items outside of its loose bounds
+			if index != 0 && !node.loose.overlaps(region) {
+				continue;
+			}
+
+			for &index in &node.entries {
+				let entry = &self.entries[index];
+				if entry.bounds.overlaps(region) {
+					visit(self.handle(index), &entry.item);
+				}
+			}
+
+			if node.children != NULL {
+				stack.extend(node.children..node.children + (1 << B::AXES));
+			}
+		}
+	}
+
+	/// Finds up to `count` items closest to `point`, nearest first, with their distance.
+	/// Distances are measured to each item's box, so are zero for boxes containing the point.
+	pub fn nearest(&self, point: B::Vector, count: usize) -> Vec<(LooseHandle, f32)> {
+		let mut nodes = BinaryHeap::from([Reverse(Candidate { square_distance: 0.0, index: 0 })]);
+		let mut found: BinaryHeap<Candidate> = BinaryHeap::with_capacity(count + 1);
+
+		while let Some(Reverse(Candidate { square_distance, index })) = nodes.pop() {
+			// Everything in the remaining nodes is further than what has been found
+			if count == 0 || found.len() == count && found.peek().is_some_and(|worst| worst.square_distance < square_distance) {
+				break;
+			}
+
+			let node = &self.nodes[index as usize];
+			for &entry in &node.entries {
+				let square_distance = self.entries[entry].bounds.square_distance_to(point);
+				found.push(Candidate { square_distance, index: entry });
+				if found.len() > count {
+					found.pop();
+				}
+			}
+
+			if node.children != NULL {
+				for child in node.children..node.children + (1 << B::AXES) {
+					let square_distance = self.nodes[child as usize].loose.square_distance_to(point);
+					nodes.push(Reverse(Candidate { square_distance, index: child }));
+				}
+			}
+		}
+
+		found.into_sorted_vec()
+			.into_iter()
+			.map(|candidate| (self.handle(candidate.index), candidate.square_distance.sqrt()))
+			.collect()
+	}
+
+	// The child of a branch that `bounds` would fit in, if any.
+	fn fitting_child(&self, node: u32, bounds: &B) -> Option<u32> {
+		let node = &self.nodes[node as usize];
+		let index = (0..B::AXES)
+			.filter(|&axis| bounds.center_on(axis) >= node.cell.center_on(axis))
+			.fold(0, |index, axis| index | 1 << axis);
+
+		let child = node.children + index as u32;
+		self.nodes[child as usize].loose.contains(bounds).then_some(child)
+	}
+}
+
+/// Modification
+impl<T, B: BoundingBox> LooseTree<T, B> {
+	pub fn insert(&mut self, bounds: B, item: T) -> LooseHandle {
+		let key = self.entries.insert(Entry { bounds, item, node: NULL });
+		self.place(key.index());
+		LooseHandle(key)
+	}
+
+	/// Removes an item, returning it if the handle was valid.
+	pub fn remove(&mut self, handle: LooseHandle) -> Option<T> {
+		let entry = self.entries.remove(handle.0)?;
+		self.detach(handle.0.index(), entry.node);
+		Some(entry.item)
+	}
+
+	/// Changes the box of an item, returning whether it moved to a different node.
+	pub fn update(&mut self, handle: LooseHandle, bounds: B) -> bool {
+		let Some(entry) = self.entries.get_mut(handle.0) else {
+			return false;
+		};
+
+		entry.bounds = bounds;
+		let node = entry.node;
+
+		// Stay put while the item fits the node and couldn't go any deeper
+		let fits = node == 0 || self.nodes[node as usize].loose.contains(&bounds);
+		if fits && (self.nodes[node as usize].children == NULL || self.fitting_child(node, &bounds).is_none()) {
+			return false;
+		}
+
+		self.detach(handle.0.index(), node);
+		self.place(handle.0.index());
+		true
+	}
+
+	// Adds an entry to the deepest node it fits in, splitting that node if it holds too many.
+	fn place(&mut self, entry: u32) {
+		let bounds = self.entries[entry].bounds;
+		let mut node = 0;
+		while self.nodes[node as usize].children != NULL {
+			match self.fitting_child(node, &bounds) {
+				Some(child) => node = child,
+				None => break,
+			}
+		}
+
+		self.add_to_node(entry, node);
+		self.split_if_full(node);
+	}
+
+	fn add_to_node(&mut self, entry: u32, node: u32) {
+		self.nodes[node as usize].entries.push(entry);
+		self.entries[entry].node = node;
+	}
+
+	fn split_if_full(&mut self, node: u32) {
+		let LooseNode { cell, children, depth, ref entries, .. } = self.nodes[node as usize];
+		if children != NULL || entries.len() <= self.capacity || depth >= self.max_depth {
+			return;
+		}
+
+		let child_count = 1 << B::AXES;
+		let children = self.free_nodes.pop().unwrap_or(self.nodes.len() as u32);
+		for part in 0..child_count {
+			let cell = cell.part(part);
+			let child = LooseNode {
+				cell,
+				loose: cell.scale_about_center(LOOSENESS),
+				parent: node,
+				children: NULL,
+				depth: depth + 1,
+				entries: Vec::new(),
+			};
+
+			match self.nodes.get_mut(children as usize + part) {
+				Some(existing) => *existing = child,
+				None => self.nodes.push(child),
+			}
+		}
+
+		self.nodes[node as usize].children = children;
+		for entry in std::mem::take(&mut self.nodes[node as usize].entries) {
+			let bounds = self.entries[entry].bounds;
+			let target = self.fitting_child(node, &bounds).unwrap_or(node);
+			self.add_to_node(entry, target);
+		}
+
+		for child in children..children + child_count as u32 {
+			self.split_if_full(child);
+		}
+	}
+
+	// Takes an entry out of its node, merging nodes back into their parents where they hold few enough items.
+	fn detach(&mut self, entry: u32, node: u32) {
+		let entries = &mut self.nodes[node as usize].entries;
+		let position = entries.iter().position(|&other| other == entry).unwrap();
+		entries.swap_remove(position);
+
+		let mut branch = if self.nodes[node as usize].children == NULL { self.nodes[node as usize].parent } else { node };
+		while branch != NULL && self.merge(branch) {
+			branch = self.nodes[branch as usize].parent;
+		}
+	}
+
+	// Moves the items of a branch's children into it if they are all leaves and there is room, returning whether it did.
+	fn merge(&mut self, branch: u32) -> bool {
+		let children = self.nodes[branch as usize].children;
+		let children = children as usize..children as usize + (1 << B::AXES);
+		if self.nodes[children.clone()].iter().any(|child| child.children != NULL) {
+			return false;
+		}
+
+		let total = self.nodes[branch as usize].entries.len() + self.nodes[children.clone()].iter().map(|child| child.entries.len()).sum::<usize>();
+		if total > self.capacity {
+			return false;
+		}
+
+		for child in children.clone() {
+			for entry in std::mem::take(&mut self.nodes[child].entries) {
+				self.add_to_node(entry, branch);
+			}
+		}
+
+		self.nodes[branch as usize].children = NULL;
+		self.free_nodes.push(children.start as u32);
+		true
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+	use rand::{Rng, SeedableRng};
+	use rand::rngs::StdRng;
+
+	fn random_box(rng: &mut StdRng) -> Aabb3 {
+		let center = Vec3::new(rng.random_range(-50.0..50.0), rng.random_range(-50.0..50.0), rng.random_range(-50.0..50.0));
+		Aabb3::from_center_extents(center, rng.random_range(0.05..3.0))
+	}
+
+	fn brute_query<T>(tree: &LooseTree<T, Aabb3>, region: &Aabb3) -> Vec<LooseHandle> {
+		tree.iter()
+			.map(|(handle, _)| handle)
+			.filter(|&handle| tree.item_bounds(handle).unwrap().overlaps(region))
+			.collect()
+	}
+
+	fn query(tree: &Octree<usize>, region: &Aabb3) -> Vec<LooseHandle> {
+		let mut found = Vec::new();
+		tree.query(region, |handle, _| found.push(handle));
+		found.sort();
+		found
+	}
+
+	// Checks that every item is in the node it thinks it is, and fits in that node's loose bounds.
+	fn validate<T>(tree: &LooseTree<T, Aabb3>) {
+		let mut count = 0;
+		for (index, node) in tree.nodes.iter().enumerate() {
+			for &entry in &node.entries {
+				let entry = &tree.entries[entry];
+				assert_eq!(entry.node, index as u32);
+				assert!(index == 0 || node.loose.contains(&entry.bounds));
+				count += 1;
+			}
+		}
+
+		assert_eq!(count, tree.len());
+	}
+
+	#[test]
+	fn test_octree() {
+		let mut rng = StdRng::seed_from_u64(8);
+		let mut tree = Octree::new(Aabb3::from_center_extents(Vec3::zero(), 50.0), 6, 4);
+		let mut handles: Vec<LooseHandle> = (0..400).map(|index| tree.insert(random_box(&mut rng), index)).collect();
+
+		validate(&tree);
+		assert_eq!(tree.len(), 400);
+		assert!(tree.leaf_bounds().count() > 20);
+		assert!(tree.leaf_bounds().all(|cell| tree.bounds().contains(&cell)));
+
+		for _ in 0..20 {
+			let region = Aabb3::from_center_extents(random_box(&mut rng).center(), 10.0);
+			assert_eq!(query(&tree, &region), brute_query(&tree, &region));
+		}
+
+		for &handle in &handles[..200] {
+			let bounds = tree.item_bounds(handle).unwrap();
+			tree.update(handle, bounds.translate(Vec3::new(rng.random_range(-5.0..5.0), rng.random_range(-5.0..5.0), 0.0)));
+		}
+		validate(&tree);
+
+		let removed: Vec<LooseHandle> = handles.drain(100..300).collect();
+		for &handle in &removed {
+			assert!(tree.remove(handle).is_some());
+			assert!(tree.get(handle).is_none());
+		}
+		validate(&tree);
+		assert_eq!(tree.len(), 200);
+
+		// Handles of removed items don't reach the items reusing their slots
+		let added: Vec<LooseHandle> = (0..50).map(|index| tree.insert(random_box(&mut rng), 500 + index)).collect();
+		for &handle in &removed {
+			assert!(tree.get(handle).is_none());
+			assert!(!tree.update(handle, Aabb3::zero()));
+			assert!(tree.remove(handle).is_none());
+		}
+		for handle in added {
+			tree.remove(handle);
+		}
+		validate(&tree);
+
+		for _ in 0..20 {
+			let region = Aabb3::from_center_extents(random_box(&mut rng).center(), 15.0);
+			assert_eq!(query(&tree, &region), brute_query(&tree, &region));
+		}
+
+		// Items outside the tree's bounds are still found
+		let outside = tree.insert(Aabb3::from_center_extents(Vec3::splat(200.0), 1.0), 1000);
+		assert_eq!(query(&tree, &Aabb3::from_center_extents(Vec3::splat(200.0), 0.5)), vec![outside]);
+		tree.update(outside, Aabb3::from_center_extents(Vec3::splat(10.0), 1.0));
+		assert_eq!(query(&tree, &Aabb3::from_center_extents(Vec3::splat(10.0), 0.5)), vec![outside]);
+		validate(&tree);
+
+		// Removing everything merges the tree back into the root
+		let remaining: Vec<LooseHandle> = tree.iter().map(|(handle, _)| handle).collect();
+		for handle in remaining {
+			tree.remove(handle);
+		}
+		assert!(tree.is_empty());
+		assert_eq!(tree.nodes[0].children, u32::MAX);
+	}
+
+	#[test]
+	fn test_nearest() {
+		let mut rng = StdRng::seed_from_u64(9);
+		let mut tree = Octree::new(Aabb3::from_center_extents(Vec3::zero(), 50.0), 8, 2);
+		for index in 0..300 {
+			let point = Vec3::new(rng.random_range(-50.0..50.0), rng.random_range(-50.0..50.0), rng.random_range(-50.0..50.0));
+			tree.insert(Aabb3::from_point(point), index);
+		}
+
+		for _ in 0..20 {
+			let point = Vec3::new(rng.random_range(-60.0..60.0), rng.random_range(-60.0..60.0), rng.random_range(-60.0..60.0));
+			let mut expected: Vec<f32> = tree.iter()
+				.map(|(handle, _)| tree.item_bounds(handle).unwrap().center().distance(point))
+				.collect();
+			expected.sort_by(f32::total_cmp);
+
+			let nearest = tree.nearest(point, 5);
+			assert_eq!(nearest.len(), 5);
+			for ((handle, distance), expected) in nearest.into_iter().zip(expected) {
+				assert_almost_eq!(distance, expected);
+				assert_almost_eq!(tree.item_bounds(handle).unwrap().center().distance(point), expected);
+			}
+		}
+
+		assert_eq!(tree.nearest(Vec3::zero(), 1000).len(), 300);
+		assert!(tree.nearest(Vec3::zero(), 0).is_empty());
+	}
+
+	#[test]
+	fn test_quadtree() {
+		let mut tree = Quadtree::new(Aabb2::new(Vec2::zero(), Vec2::splat(16.0)), 3, 1);
+		let a = tree.insert(Aabb2::from_center_extents(Vec2::splat(1.0), 0.5), 'a');
+		let b = tree.insert(Aabb2::from_center_extents(Vec2::splat(3.0), 0.5), 'b');
+		let c = tree.insert(Aabb2::from_center_extents(Vec2::new(12.0, 12.0), 0.5), 'c');
+
+		// Splits down to the maximum depth where the cells are 2 wide, leaving the large box at the root
+		let large = tree.insert(Aabb2::from_center_extents(Vec2::splat(8.0), 6.0), 'd');
+		let mut leaves: Vec<Aabb2> = tree.leaf_bounds().collect();
+		leaves.sort_by(|x, y| x.min.x.total_cmp(&y.min.x));
+		assert_eq!(leaves.len(), 3);
+		assert_vec_eq!(leaves[0].size(), Vec2::splat(2.0));
+		assert_vec_eq!(leaves[0].min, Vec2::zero());
+		assert_vec_eq!(leaves[1].min, Vec2::splat(2.0));
+		assert_vec_eq!(leaves[2].size(), Vec2::splat(8.0));
+
+		let mut found = Vec::new();
+		tree.query(&Aabb2::new(Vec2::splat(2.5), Vec2::splat(13.0)), |_, &item| found.push(item));
+		found.sort();
+		assert_eq!(found, vec!['b', 'c', 'd']);
+
+		assert_eq!(tree.nearest(Vec2::new(0.0, 1.0), 2).iter().map(|&(handle, _)| handle).collect::<Vec<_>>(), vec![a, large]);
+		assert_almost_eq!(tree.nearest(Vec2::new(0.0, 1.0), 1)[0].1, 0.5);
+
+		// Moving within the loose bounds of its node leaves an item where it is
+		assert!(!tree.update(c, Aabb2::from_center_extents(Vec2::new(12.5, 11.0), 0.5)));
+		assert!(tree.update(b, Aabb2::from_center_extents(Vec2::new(10.0, 3.0), 0.5)));
+		*tree.get_mut(b).unwrap() = 'e';
+		assert_eq!(tree.get(b), Some(&'e'));
+	}
+}
@@ -0,0 +1,271 @@
+use crate::math::*;
+use crate::geometry::boolean::{PolygonSet2, FillRule};
+use crate::geometry::polygon::{Polygon2, Winding};
+use crate::geometry::stroke::LineJoin;
+use crate::geometry::path::arc_segment_count;
+
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OffsetOptions {
+	/// How corners that open up when offset are filled.
+	pub join: LineJoin,
+
+	/// The maximum ratio of miter length to offset distance before miter joins are beveled.
+	pub miter_limit: f32,
+
+	/// Maximum error when approximating round joins.
+	pub tolerance: f32,
+}
+
+impl OffsetOptions {
+	pub fn new(join: LineJoin) -> OffsetOptions {
+		OffsetOptions {
+			join,
+			miter_limit: 2.0,
+			tolerance: 0.01,
+		}
+	}
+
+	pub fn with_miter_limit(self, miter_limit: f32) -> OffsetOptions {
+		OffsetOptions { miter_limit, ..self }
+	}
+
+	pub fn with_tolerance(self, tolerance: f32) -> OffsetOptions {
+		OffsetOptions { tolerance, ..self }
+	}
+}
+
+impl Default for OffsetOptions {
+	fn default() -> OffsetOptions {
+		OffsetOptions::new(LineJoin::Miter)
+	}
+}
+
+
+impl PolygonSet2 {
+	/// Grows the filled region outwards by `delta`, or shrinks it for negative `delta`.
+	///
+	/// Contours are expected to be wound like the results of boolean operations, with holes opposite their outer contours.
+	/// Arbitrary input should be passed through `simplify` first.
+	pub fn offset(&self, delta: f32, options: &OffsetOptions) -> PolygonSet2 {
+		if delta == 0.0 {
+			return self.clone();
+		}
+
+		// Each contour is offset independently, which may leave loops where edges collapse or overlap.
+		// Those wind the wrong way, so they are removed by keeping only positively wound regions.
+		let raw: PolygonSet2 = self.contours.iter()
+			.map(|contour| offset_contour(contour, delta, options))
+			.collect();
+
+		raw.simplify(FillRule::Positive)
+	}
+}
+
+impl Polygon2 {
+	/// Grows the polygon outwards by `delta`, or shrinks it for negative `delta`, regardless of its winding.
+	/// Shrinking may split the polygon into several parts.
+	pub fn offset(&self, delta: f32, options: &OffsetOptions) -> PolygonSet2 {
+		PolygonSet2::from(self.clone().with_winding(Winding::CounterClockwise))
+			.offset(delta, options)
+	}
+}
+
+
+// Moves each edge of `contour` by `delta` to its right, joining up the moved edges.
+fn offset_contour(contour: &Polygon2, delta: f32, options: &OffsetOptions) -> Polygon2 {
+	let mut points = contour.points.clone();
+	points.dedup_by(|a, b| (*a - *b).square_length() < 1.0e-12);
+	while points.len() > 1 && (points[0] - points[points.len() - 1]).square_length() < 1.0e-12 {
+		points.pop();
+	}
+
+	let count = points.len();
+	if count < 3 {
+		return Polygon2::default();
+	}
+
+	let right_normal = |from: Vec2, to: Vec2| {
+		let direction = (to - from).normalize();
+		Vec2::new(direction.y, -direction.x)
+	};
+
+	let mut offset = Vec::with_capacity(count * 2);
+
+	for i in 0..count {
+		let previous = points[(i + count - 1) % count];
+		let point = points[i];
+		let next = points[(i + 1) % count];
+
+		let n0 = right_normal(previous, point);
+		let n1 = right_normal(point, next);
+		let (from, to) = (point + n0 * delta, point + n1 * delta);
+
+		let turn = n0.wedge(n1);
+		let straight = turn.abs() < 1.0e-6 && n0.dot(n1) > 0.0;
+
+		if straight {
+			offset.push(from);
+			continue
+		}
+
+		if turn * delta < 0.0 {
+			// The offset edges overlap here, so loop back through the original point.
+			// The loop winds the wrong way and is removed when the result is simplified.
+			offset.extend([from, point, to]);
+			continue
+		}
+
+		match options.join {
+			LineJoin::Bevel => offset.extend([from, to]),
+
+			LineJoin::Miter => {
+				let bisector = n0 + n1;
+				let miter_ratio = 2.0 / bisector.length().max(1.0e-6);
+
+				if miter_ratio <= options.miter_limit {
+					offset.push(point + bisector.normalize() * (delta * miter_ratio));
+				} else {
+					offset.extend([from, to]);
+				}
+			}
+
+			LineJoin::Round => {
+				let radius = delta.abs();
+				let sweep = n0.wedge(n1).atan2(n0.dot(n1));
+				let start_angle = (n0 * delta).to_angle();
+				let steps = arc_segment_count(radius, sweep, options.tolerance);
+
+				offset.push(from);
+				for step in 1..steps {
+					let angle = start_angle + sweep * step as f32 / steps as f32;
+					offset.push(point + Vec2::from_angle(angle) * radius);
+				}
+				offset.push(to);
+			}
+		}
+	}
+
+	Polygon2::new(offset)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+	use rand::{Rng, SeedableRng};
+	use rand::rngs::StdRng;
+
+	fn square(size: f32) -> Polygon2 {
+		Polygon2::from_aabb(Aabb2::new(Vec2::zero(), Vec2::splat(size)))
+	}
+
+	#[test]
+	fn test_grow() {
+		let miter = square(2.0).offset(1.0, &OffsetOptions::new(LineJoin::Miter));
+		assert_eq!(miter.contours.len(), 1);
+		assert_eq!(miter.contours[0].len(), 4);
+		assert_almost_eq!(miter.area(), 16.0);
+
+		let bevel = square(2.0).offset(1.0, &OffsetOptions::new(LineJoin::Bevel));
+		assert_eq!(bevel.contours[0].len(), 8);
+		assert_almost_eq!(bevel.area(), 14.0);
+
+		let round = square(2.0).offset(1.0, &OffsetOptions::new(LineJoin::Round).with_tolerance(0.0001));
+		assert!((round.area() - (4.0 + 8.0 + PI)).abs() < 0.01);
+
+		// Sharp corners past the miter limit are beveled
+		let spike = Polygon2::new(vec![Vec2::zero(), Vec2::new(10.0, 0.5), Vec2::from_y(1.0)]);
+		let grown = spike.offset(0.5, &OffsetOptions::new(LineJoin::Miter));
+		assert!(grown.bounds().max.x < 11.0);
+	}
+
+	#[test]
+	fn test_shrink() {
+		let shrunk = square(2.0).offset(-0.5, &OffsetOptions::default());
+		assert_almost_eq!(shrunk.area(), 1.0);
+
+		assert!(square(2.0).offset(-1.5, &OffsetOptions::default()).is_empty());
+
+		// Clockwise input is treated the same
+		let clockwise = square(2.0).with_winding(Winding::Clockwise);
+		assert_almost_eq!(clockwise.offset(-0.5, &OffsetOptions::default()).area(), 1.0);
+
+		// A dumbbell splits in two when its handle collapses
+		let dumbbell = rect_union(&[
+			Aabb2::new(Vec2::zero(), Vec2::splat(2.0)),
+			Aabb2::new(Vec2::new(2.0, 0.75), Vec2::new(4.0, 1.25)),
+			Aabb2::new(Vec2::from_x(4.0), Vec2::new(6.0, 2.0)),
+		]);
+
+		let shrunk = dumbbell.offset(-0.5, &OffsetOptions::default());
+		assert_eq!(shrunk.contours.len(), 2);
+		assert_almost_eq!(shrunk.area(), 2.0);
+
+		// Holes grow as the region shrinks
+		let frame = PolygonSet2::from(square(4.0)).difference(&Polygon2::from_aabb(Aabb2::new(Vec2::one(), Vec2::splat(3.0))).into());
+		let shrunk = frame.offset(-0.25, &OffsetOptions::default());
+		assert_almost_eq!(shrunk.area(), 3.5 * 3.5 - 2.5 * 2.5);
+	}
+
+	fn rect_union(rects: &[Aabb2]) -> PolygonSet2 {
+		rects.iter().fold(PolygonSet2::empty(), |set, &rect| set.union(&Polygon2::from_aabb(rect).into()))
+	}
+
+	#[test]
+	fn test_fuzz() {
+		let mut rng = StdRng::seed_from_u64(54321);
+
+		for iteration in 0..100 {
+			let rects: Vec<Aabb2> = (0..rng.random_range(1..5))
+				.map(|_| {
+					let min = Vec2::new(rng.random_range(-5.0..5.0), rng.random_range(-5.0..5.0));
+					Aabb2::new(min, min + Vec2::new(rng.random_range(0.5..4.0), rng.random_range(0.5..4.0)))
+				})
+				.collect();
+
+			let set = rect_union(&rects);
+			let delta = rng.random_range(0.05..1.0);
+			let join = [LineJoin::Miter, LineJoin::Round, LineJoin::Bevel][iteration % 3];
+			let options = OffsetOptions::new(join);
+			let context = format!("iteration {}: {:?} {} {:?}", iteration, rects, delta, join);
+
+			let grown = set.offset(delta, &options);
+			let shrunk = set.offset(-delta, &options);
+
+			assert!(grown.area() >= set.area(), "{}", context);
+			assert!(shrunk.area() <= set.area(), "{}", context);
+
+			// Offsetting is deterministic
+			assert_eq!(format!("{:?}", set.offset(delta, &options)), format!("{:?}", grown), "{}", context);
+
+			for _ in 0..50 {
+				let point = Vec2::new(rng.random_range(-7.0..11.0), rng.random_range(-7.0..11.0));
+				let distance_to_boundary = set.contours.iter()
+					.flat_map(|contour| contour.edges())
+					.map(|(start, end)| {
+						let t = ((point - start).dot(end - start) / (end - start).square_length()).clamp(0.0, 1.0);
+						(start + (end - start) * t - point).length()
+					})
+					.fold(f32::INFINITY, f32::min);
+
+				// Points well inside stay inside the grown set, points well outside stay outside the shrunk set
+				if set.contains_point(point) && distance_to_boundary > 0.01 {
+					assert!(grown.contains_point(point), "{} {:?}", context, point);
+				}
+
+				if !set.contains_point(point) && distance_to_boundary > 0.01 {
+					assert!(!shrunk.contains_point(point), "{} {:?}", context, point);
+				}
+
+				// Points near the original boundary are removed by shrinking and covered by growing
+				if distance_to_boundary < delta * 0.9 {
+					assert!(!shrunk.contains_point(point), "{} {:?}", context, point);
+					assert!(grown.contains_point(point), "{} {:?}", context, point);
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,378 @@
+use crate::math::*;
+use crate::geometry::stroke::{StrokeStyle, stroke_polyline};
+
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PathCommand {
+	MoveTo(Vec2),
+	LineTo(Vec2),
+	QuadTo { control: Vec2, end: Vec2 },
+	CubicTo { control1: Vec2, control2: Vec2, end: Vec2 },
+
+	/// A circular arc starting at the current point, which lies on the circle at `start_angle`.
+	/// Positive `sweep_angle` is counter-clockwise.
+	Arc { center: Vec2, radius: f32, start_angle: f32, sweep_angle: f32 },
+
+	/// Joins the current point back to the start of the subpath.
+	Close,
+}
+
+
+/// A polyline produced by flattening a `Path2`.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polyline2 {
+	pub points: Vec<Vec2>,
+
+	/// Whether the last point connects back to the first.
+	pub closed: bool,
+}
+
+
+/// A sequence of 2D subpaths made up of lines, bezier curves and arcs.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Path2 {
+	commands: Vec<PathCommand>,
+	subpath_start: Option<Vec2>,
+	current: Option<Vec2>,
+}
+
+impl Path2 {
+	pub fn new() -> Path2 {
+		Path2::default()
+	}
+
+	pub fn commands(&self) -> &[PathCommand] {
+		&self.commands
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.commands.is_empty()
+	}
+
+	/// The end of the last command, if any.
+	pub fn current_point(&self) -> Option<Vec2> {
+		self.current
+	}
+
+	/// Starts a new subpath at `point`.
+	pub fn move_to(mut self, point: Vec2) -> Path2 {
+		self.commands.push(PathCommand::MoveTo(point));
+		self.subpath_start = Some(point);
+		self.current = Some(point);
+		self
+	}
+
+	/// Drawing commands without a current point start a new subpath at their first point.
+	pub fn line_to(self, point: Vec2) -> Path2 {
+		let mut path = self.ensure_subpath(point);
+		path.commands.push(PathCommand::LineTo(point));
+		path.current = Some(point);
+		path
+	}
+
+	pub fn quad_to(self, control: Vec2, end: Vec2) -> Path2 {
+		let mut path = self.ensure_subpath(control);
+		path.commands.push(PathCommand::QuadTo { control, end });
+		path.current = Some(end);
+		path
+	}
+
+	pub fn cubic_to(self, control1: Vec2, control2: Vec2, end: Vec2) -> Path2 {
+		let mut path = self.ensure_subpath(control1);
+		path.commands.push(PathCommand::CubicTo { control1, control2, end });
+		path.current = Some(end);
+		path
+	}
+
+	/// Adds an arc of `radius` tangent to both the line from the current point to `corner`,
+	/// and the line from `corner` to `end`, connected to the current point by a straight line.
+	/// As with html canvas `arcTo`, the path ends at the second tangent point rather than `end`.
+	///
+	/// Falls back to a line to `corner` if the arc is degenerate.
+	pub fn arc_to(self, corner: Vec2, end: Vec2, radius: f32) -> Path2 {
+		let Some(current) = self.current else {
+			return self.move_to(corner);
+		};
+
+		let to_start = current - corner;
+		let to_end = end - corner;
+
+		let degenerate = radius <= 0.0
+			|| to_start.square_length() < 1.0e-12
+			|| to_end.square_length() < 1.0e-12
+			|| to_start.normalize().wedge(to_end.normalize()).abs() < 1.0e-6;
+
+		if degenerate {
+			return self.line_to(corner);
+		}
+
+		let to_start = to_start.normalize();
+		let to_end = to_end.normalize();
+
+		let half_angle = to_start.dot(to_end).clamp(-1.0, 1.0).acos() / 2.0;
+		let tangent_distance = radius / half_angle.tan();
+
+		let start = corner + to_start * tangent_distance;
+		let finish = corner + to_end * tangent_distance;
+		let center = corner + (to_start + to_end).normalize() * (radius / half_angle.sin());
+
+		let start_angle = (start - center).to_angle();
+		let end_angle = (finish - center).to_angle();
+		let sweep_angle = (end_angle - start_angle + PI).rem_euclid(TAU) - PI;
+
+		let mut path = if (start - current).square_length() > 1.0e-12 {
+			self.line_to(start)
+		} else {
+			self
+		};
+
+		path.commands.push(PathCommand::Arc { center, radius, start_angle, sweep_angle });
+		path.current = Some(finish);
+		path
+	}
+
+	/// Closes the current subpath. Following commands continue from the start of the closed subpath.
+	pub fn close(mut self) -> Path2 {
+		if self.subpath_start.is_some() {
+			self.commands.push(PathCommand::Close);
+			self.current = self.subpath_start;
+		}
+
+		self
+	}
+
+	/// Converts each subpath into a polyline, approximating curves to within `tolerance`.
+	/// Subpaths consisting only of a `MoveTo` are skipped.
+	pub fn flatten(&self, tolerance: f32) -> Vec<Polyline2> {
+		let tolerance = tolerance.max(1.0e-5);
+
+		let mut polylines = Vec::new();
+		let mut polyline = Polyline2::default();
+		let mut has_segments = false;
+
+		let mut finish_polyline = |polyline: &mut Polyline2, has_segments: &mut bool| {
+			let polyline = std::mem::take(polyline);
+			if *has_segments {
+				polylines.push(polyline);
+			}
+			*has_segments = false;
+		};
+
+		for &command in self.commands.iter() {
+			let current = polyline.points.last().copied().unwrap_or_default();
+
+			match command {
+				PathCommand::MoveTo(point) => {
+					finish_polyline(&mut polyline, &mut has_segments);
+					polyline.points.push(point);
+					continue
+				}
+
+				PathCommand::LineTo(point) => polyline.points.push(point),
+
+				PathCommand::QuadTo { control, end } => {
+					let curve = QuadBezier2::new(current, control, end).to_cubic();
+					flatten_cubic(&curve, tolerance, 0, &mut polyline.points);
+				}
+
+				PathCommand::CubicTo { control1, control2, end } => {
+					let curve = CubicBezier2::new(current, control1, control2, end);
+					flatten_cubic(&curve, tolerance, 0, &mut polyline.points);
+				}
+
+				PathCommand::Arc { center, radius, start_angle, sweep_angle } => {
+					let steps = arc_segment_count(radius, sweep_angle, tolerance);
+					for step in 1..=steps {
+						let angle = start_angle + sweep_angle * step as f32 / steps as f32;
+						polyline.points.push(center + Vec2::from_angle(angle) * radius);
+					}
+				}
+
+				PathCommand::Close => {
+					let start = polyline.points.first().copied();
+					has_segments = true;
+
+					// The closing segment is implicit
+					if polyline.points.len() > 1 && start.is_some_and(|start| (start - current).square_length() < 1.0e-12) {
+						polyline.points.pop();
+					}
+
+					polyline.closed = true;
+					finish_polyline(&mut polyline, &mut has_segments);
+
+					// Subsequent commands continue from the start of the closed subpath
+					polyline.points.extend(start);
+					continue
+				}
+			}
+
+			has_segments = true;
+		}
+
+		finish_polyline(&mut polyline, &mut has_segments);
+		polylines
+	}
+
+	/// Flattens the path with `style.tolerance` and strokes each subpath into a triangle list.
+	pub fn stroke(&self, style: &StrokeStyle) -> Vec<[Vec2; 3]> {
+		let mut triangles = Vec::new();
+
+		for polyline in self.flatten(style.tolerance) {
+			stroke_polyline(&polyline.points, polyline.closed, style, &mut triangles);
+		}
+
+		triangles
+	}
+
+	fn ensure_subpath(self, point: Vec2) -> Path2 {
+		if self.current.is_some() {
+			self
+		} else {
+			self.move_to(point)
+		}
+	}
+}
+
+
+/// The number of line segments needed to approximate an arc to within `tolerance`.
+pub(crate) fn arc_segment_count(radius: f32, sweep_angle: f32, tolerance: f32) -> usize {
+	// The furthest a chord strays from its arc is r(1 - cos(θ/2))
+	let max_step = if tolerance < radius {
+		2.0 * (1.0 - tolerance / radius).acos()
+	} else {
+		PI / 2.0
+	};
+
+	let steps = (sweep_angle.abs() / max_step.max(1.0e-3)).ceil() as usize;
+	steps.clamp(1, 1024)
+}
+
+// Recursively subdivides `curve` until it is flat enough to be replaced by its chord,
+// pushing every point but the first into `points`.
+fn flatten_cubic(curve: &CubicBezier2, tolerance: f32, depth: u32, points: &mut Vec<Vec2>) {
+	const MAX_DEPTH: u32 = 16;
+
+	let chord = curve.p3 - curve.p0;
+	let chord_length = chord.length();
+
+	// Control points bound the curve, so their distance from the chord bounds the error
+	let deviation = if chord_length > 1.0e-6 {
+		let normal = chord.perp() / chord_length;
+		normal.dot(curve.p1 - curve.p0).abs().max(normal.dot(curve.p2 - curve.p0).abs())
+	} else {
+		(curve.p1 - curve.p0).length().max((curve.p2 - curve.p0).length())
+	};
+
+	// Control points far past the ends of the chord also stray from it
+	let overshoot = if chord_length > 1.0e-6 {
+		let direction = chord / chord_length;
+		[curve.p1, curve.p2].iter()
+			.map(|p| direction.dot(*p - curve.p0))
+			.map(|d| (-d).max(d - chord_length))
+			.fold(0.0, f32::max)
+	} else {
+		0.0
+	};
+
+	if depth >= MAX_DEPTH || deviation.max(overshoot) <= tolerance {
+		points.push(curve.p3);
+		return;
+	}
+
+	let (first, second) = curve.split(0.5);
+	flatten_cubic(&first, tolerance, depth + 1, points);
+	flatten_cubic(&second, tolerance, depth + 1, points);
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn test_lines() {
+		let path = Path2::new()
+			.move_to(Vec2::zero())
+			.line_to(Vec2::from_x(1.0))
+			.line_to(Vec2::one())
+			.close()
+			.line_to(Vec2::from_y(-1.0))
+			.move_to(Vec2::splat(5.0));
+
+		let polylines = path.flatten(0.1);
+		assert_eq!(polylines.len(), 2);
+
+		assert!(polylines[0].closed);
+		assert_eq!(polylines[0].points.len(), 3);
+		for (&point, expected) in polylines[0].points.iter().zip([Vec2::zero(), Vec2::from_x(1.0), Vec2::one()]) {
+			assert_vec_eq!(point, expected);
+		}
+
+		assert!(!polylines[1].closed);
+		assert_eq!(polylines[1].points.len(), 2);
+		assert_vec_eq!(polylines[1].points[0], Vec2::zero());
+		assert_vec_eq!(polylines[1].points[1], Vec2::from_y(-1.0));
+	}
+
+	#[test]
+	fn test_curves_within_tolerance() {
+		let quad = QuadBezier2::new(Vec2::zero(), Vec2::new(1.0, 3.0), Vec2::new(2.0, 0.0));
+		let cubic = CubicBezier2::new(Vec2::new(2.0, 0.0), Vec2::new(3.0, -2.0), Vec2::new(-1.0, -2.0), Vec2::new(4.0, 1.0));
+
+		for tolerance in [0.5, 0.1, 0.01] {
+			let polylines = Path2::new()
+				.move_to(quad.p0)
+				.quad_to(quad.p1, quad.p2)
+				.cubic_to(cubic.p1, cubic.p2, cubic.p3)
+				.flatten(tolerance);
+
+			let points = &polylines[0].points;
+			assert_vec_eq!(*points.last().unwrap(), cubic.p3);
+
+			let curve_points: Vec<Vec2> = (0..=20000)
+				.map(|i| i as f32 / 20000.0)
+				.flat_map(|t| [quad.eval(t), cubic.eval(t)])
+				.collect();
+
+			let distance = |p: Vec2| curve_points.iter()
+				.map(|&c| (c - p).length())
+				.fold(f32::INFINITY, f32::min);
+
+			// Every point lies on a curve, and every segment midpoint lies near one
+			for pair in points.windows(2) {
+				assert!(distance(pair[0]) < 0.001);
+				assert!(distance((pair[0] + pair[1]) / 2.0) <= tolerance + 0.001);
+			}
+		}
+
+		let coarse = Path2::new().move_to(quad.p0).quad_to(quad.p1, quad.p2).flatten(0.5);
+		let fine = Path2::new().move_to(quad.p0).quad_to(quad.p1, quad.p2).flatten(0.01);
+		assert!(fine[0].points.len() > coarse[0].points.len());
+	}
+
+	#[test]
+	fn test_arc_to() {
+		// A rounded corner
+		let path = Path2::new()
+			.move_to(Vec2::zero())
+			.arc_to(Vec2::from_x(4.0), Vec2::new(4.0, 4.0), 1.0);
+
+		assert_vec_eq!(path.current_point().unwrap(), Vec2::new(4.0, 1.0));
+
+		let points = &path.flatten(0.001)[0].points;
+		assert_vec_eq!(points[1], Vec2::from_x(3.0));
+
+		let center = Vec2::new(3.0, 1.0);
+		for &point in &points[1..] {
+			assert_almost_eq!((point - center).length(), 1.0);
+		}
+
+		// Collinear points degenerate to a line
+		let path = Path2::new().move_to(Vec2::zero()).arc_to(Vec2::from_x(1.0), Vec2::from_x(2.0), 1.0);
+		assert!(matches!(path.commands().last(), Some(&PathCommand::LineTo(point)) if point.x == 1.0 && point.y == 0.0));
+	}
+}
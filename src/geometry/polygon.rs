@@ -0,0 +1,478 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+use crate::math::*;
+use crate::geometry::triangulate::triangulate_polygon;
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Winding {
+	CounterClockwise,
+	Clockwise,
+}
+
+
+/// A closed polygon. The last point implicitly connects back to the first.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polygon2 {
+	pub points: Vec<Vec2>,
+}
+
+/// Constructors
+impl Polygon2 {
+	pub fn new(points: Vec<Vec2>) -> Polygon2 {
+		Polygon2 { points }
+	}
+
+	pub fn from_aabb(aabb: Aabb2) -> Polygon2 {
+		Polygon2::new(vec![aabb.min, aabb.max_min_corner(), aabb.max, aabb.min_max_corner()])
+	}
+
+	/// A counter-clockwise regular polygon with its first point at `angle` radians.
+	pub fn regular(center: Vec2, radius: f32, sides: usize, angle: f32) -> Polygon2 {
+		(0..sides)
+			.map(|i| center + Vec2::from_angle(angle + i as f32 / sides as f32 * TAU) * radius)
+			.collect()
+	}
+}
+
+/// Properties
+impl Polygon2 {
+	pub fn len(&self) -> usize {
+		self.points.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.points.is_empty()
+	}
+
+	/// Iterates over each edge as a pair of points, including the closing edge.
+	pub fn edges(&self) -> impl Iterator<Item=(Vec2, Vec2)> + '_ {
+		let count = self.points.len();
+		(0..count).map(move |i| (self.points[i], self.points[(i + 1) % count]))
+	}
+
+	/// Positive for counter-clockwise polygons.
+	pub fn signed_area(&self) -> f32 {
+		// Relative to the first point to reduce precision loss far from the origin
+		let origin = self.points.first().copied().unwrap_or_default();
+		self.edges().map(|(a, b)| (a - origin).wedge(b - origin)).sum::<f32>() / 2.0
+	}
+
+	pub fn area(&self) -> f32 {
+		self.signed_area().abs()
+	}
+
+	pub fn perimeter(&self) -> f32 {
+		self.edges().map(|(a, b)| (b - a).length()).sum()
+	}
+
+	/// The center of mass of the polygon's area.
+	/// Falls back to the average of the points if the polygon has no area.
+	pub fn centroid(&self) -> Vec2 {
+		let signed_area = self.signed_area();
+
+		if signed_area.abs() < 1.0e-9 {
+			let sum: Vec2 = self.points.iter().copied().sum();
+			return sum / self.points.len().max(1) as f32;
+		}
+
+		let origin = self.points.first().copied().unwrap_or_default();
+		let weighted_sum: Vec2 = self.edges()
+			.map(|(a, b)| {
+				let (a, b) = (a - origin, b - origin);
+				(a + b) * a.wedge(b)
+			})
+			.sum();
+
+		origin + weighted_sum / (6.0 * signed_area)
+	}
+
+	/// Polygons with no area are considered counter-clockwise.
+	pub fn winding(&self) -> Winding {
+		if self.signed_area() < 0.0 {
+			Winding::Clockwise
+		} else {
+			Winding::CounterClockwise
+		}
+	}
+
+	/// Whether every interior angle is at most 180 degrees and the boundary does not wrap around more than once.
+	/// Collinear and duplicate points are allowed.
+	pub fn is_convex(&self) -> bool {
+		let count = self.points.len();
+		if count < 3 {
+			return false;
+		}
+
+		let mut turn_sign = 0.0;
+		let mut total_turn = 0.0;
+
+		for i in 0..count {
+			let previous = self.points[(i + count - 1) % count];
+			let point = self.points[i];
+			let next = self.points[(i + 1) % count];
+
+			let incoming = point - previous;
+			let outgoing = next - point;
+			if incoming.square_length() < 1.0e-12 || outgoing.square_length() < 1.0e-12 {
+				continue
+			}
+
+			let cross = incoming.wedge(outgoing);
+			if cross.abs() > 1.0e-6 * incoming.length() * outgoing.length() {
+				if turn_sign * cross < 0.0 {
+					return false;
+				}
+
+				turn_sign = cross.signum();
+			}
+
+			total_turn += cross.atan2(incoming.dot(outgoing));
+		}
+
+		// Star shaped polygons turn in one direction, but wrap around multiple times
+		turn_sign != 0.0 && (total_turn.abs() - TAU).abs() < 0.01
+	}
+
+	pub fn bounds(&self) -> Aabb2 {
+		Aabb2::from_points(&self.points)
+	}
+}
+
+/// Queries
+impl Polygon2 {
+	/// The number of times the boundary winds counter-clockwise around `point`.
+	/// Negative for clockwise windings. Points exactly on the boundary may be counted either way.
+	// http://geomalgorithms.com/a03-_inclusion.html
+	pub fn winding_number(&self, point: Vec2) -> i32 {
+		let mut winding_number = 0;
+
+		for (a, b) in self.edges() {
+			let side = (b - a).wedge(point - a);
+
+			if a.y <= point.y {
+				if b.y > point.y && side > 0.0 {
+					winding_number += 1;
+				}
+			} else if b.y <= point.y && side < 0.0 {
+				winding_number -= 1;
+			}
+		}
+
+		winding_number
+	}
+
+	/// Whether `point` is inside the polygon, using the nonzero winding rule.
+	pub fn contains_point(&self, point: Vec2) -> bool {
+		self.winding_number(point) != 0
+	}
+
+	/// Counter-clockwise triangles covering the polygon, as indices into `points`.
+	pub fn triangulate(&self) -> Vec<[u32; 3]> {
+		triangulate_polygon(&self.points)
+	}
+}
+
+/// Modification
+impl Polygon2 {
+	pub fn reverse(&mut self) {
+		self.points.reverse();
+	}
+
+	pub fn with_winding(mut self, winding: Winding) -> Polygon2 {
+		if self.winding() != winding {
+			self.reverse();
+		}
+
+		self
+	}
+
+	/// Transforms every point by `transform`. Transforms with reflections reverse the winding of the result.
+	pub fn transform(&self, transform: &Mat2x3) -> Polygon2 {
+		self.points.iter().map(|&point| *transform * point).collect()
+	}
+
+	pub fn translate(&self, offset: Vec2) -> Polygon2 {
+		self.points.iter().map(|&point| point + offset).collect()
+	}
+
+	/// Simplifies the polygon with the Ramer-Douglas-Peucker algorithm, removing points that are less than `epsilon`
+	/// away from the simplified boundary. Good at preserving sharp features.
+	pub fn simplify_rdp(&self, epsilon: f32) -> Polygon2 {
+		let count = self.points.len();
+		if count <= 3 {
+			return self.clone();
+		}
+
+		// A closed polygon is split into chains between the first point, the point furthest from it,
+		// and the point furthest from the line between them, so that at least a triangle remains
+		let start = self.points[0];
+		let furthest_from = |from: &dyn Fn(Vec2) -> f32| (1..count)
+			.max_by(|&a, &b| from(self.points[a]).total_cmp(&from(self.points[b])))
+			.unwrap();
+
+		let far = furthest_from(&|p| (p - start).square_length());
+		let axis = self.points[far] - start;
+		let side = furthest_from(&|p| axis.wedge(p - start).abs());
+
+		let mut anchors = [0, far, side, count];
+		anchors.sort();
+
+		let mut ring = self.points.clone();
+		ring.push(start);
+
+		let mut keep_ring = vec![false; count + 1];
+		for pair in anchors.windows(2) {
+			keep_ring[pair[0]] = true;
+			rdp_mark(&ring, pair[0], pair[1], epsilon, &mut keep_ring);
+		}
+
+		self.points.iter()
+			.zip(&keep_ring)
+			.filter_map(|(&point, &keep)| keep.then_some(point))
+			.collect()
+	}
+
+	/// Simplifies the polygon with the Visvalingam-Whyatt algorithm, repeatedly removing the point forming the smallest
+	/// triangle with its neighbours until every remaining triangle has an area of at least `min_area`.
+	/// Good at preserving overall shape. At least three points are kept.
+	pub fn simplify_visvalingam(&self, min_area: f32) -> Polygon2 {
+		let count = self.points.len();
+		if count <= 3 {
+			return self.clone();
+		}
+
+		let mut next: Vec<usize> = (0..count).map(|i| (i + 1) % count).collect();
+		let mut previous: Vec<usize> = (0..count).map(|i| (i + count - 1) % count).collect();
+		let mut removed = vec![false; count];
+		let mut remaining = count;
+
+		let area = |previous: usize, index: usize, next: usize| {
+			(self.points[index] - self.points[previous]).wedge(self.points[next] - self.points[previous]).abs() / 2.0
+		};
+
+		// Removing a point only changes the triangles of its two neighbours, which are pushed again with their new area.
+		// Entries whose area is out of date are skipped.
+		let mut areas: Vec<f32> = (0..count).map(|i| area(previous[i], i, next[i])).collect();
+		let mut heap: BinaryHeap<Reverse<Candidate>> = areas.iter()
+			.enumerate()
+			.map(|(index, &area)| Reverse(Candidate { area, index }))
+			.collect();
+
+		while remaining > 3 {
+			let Some(Reverse(Candidate { area: smallest_area, index })) = heap.pop() else { break };
+			if removed[index] || smallest_area.to_bits() != areas[index].to_bits() {
+				continue;
+			}
+
+			if smallest_area >= min_area {
+				break
+			}
+
+			next[previous[index]] = next[index];
+			previous[next[index]] = previous[index];
+			removed[index] = true;
+			remaining -= 1;
+
+			for neighbor in [previous[index], next[index]] {
+				areas[neighbor] = area(previous[neighbor], neighbor, next[neighbor]);
+				heap.push(Reverse(Candidate { area: areas[neighbor], index: neighbor }));
+			}
+		}
+
+		self.points.iter()
+			.zip(&removed)
+			.filter_map(|(&point, &removed)| (!removed).then_some(point))
+			.collect()
+	}
+}
+
+impl FromIterator<Vec2> for Polygon2 {
+	fn from_iter<I>(iter: I) -> Polygon2 where I: IntoIterator<Item=Vec2> {
+		Polygon2::new(iter.into_iter().collect())
+	}
+}
+
+impl From<Vec<Vec2>> for Polygon2 {
+	fn from(points: Vec<Vec2>) -> Polygon2 {
+		Polygon2::new(points)
+	}
+}
+
+
+// Marks the points between `start` and `end` that should be kept to stay within `epsilon` of the chain.
+fn rdp_mark(points: &[Vec2], start: usize, end: usize, epsilon: f32, keep: &mut [bool]) {
+	if end <= start + 1 {
+		return;
+	}
+
+	let (a, b) = (points[start], points[end]);
+	let chord = b - a;
+	let chord_length = chord.length();
+
+	let distance = |p: Vec2| if chord_length > 1.0e-9 {
+		chord.wedge(p - a).abs() / chord_length
+	} else {
+		(p - a).length()
+	};
+
+	let (furthest, furthest_distance) = (start + 1..end)
+		.map(|i| (i, distance(points[i])))
+		.max_by(|x, y| x.1.total_cmp(&y.1))
+		.unwrap();
+
+	if furthest_distance > epsilon {
+		keep[furthest] = true;
+		rdp_mark(points, start, furthest, epsilon, keep);
+		rdp_mark(points, furthest, end, epsilon, keep);
+	}
+}
+
+
+// A point to remove in Visvalingam-Whyatt simplification, ordered by the area of its triangle.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Candidate {
+	area: f32,
+	index: usize,
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+	fn partial_cmp(&self, other: &Candidate) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Candidate {
+	fn cmp(&self, other: &Candidate) -> Ordering {
+		self.area.total_cmp(&other.area).then(self.index.cmp(&other.index))
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn l_shape() -> Polygon2 {
+		Polygon2::new(vec![
+			Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 1.0),
+			Vec2::new(1.0, 1.0), Vec2::new(1.0, 2.0), Vec2::new(0.0, 2.0),
+		])
+	}
+
+	#[test]
+	fn test_area_and_centroid() {
+		let polygon = l_shape();
+		assert_almost_eq!(polygon.signed_area(), 3.0);
+		assert_almost_eq!(polygon.perimeter(), 8.0);
+		assert_vec_eq!(polygon.centroid(), Vec2::splat(5.0 / 6.0));
+
+		let reversed = polygon.clone().with_winding(Winding::Clockwise);
+		assert_eq!(reversed.winding(), Winding::Clockwise);
+		assert_almost_eq!(reversed.signed_area(), -3.0);
+		assert_almost_eq!(reversed.area(), 3.0);
+		assert_vec_eq!(reversed.centroid(), Vec2::splat(5.0 / 6.0));
+
+		let far = polygon.translate(Vec2::splat(10000.0));
+		assert_vec_eq!(far.centroid(), Vec2::splat(10000.0 + 5.0 / 6.0));
+
+		let bounds = polygon.bounds();
+		assert_vec_eq!(bounds.min, Vec2::zero());
+		assert_vec_eq!(bounds.max, Vec2::splat(2.0));
+	}
+
+	#[test]
+	fn test_convexity() {
+		assert!(!l_shape().is_convex());
+		assert!(Polygon2::regular(Vec2::zero(), 1.0, 7, 0.0).is_convex());
+		assert!(Polygon2::from_aabb(Aabb2::new(Vec2::zero(), Vec2::one())).with_winding(Winding::Clockwise).is_convex());
+
+		// Collinear points are fine
+		let square = Polygon2::new(vec![Vec2::zero(), Vec2::from_x(1.0), Vec2::from_x(2.0), Vec2::splat(2.0), Vec2::from_y(2.0)]);
+		assert!(square.is_convex());
+
+		// A pentagram turns one way, but wraps twice
+		let pentagram: Polygon2 = (0..5).map(|i| Vec2::from_angle(i as f32 * TAU * 2.0 / 5.0)).collect();
+		assert!(!pentagram.is_convex());
+	}
+
+	#[test]
+	fn test_containment() {
+		let polygon = l_shape();
+		assert!(polygon.contains_point(Vec2::splat(0.5)));
+		assert!(polygon.contains_point(Vec2::new(1.5, 0.5)));
+		assert!(!polygon.contains_point(Vec2::splat(1.5)));
+		assert!(!polygon.contains_point(Vec2::splat(-0.5)));
+
+		assert_eq!(polygon.winding_number(Vec2::splat(0.5)), 1);
+		assert_eq!(polygon.clone().with_winding(Winding::Clockwise).winding_number(Vec2::splat(0.5)), -1);
+
+		// The center of a pentagram is wound twice
+		let pentagram: Polygon2 = (0..5).map(|i| Vec2::from_angle(i as f32 * TAU * 2.0 / 5.0)).collect();
+		assert_eq!(pentagram.winding_number(Vec2::zero()), 2);
+	}
+
+	#[test]
+	fn test_transform() {
+		let polygon = l_shape();
+		let transform = Mat2x3::scale_rotate_translate(2.0, PI / 2.0, Vec2::new(5.0, 0.0));
+		let transformed = polygon.transform(&transform);
+
+		assert_almost_eq!(transformed.signed_area(), 12.0);
+		assert_vec_eq!(transformed.points[1], Vec2::new(5.0, 4.0));
+		assert_vec_eq!(transformed.centroid(), transform * polygon.centroid());
+
+		// Reflections reverse winding
+		let mirrored = polygon.transform(&Mat2x3::scale(Vec2::new(-1.0, 1.0)));
+		assert_eq!(mirrored.winding(), Winding::Clockwise);
+	}
+
+	#[test]
+	fn test_simplify() {
+		// A square with a noisy edge
+		let mut points = vec![Vec2::zero()];
+		points.extend((1..10).map(|i| Vec2::new(i as f32, if i % 2 == 0 { 0.01 } else { -0.01 })));
+		points.extend([Vec2::new(10.0, 0.0), Vec2::splat(10.0), Vec2::from_y(10.0)]);
+		let polygon = Polygon2::new(points);
+
+		let simplified = polygon.simplify_rdp(0.1);
+		assert_eq!(simplified.len(), 4);
+		assert_almost_eq!(simplified.area(), 100.0);
+
+		let simplified = polygon.simplify_visvalingam(0.5);
+		assert_eq!(simplified.len(), 4);
+		assert_almost_eq!(simplified.area(), 100.0);
+
+		// Large tolerances still leave a triangle
+		assert_eq!(polygon.simplify_rdp(100.0).len(), 3);
+		assert_eq!(polygon.simplify_visvalingam(1000.0).len(), 3);
+
+		// Small tolerances keep every point
+		assert_eq!(polygon.simplify_rdp(0.001).len(), polygon.len());
+		assert_eq!(polygon.simplify_visvalingam(0.001).len(), polygon.len());
+
+		// Large inputs, which need more than a scan of every point per removal
+		let circle: Polygon2 = (0..50000).map(|i| Vec2::from_angle(i as f32 / 50000.0 * TAU) * 10.0).collect();
+		let simplified = circle.simplify_visvalingam(0.01);
+		assert!(simplified.len() > 16 && simplified.len() < 1000);
+		assert!((simplified.area() - circle.area()).abs() < 1.0);
+	}
+
+	#[test]
+	fn test_triangulate() {
+		let polygon = l_shape();
+		let area: f32 = polygon.triangulate().iter()
+			.map(|&[a, b, c]| {
+				let [a, b, c] = [a, b, c].map(|i| polygon.points[i as usize]);
+				(b - a).wedge(c - a) / 2.0
+			})
+			.sum();
+
+		assert_almost_eq!(area, 3.0);
+	}
+}
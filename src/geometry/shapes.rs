@@ -0,0 +1,11 @@
+pub mod circle;
+pub mod segment;
+pub mod capsule;
+pub mod triangle;
+pub mod obb;
+
+pub use circle::*;
+pub use segment::*;
+pub use capsule::*;
+pub use triangle::*;
+pub use obb::*;
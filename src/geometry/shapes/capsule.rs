@@ -0,0 +1,137 @@
+use crate::math::*;
+use crate::geometry::shapes::circle::{max_scale_2d, max_scale_3d};
+use crate::geometry::shapes::segment::{Segment2, Segment3};
+
+
+/// All points within `radius` of the segment from `start` to `end`.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capsule2 {
+	pub start: Vec2,
+	pub end: Vec2,
+	pub radius: f32,
+}
+
+/// All points within `radius` of the segment from `start` to `end`.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capsule3 {
+	pub start: Vec3,
+	pub end: Vec3,
+	pub radius: f32,
+}
+
+macro_rules! impl_capsule {
+	($ty:ident, $vec:ident, $segment:ident, $mat:ident, $max_scale:ident) => {
+		/// Constructors
+		impl $ty {
+			pub fn new(start: $vec, end: $vec, radius: f32) -> $ty {
+				$ty { start, end, radius }
+			}
+		}
+
+		/// Properties
+		impl $ty {
+			/// The segment running through the middle of the capsule.
+			pub fn segment(&self) -> $segment {
+				$segment::new(self.start, self.end)
+			}
+		}
+
+		/// Queries
+		impl $ty {
+			pub fn contains_point(&self, point: $vec) -> bool {
+				(point - self.segment().closest_point(point)).square_length() <= self.radius * self.radius
+			}
+
+			/// The point within the capsule closest to `point`, which is `point` itself if it is inside.
+			pub fn closest_point(&self, point: $vec) -> $vec {
+				let axis_point = self.segment().closest_point(point);
+				let offset = point - axis_point;
+				let distance = offset.length();
+
+				if distance <= self.radius {
+					point
+				} else {
+					axis_point + offset * (self.radius / distance)
+				}
+			}
+		}
+
+		/// Modification
+		impl $ty {
+			/// Non-uniform scales can't be represented, so the radius is scaled by the largest scale factor.
+			pub fn transform(&self, transform: &$mat) -> $ty {
+				$ty::new(*transform * self.start, *transform * self.end, self.radius * $max_scale(transform))
+			}
+		}
+	}
+}
+
+impl_capsule!(Capsule2, Vec2, Segment2, Mat2x3, max_scale_2d);
+impl_capsule!(Capsule3, Vec3, Segment3, Mat3x4, max_scale_3d);
+
+impl Capsule2 {
+	pub fn bounds(&self) -> Aabb2 {
+		self.segment().bounds().grow(self.radius)
+	}
+
+	pub fn area(&self) -> f32 {
+		2.0 * self.radius * self.segment().length() + PI * self.radius * self.radius
+	}
+
+	pub fn perimeter(&self) -> f32 {
+		2.0 * self.segment().length() + TAU * self.radius
+	}
+}
+
+impl Capsule3 {
+	pub fn bounds(&self) -> Aabb3 {
+		self.segment().bounds().grow(self.radius)
+	}
+
+	pub fn volume(&self) -> f32 {
+		let radius_squared = self.radius * self.radius;
+		PI * radius_squared * self.segment().length() + 4.0 / 3.0 * PI * radius_squared * self.radius
+	}
+
+	pub fn surface_area(&self) -> f32 {
+		TAU * self.radius * self.segment().length() + 4.0 * PI * self.radius * self.radius
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn test_capsule2() {
+		let capsule = Capsule2::new(Vec2::zero(), Vec2::from_x(2.0), 1.0);
+		assert_almost_eq!(capsule.area(), 4.0 + PI);
+		assert_almost_eq!(capsule.perimeter(), 4.0 + TAU);
+		assert_vec_eq!(capsule.bounds().min, Vec2::splat(-1.0));
+
+		assert!(capsule.contains_point(Vec2::new(1.0, 0.9)));
+		assert!(capsule.contains_point(Vec2::new(2.5, 0.5)));
+		assert!(!capsule.contains_point(Vec2::new(2.8, 0.8)));
+		assert_vec_eq!(capsule.closest_point(Vec2::new(1.0, 3.0)), Vec2::new(1.0, 1.0));
+		assert_vec_eq!(capsule.closest_point(Vec2::from_x(-5.0)), Vec2::from_x(-1.0));
+
+		let transformed = capsule.transform(&Mat2x3::scale(2.0));
+		assert_almost_eq!(transformed.area(), 4.0 * capsule.area());
+	}
+
+	#[test]
+	fn test_capsule3() {
+		let capsule = Capsule3::new(Vec3::zero(), Vec3::from_y(3.0), 0.5);
+		assert_almost_eq!(capsule.volume(), PI * 0.25 * 3.0 + PI / 6.0);
+		assert_almost_eq!(capsule.surface_area(), 3.0 * PI + PI);
+		assert_vec_eq!(capsule.bounds().max, Vec3::new(0.5, 3.5, 0.5));
+
+		assert!(capsule.contains_point(Vec3::new(0.3, 3.3, 0.0)));
+		assert!(!capsule.contains_point(Vec3::new(0.4, 3.4, 0.0)));
+		assert_vec_eq!(capsule.closest_point(Vec3::new(0.0, 1.0, 2.0)), Vec3::new(0.0, 1.0, 0.5));
+	}
+}
@@ -0,0 +1,165 @@
+use crate::math::*;
+
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Circle {
+	pub center: Vec2,
+	pub radius: f32,
+}
+
+/// Constructors
+impl Circle {
+	pub fn new(center: Vec2, radius: f32) -> Circle {
+		Circle { center, radius }
+	}
+}
+
+/// Properties
+impl Circle {
+	pub fn bounds(&self) -> Aabb2 {
+		Aabb2::from_center_extents(self.center, self.radius)
+	}
+
+	pub fn area(&self) -> f32 {
+		PI * self.radius * self.radius
+	}
+
+	pub fn perimeter(&self) -> f32 {
+		TAU * self.radius
+	}
+}
+
+/// Queries
+impl Circle {
+	pub fn contains_point(&self, point: Vec2) -> bool {
+		(point - self.center).square_length() <= self.radius * self.radius
+	}
+
+	/// The point within the circle closest to `point`, which is `point` itself if it is inside.
+	pub fn closest_point(&self, point: Vec2) -> Vec2 {
+		let offset = point - self.center;
+		let distance = offset.length();
+
+		if distance <= self.radius {
+			point
+		} else {
+			self.center + offset * (self.radius / distance)
+		}
+	}
+}
+
+/// Modification
+impl Circle {
+	/// Non-uniform scales can't be represented, so the radius is scaled by the largest scale factor.
+	pub fn transform(&self, transform: &Mat2x3) -> Circle {
+		Circle::new(*transform * self.center, self.radius * max_scale_2d(transform))
+	}
+}
+
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Sphere {
+	pub center: Vec3,
+	pub radius: f32,
+}
+
+/// Constructors
+impl Sphere {
+	pub fn new(center: Vec3, radius: f32) -> Sphere {
+		Sphere { center, radius }
+	}
+}
+
+/// Properties
+impl Sphere {
+	pub fn bounds(&self) -> Aabb3 {
+		Aabb3::from_center_extents(self.center, self.radius)
+	}
+
+	pub fn volume(&self) -> f32 {
+		4.0 / 3.0 * PI * self.radius.powi(3)
+	}
+
+	pub fn surface_area(&self) -> f32 {
+		4.0 * PI * self.radius * self.radius
+	}
+}
+
+/// Queries
+impl Sphere {
+	pub fn contains_point(&self, point: Vec3) -> bool {
+		(point - self.center).square_length() <= self.radius * self.radius
+	}
+
+	/// The point within the sphere closest to `point`, which is `point` itself if it is inside.
+	pub fn closest_point(&self, point: Vec3) -> Vec3 {
+		let offset = point - self.center;
+		let distance = offset.length();
+
+		if distance <= self.radius {
+			point
+		} else {
+			self.center + offset * (self.radius / distance)
+		}
+	}
+}
+
+/// Modification
+impl Sphere {
+	/// Non-uniform scales can't be represented, so the radius is scaled by the largest scale factor.
+	pub fn transform(&self, transform: &Mat3x4) -> Sphere {
+		Sphere::new(*transform * self.center, self.radius * max_scale_3d(transform))
+	}
+}
+
+
+pub(crate) fn max_scale_2d(transform: &Mat2x3) -> f32 {
+	transform.column_x().length().max(transform.column_y().length())
+}
+
+pub(crate) fn max_scale_3d(transform: &Mat3x4) -> f32 {
+	transform.column_x().length()
+		.max(transform.column_y().length())
+		.max(transform.column_z().length())
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn test_circle() {
+		let circle = Circle::new(Vec2::new(1.0, 2.0), 2.0);
+		assert_almost_eq!(circle.area(), 4.0 * PI);
+		assert_vec_eq!(circle.bounds().min, Vec2::new(-1.0, 0.0));
+
+		assert!(circle.contains_point(Vec2::new(2.0, 3.0)));
+		assert!(!circle.contains_point(Vec2::new(3.0, 4.0)));
+		assert_vec_eq!(circle.closest_point(Vec2::new(1.0, 10.0)), Vec2::new(1.0, 4.0));
+		assert_vec_eq!(circle.closest_point(Vec2::new(1.5, 2.0)), Vec2::new(1.5, 2.0));
+
+		let transformed = circle.transform(&Mat2x3::scale_rotate_translate(Vec2::new(1.0, 3.0), PI, Vec2::from_x(1.0)));
+		assert_vec_eq!(transformed.center, Vec2::new(0.0, -6.0));
+		assert_almost_eq!(transformed.radius, 6.0);
+	}
+
+	#[test]
+	fn test_sphere() {
+		let sphere = Sphere::new(Vec3::one(), 3.0);
+		assert_almost_eq!(sphere.volume(), 36.0 * PI);
+		assert_almost_eq!(sphere.surface_area(), 36.0 * PI);
+		assert_vec_eq!(sphere.bounds().max, Vec3::splat(4.0));
+
+		assert!(sphere.contains_point(Vec3::new(3.0, 3.0, 1.0)));
+		assert!(!sphere.contains_point(Vec3::new(3.0, 3.0, 3.0)));
+		assert_vec_eq!(sphere.closest_point(Vec3::new(1.0, 1.0, -5.0)), Vec3::new(1.0, 1.0, -2.0));
+
+		let transformed = sphere.transform(&Mat3x4::scale_translate(2.0, Vec3::from_y(1.0)));
+		assert_vec_eq!(transformed.center, Vec3::new(2.0, 3.0, 2.0));
+		assert_almost_eq!(transformed.radius, 6.0);
+	}
+}
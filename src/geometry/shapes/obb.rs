@@ -0,0 +1,346 @@
+use crate::math::*;
+
+
+/// An oriented box, with `orientation` rotating its local axes into world space.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obb2 {
+	pub center: Vec2,
+	pub orientation: Mat2,
+	pub extents: Vec2,
+}
+
+/// An oriented box, with `orientation` rotating its local axes into world space.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Obb3 {
+	pub center: Vec3,
+	pub orientation: Quat,
+	pub extents: Vec3,
+}
+
+
+/// Constructors
+impl Obb2 {
+	pub fn new(center: Vec2, orientation: Mat2, extents: Vec2) -> Obb2 {
+		Obb2 { center, orientation, extents }
+	}
+
+	pub fn from_angle(center: Vec2, angle: f32, extents: Vec2) -> Obb2 {
+		Obb2::new(center, Mat2::rotate(angle), extents)
+	}
+
+	pub fn from_aabb(aabb: Aabb2) -> Obb2 {
+		Obb2::new(aabb.center(), Mat2::identity(), aabb.extents())
+	}
+}
+
+/// Properties
+impl Obb2 {
+	/// The local x and y axes in world space.
+	pub fn axes(&self) -> [Vec2; 2] {
+		self.orientation.columns()
+	}
+
+	/// The corners in counter-clockwise order.
+	pub fn corners(&self) -> [Vec2; 4] {
+		let [x, y] = self.axes();
+		let (x, y) = (x * self.extents.x, y * self.extents.y);
+		[self.center - x - y, self.center + x - y, self.center + x + y, self.center - x + y]
+	}
+
+	pub fn bounds(&self) -> Aabb2 {
+		let [x, y] = self.axes();
+		let extents = Vec2::new(
+			x.x.abs() * self.extents.x + y.x.abs() * self.extents.y,
+			x.y.abs() * self.extents.x + y.y.abs() * self.extents.y,
+		);
+
+		Aabb2::from_center_extents(self.center, extents)
+	}
+
+	pub fn area(&self) -> f32 {
+		4.0 * self.extents.x * self.extents.y
+	}
+
+	pub fn perimeter(&self) -> f32 {
+		4.0 * (self.extents.x + self.extents.y)
+	}
+}
+
+/// Queries
+impl Obb2 {
+	/// Converts a world space point into the box's local space, where the box spans `-extents` to `extents`.
+	pub fn to_local(&self, point: Vec2) -> Vec2 {
+		let [x, y] = self.axes();
+		let offset = point - self.center;
+		Vec2::new(offset.dot(x), offset.dot(y))
+	}
+
+	pub fn from_local(&self, point: Vec2) -> Vec2 {
+		self.center + self.orientation * point
+	}
+
+	pub fn contains_point(&self, point: Vec2) -> bool {
+		let local = self.to_local(point);
+		local.x.abs() <= self.extents.x && local.y.abs() <= self.extents.y
+	}
+
+	/// The point within the box closest to `point`, which is `point` itself if it is inside.
+	pub fn closest_point(&self, point: Vec2) -> Vec2 {
+		let local = self.to_local(point);
+		self.from_local(Vec2::new(
+			local.x.clamp(-self.extents.x, self.extents.x),
+			local.y.clamp(-self.extents.y, self.extents.y),
+		))
+	}
+}
+
+/// Modification
+impl Obb2 {
+	/// Shear can't be represented, so the box's axes are kept perpendicular under sheared transforms.
+	pub fn transform(&self, transform: &Mat2x3) -> Obb2 {
+		let axes = self.axes();
+		let linear = |v: Vec2| transform.column_x() * v.x + transform.column_y() * v.y;
+		let unit = axes.map(linear);
+		let (x, y) = (unit[0] * self.extents.x, unit[1] * self.extents.y);
+
+		// Reflections flip the box, which is symmetric anyway. An axis with no direction is rebuilt from the other,
+		// or the box keeps its own axes if neither has one
+		let x_axis = match axis_directions([x, y], unit) {
+			[Some(x), _] => x.normalize(),
+			[None, Some(y)] => -y.normalize().perp(),
+			[None, None] => axes[0],
+		};
+		let y_axis = x_axis.perp();
+		let orientation = Mat2::from_columns([x_axis, y_axis]);
+
+		Obb2::new(*transform * self.center, orientation, Vec2::new(x.length(), y.dot(y_axis).abs()))
+	}
+}
+
+
+/// Constructors
+impl Obb3 {
+	pub fn new(center: Vec3, orientation: Quat, extents: Vec3) -> Obb3 {
+		Obb3 { center, orientation, extents }
+	}
+
+	pub fn from_aabb(aabb: Aabb3) -> Obb3 {
+		Obb3::new(aabb.center(), Quat::identity(), aabb.extents())
+	}
+}
+
+/// Properties
+impl Obb3 {
+	/// The local x, y and z axes in world space.
+	pub fn axes(&self) -> [Vec3; 3] {
+		[self.orientation.right(), self.orientation.up(), self.orientation.backward()]
+	}
+
+	/// The eight corners, with bit 0 of the index selecting +x, bit 1 +y and bit 2 +z in local space.
+	pub fn corners(&self) -> [Vec3; 8] {
+		std::array::from_fn(|i| {
+			let sign = |bit: usize| if i & bit == 0 { -1.0 } else { 1.0 };
+			self.from_local(Vec3::new(sign(1) * self.extents.x, sign(2) * self.extents.y, sign(4) * self.extents.z))
+		})
+	}
+
+	pub fn bounds(&self) -> Aabb3 {
+		let [x, y, z] = self.axes();
+		let extent = |axis: fn(Vec3) -> f32| {
+			axis(x).abs() * self.extents.x + axis(y).abs() * self.extents.y + axis(z).abs() * self.extents.z
+		};
+
+		Aabb3::from_center_extents(self.center, Vec3::new(extent(|v| v.x), extent(|v| v.y), extent(|v| v.z)))
+	}
+
+	pub fn volume(&self) -> f32 {
+		8.0 * self.extents.x * self.extents.y * self.extents.z
+	}
+
+	pub fn surface_area(&self) -> f32 {
+		let Vec3{x, y, z} = self.extents;
+		8.0 * (x * y + y * z + z * x)
+	}
+}
+
+/// Queries
+impl Obb3 {
+	/// Converts a world space point into the box's local space, where the box spans `-extents` to `extents`.
+	pub fn to_local(&self, point: Vec3) -> Vec3 {
+		let [x, y, z] = self.axes();
+		let offset = point - self.center;
+		Vec3::new(offset.dot(x), offset.dot(y), offset.dot(z))
+	}
+
+	pub fn from_local(&self, point: Vec3) -> Vec3 {
+		self.center + self.orientation * point
+	}
+
+	pub fn contains_point(&self, point: Vec3) -> bool {
+		let local = self.to_local(point);
+		local.x.abs() <= self.extents.x
+			&& local.y.abs() <= self.extents.y
+			&& local.z.abs() <= self.extents.z
+	}
+
+	/// The point within the box closest to `point`, which is `point` itself if it is inside.
+	pub fn closest_point(&self, point: Vec3) -> Vec3 {
+		let local = self.to_local(point);
+		self.from_local(Vec3::new(
+			local.x.clamp(-self.extents.x, self.extents.x),
+			local.y.clamp(-self.extents.y, self.extents.y),
+			local.z.clamp(-self.extents.z, self.extents.z),
+		))
+	}
+}
+
+/// Modification
+impl Obb3 {
+	/// Shear can't be represented, so the box's axes are kept perpendicular under sheared transforms.
+	pub fn transform(&self, transform: &Mat3x4) -> Obb3 {
+		let axes = self.axes();
+		let linear = |v: Vec3| transform.column_x() * v.x + transform.column_y() * v.y + transform.column_z() * v.z;
+		let unit = axes.map(linear);
+		let (x, y, z) = (unit[0] * self.extents.x, unit[1] * self.extents.y, unit[2] * self.extents.z);
+
+		// Gram-Schmidt over the axes with a direction, skipping any parallel to those before. Reflections flip the box,
+		// which is symmetric anyway
+		let mut result = axes;
+		let mut found = Vec::with_capacity(3);
+		for (i, direction) in axis_directions([x, y, z], unit).into_iter().enumerate() {
+			let Some(direction) = direction else { continue };
+			let orthogonal = found.iter().fold(direction, |v, &j: &usize| v - result[j] * v.dot(result[j]));
+			if found.len() < 2 && orthogonal.length() > 1.0e-4 * direction.length() {
+				result[i] = orthogonal.normalize();
+				found.push(i);
+			}
+		}
+
+		// With a single axis found, the box's own axes are turned to be perpendicular to it
+		if let [i] = found[..] {
+			let orthogonal = |j: usize| axes[j] - result[i] * axes[j].dot(result[i]);
+			let [j, k] = [(i + 1) % 3, (i + 2) % 3];
+			let j = if orthogonal(j).length() >= orthogonal(k).length() { j } else { k };
+			result[j] = orthogonal(j).normalize();
+			found.push(j);
+		}
+
+		// The last axis completes a right handed basis, unless none had a direction and the box keeps its own
+		if let [i, j] = found[..] {
+			let k = 3 - i - j;
+			result[k] = result[(k + 1) % 3].cross(result[(k + 2) % 3]);
+		}
+
+		let [x_axis, y_axis, z_axis] = result;
+		let orientation = Quat::from_mat3x4(&Mat3x4::from_columns([x_axis, y_axis, z_axis, Vec3::zero()]));
+		let extents = Vec3::new(x.length(), y.dot(y_axis).abs(), z.dot(z_axis).abs());
+
+		Obb3::new(*transform * self.center, orientation, extents)
+	}
+}
+
+// The directions of transformed axes, scaled by the box's extents where that leaves a direction. An axis of a flat
+// box then takes the direction of the transformed unit axis, and one squashed by a degenerate transform has none.
+fn axis_directions<V: FloatVector, const N: usize>(scaled: [V; N], unit: [V; N]) -> [Option<V>; N] {
+	let usable = |vectors: [V; N]| {
+		let largest = vectors.iter().fold(0.0, |largest: f32, v| largest.max(v.length()));
+		vectors.map(|v| v.length() > f32::EPSILON * largest)
+	};
+
+	let (scaled_usable, unit_usable) = (usable(scaled), usable(unit));
+	std::array::from_fn(|i| match (scaled_usable[i], unit_usable[i]) {
+		(true, _) => Some(scaled[i]),
+		(false, true) => Some(unit[i]),
+		(false, false) => None,
+	})
+}
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn test_obb2() {
+		let obb = Obb2::from_angle(Vec2::one(), PI / 4.0, Vec2::new(2.0, 1.0));
+		assert_almost_eq!(obb.area(), 8.0);
+		assert_vec_eq!(obb.bounds().extents(), Vec2::splat(3.0 * INV_SQRT_2));
+
+		let corners = obb.corners();
+		assert!(Polygon2::new(corners.to_vec()).winding() == Winding::CounterClockwise);
+		assert_almost_eq!(Polygon2::new(corners.to_vec()).area(), 8.0);
+
+		assert!(obb.contains_point(Vec2::one() + Vec2::splat(1.0)));
+		assert!(!obb.contains_point(Vec2::one() + Vec2::new(1.0, -1.0)));
+		assert_vec_eq!(obb.closest_point(Vec2::one() + Vec2::new(2.0, -2.0)), Vec2::one() + Vec2::new(INV_SQRT_2, -INV_SQRT_2));
+		assert_vec_eq!(obb.closest_point(Vec2::splat(1.5)), Vec2::splat(1.5));
+
+		let transform = Mat2x3::scale_rotate_translate(Vec2::new(2.0, 3.0), 0.5, Vec2::new(3.0, -1.0));
+		let aabb = Obb2::from_aabb(Aabb2::new(Vec2::zero(), Vec2::new(1.0, 2.0))).transform(&transform);
+		assert_almost_eq!(aabb.area(), 12.0);
+		assert_vec_eq!(aabb.center, transform * Vec2::new(0.5, 1.0));
+
+		let expected = Polygon2::from_aabb(Aabb2::new(Vec2::zero(), Vec2::new(1.0, 2.0))).transform(&transform);
+		for (corner, expected) in aabb.corners().iter().zip(expected.points.iter()) {
+			assert_vec_eq!(*corner, *expected);
+		}
+
+		// Flat boxes keep the direction of their flat axis
+		let flat = Obb2::from_aabb(Aabb2::new(Vec2::new(1.0, 0.0), Vec2::new(1.0, 2.0)));
+		let transformed = flat.transform(&transform);
+		assert_vec_eq!(transformed.extents, Vec2::new(0.0, 3.0));
+		for (corner, transformed_corner) in flat.corners().iter().zip(transformed.corners().iter()) {
+			assert_vec_eq!(transform * *corner, *transformed_corner);
+		}
+
+		// Degenerate transforms squash the box rather than leaving it without axes
+		let squashed = obb.transform(&Mat2x3::scale(Vec2::new(1.0, 0.0)));
+		assert!(squashed.axes().iter().all(|axis| axis.x.is_finite() && axis.y.is_finite()));
+		assert_almost_eq!(squashed.extents.y, 0.0);
+		assert_vec_eq!(obb.transform(&Mat2x3::scale(0.0)).extents, Vec2::zero());
+	}
+
+	#[test]
+	fn test_obb3() {
+		let orientation = Quat::from_axis_angle(Vec3::new(1.0, 1.0, 0.0).normalize(), 0.7);
+		let obb = Obb3::new(Vec3::from_z(2.0), orientation, Vec3::new(1.0, 2.0, 3.0));
+		assert_almost_eq!(obb.volume(), 48.0);
+		assert_almost_eq!(obb.surface_area(), 88.0);
+
+		let bounds = obb.bounds();
+		for corner in obb.corners() {
+			assert!(bounds.grow(0.001).contains_point(corner));
+			assert!(obb.contains_point(obb.center + (corner - obb.center) * 0.99));
+			assert!(!obb.contains_point(obb.center + (corner - obb.center) * 1.01));
+		}
+
+		let far = obb.from_local(Vec3::new(5.0, 0.5, -5.0));
+		assert_vec_eq!(obb.closest_point(far), obb.from_local(Vec3::new(1.0, 0.5, -3.0)));
+
+		let transform = Mat3x4::scale_rotate_translate(2.0, Quat::from_yaw(1.0), Vec3::one());
+		let transformed = obb.transform(&transform);
+		assert_almost_eq!(transformed.volume(), 8.0 * obb.volume());
+		for (corner, transformed_corner) in obb.corners().iter().zip(transformed.corners().iter()) {
+			assert_vec_eq!(transform * *corner, *transformed_corner);
+		}
+
+		// Flat boxes keep the direction of their flat axes
+		for max in [Vec3::new(1.0, 2.0, 3.0), Vec3::new(1.0, 2.0, 0.0), Vec3::new(1.0, 0.0, 0.0)] {
+			let flat = Obb3::from_aabb(Aabb3::new(Vec3::from_x(1.0), max));
+			let transformed = flat.transform(&transform);
+			assert_vec_eq!(transformed.extents, max - Vec3::from_x(1.0));
+			for (corner, transformed_corner) in flat.corners().iter().zip(transformed.corners().iter()) {
+				assert_vec_eq!(transform * *corner, *transformed_corner);
+			}
+		}
+
+		// Degenerate transforms squash the box rather than leaving it without axes
+		for scale in [Vec3::new(1.0, 0.0, 1.0), Vec3::new(0.0, 0.0, 2.0), Vec3::zero()] {
+			let squashed = obb.transform(&Mat3x4::scale(scale));
+			assert!(squashed.axes().iter().all(|axis| axis.x.is_finite() && axis.y.is_finite() && axis.z.is_finite()));
+			assert_almost_eq!(squashed.volume(), 0.0);
+		}
+	}
+}
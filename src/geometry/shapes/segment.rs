@@ -0,0 +1,205 @@
+use crate::math::*;
+
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segment2 {
+	pub start: Vec2,
+	pub end: Vec2,
+}
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segment3 {
+	pub start: Vec3,
+	pub end: Vec3,
+}
+
+macro_rules! impl_segment {
+	($ty:ident, $vec:ident, $aabb:ident, $mat:ident) => {
+		/// Constructors
+		impl $ty {
+			pub fn new(start: $vec, end: $vec) -> $ty {
+				$ty { start, end }
+			}
+		}
+
+		/// Properties
+		impl $ty {
+			pub fn bounds(&self) -> $aabb {
+				$aabb::from_point(self.start).include_point(self.end)
+			}
+
+			pub fn length(&self) -> f32 {
+				(self.end - self.start).length()
+			}
+
+			pub fn direction(&self) -> $vec {
+				self.end - self.start
+			}
+
+			pub fn center(&self) -> $vec {
+				(self.start + self.end) / 2.0
+			}
+
+			pub fn point_at(&self, t: f32) -> $vec {
+				t.lerp(self.start, self.end)
+			}
+		}
+
+		/// Queries
+		impl $ty {
+			/// The parameter of the point along the segment closest to `point`, between 0 and 1.
+			pub fn closest_t(&self, point: $vec) -> f32 {
+				let direction = self.direction();
+				let square_length = direction.square_length();
+				if square_length <= 0.0 {
+					return 0.0;
+				}
+
+				((point - self.start).dot(direction) / square_length).clamp(0.0, 1.0)
+			}
+
+			pub fn closest_point(&self, point: $vec) -> $vec {
+				self.point_at(self.closest_t(point))
+			}
+
+			pub fn distance_to(&self, point: $vec) -> f32 {
+				(point - self.closest_point(point)).length()
+			}
+
+			/// Whether `point` lies on the segment, allowing for rounding errors.
+			pub fn contains_point(&self, point: $vec) -> bool {
+				self.distance_to(point) <= 1.0e-5 * self.length().max(1.0)
+			}
+
+			/// The parameters of the closest points on this segment and on `other`, each between 0 and 1.
+			pub fn closest_ts(&self, other: &$ty) -> (f32, f32) {
+				closest_segment_ts(self.start, self.direction(), other.start, other.direction())
+			}
+
+			/// The closest points on this segment and on `other`, which are the same if they cross.
+			pub fn closest_points(&self, other: &$ty) -> ($vec, $vec) {
+				let (s, t) = self.closest_ts(other);
+				(self.point_at(s), other.point_at(t))
+			}
+
+			pub fn distance_to_segment(&self, other: &$ty) -> f32 {
+				let (a, b) = self.closest_points(other);
+				(b - a).length()
+			}
+		}
+
+		/// Modification
+		impl $ty {
+			pub fn transform(&self, transform: &$mat) -> $ty {
+				$ty::new(*transform * self.start, *transform * self.end)
+			}
+		}
+	}
+}
+
+impl_segment!(Segment2, Vec2, Aabb2, Mat2x3);
+impl_segment!(Segment3, Vec3, Aabb3, Mat3x4);
+
+
+// Clamps the closest points of the two infinite lines to the segments, reclamping the first if the second is clamped.
+// From Real-Time Collision Detection, section 5.1.9.
+fn closest_segment_ts<V: FloatVector>(start_a: V, direction_a: V, start_b: V, direction_b: V) -> (f32, f32) {
+	let offset = start_a - start_b;
+	let (a, e) = (direction_a.square_length(), direction_b.square_length());
+	let f = direction_b.dot(offset);
+
+	if a <= 0.0 && e <= 0.0 {
+		return (0.0, 0.0);
+	}
+
+	if a <= 0.0 {
+		return (0.0, (f / e).clamp(0.0, 1.0));
+	}
+
+	let c = direction_a.dot(offset);
+	if e <= 0.0 {
+		return ((-c / a).clamp(0.0, 1.0), 0.0);
+	}
+
+	// Parallel segments have many closest points, so start from the start of the first
+	let b = direction_a.dot(direction_b);
+	let denominator = a * e - b * b;
+	let s = if denominator > f32::EPSILON * a * e { ((b * f - c * e) / denominator).clamp(0.0, 1.0) } else { 0.0 };
+
+	let t = (b * s + f) / e;
+	if t < 0.0 {
+		((-c / a).clamp(0.0, 1.0), 0.0)
+	} else if t > 1.0 {
+		(((b - c) / a).clamp(0.0, 1.0), 1.0)
+	} else {
+		(s, t)
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn test_segment2() {
+		let segment = Segment2::new(Vec2::zero(), Vec2::new(4.0, 0.0));
+		assert_almost_eq!(segment.length(), 4.0);
+		assert_almost_eq!(segment.closest_t(Vec2::new(1.0, 5.0)), 0.25);
+		assert_vec_eq!(segment.closest_point(Vec2::new(-3.0, 1.0)), Vec2::zero());
+		assert_almost_eq!(segment.distance_to(Vec2::new(6.0, 0.0)), 2.0);
+
+		assert!(segment.contains_point(Vec2::from_x(3.0)));
+		assert!(!segment.contains_point(Vec2::new(3.0, 0.1)));
+		assert!(!segment.contains_point(Vec2::from_x(5.0)));
+
+		// Crossing, then apart
+		let crossing = Segment2::new(Vec2::new(1.0, -1.0), Vec2::new(3.0, 1.0));
+		assert_almost_eq!(segment.distance_to_segment(&crossing), 0.0);
+		assert_vec_eq!(segment.closest_points(&crossing).0, Vec2::from_x(2.0));
+		let (a, b) = segment.closest_points(&Segment2::new(Vec2::new(5.0, 1.0), Vec2::new(7.0, 3.0)));
+		assert_vec_eq!(a, Vec2::from_x(4.0));
+		assert_vec_eq!(b, Vec2::new(5.0, 1.0));
+
+		let transformed = segment.transform(&Mat2x3::rotate_translate(PI / 2.0, Vec2::one()));
+		assert_vec_eq!(transformed.end, Vec2::new(1.0, 5.0));
+		assert_vec_eq!(transformed.bounds().min, Vec2::one());
+	}
+
+	#[test]
+	fn test_segment3() {
+		let segment = Segment3::new(Vec3::one(), Vec3::new(1.0, 1.0, 3.0));
+		assert_vec_eq!(segment.closest_point(Vec3::new(5.0, 5.0, 2.0)), Vec3::new(1.0, 1.0, 2.0));
+		assert_vec_eq!(segment.bounds().max, Vec3::new(1.0, 1.0, 3.0));
+
+		// Degenerate segments behave like points
+		let point = Segment3::new(Vec3::one(), Vec3::one());
+		assert_vec_eq!(point.closest_point(Vec3::zero()), Vec3::one());
+		assert!(point.contains_point(Vec3::one()));
+		assert_vec_eq!(segment.closest_points(&point).0, Vec3::one());
+		assert_almost_eq!(point.distance_to_segment(&point), 0.0);
+	}
+
+	#[test]
+	fn test_closest_points() {
+		let a = Segment3::new(Vec3::zero(), Vec3::from_x(4.0));
+
+		// Skew segments whose closest points are inside both
+		let (s, t) = a.closest_ts(&Segment3::new(Vec3::new(1.0, -1.0, 2.0), Vec3::new(1.0, 1.0, 2.0)));
+		assert_almost_eq!(s, 0.25);
+		assert_almost_eq!(t, 0.5);
+
+		// Clamped to an end of each
+		let (p, q) = a.closest_points(&Segment3::new(Vec3::new(5.0, 1.0, 0.0), Vec3::new(6.0, 3.0, 0.0)));
+		assert_vec_eq!(p, Vec3::from_x(4.0));
+		assert_vec_eq!(q, Vec3::new(5.0, 1.0, 0.0));
+
+		// Parallel and overlapping
+		let parallel = Segment3::new(Vec3::new(2.0, 1.0, 0.0), Vec3::new(6.0, 1.0, 0.0));
+		assert_almost_eq!(a.distance_to_segment(&parallel), 1.0);
+		assert_almost_eq!(parallel.distance_to_segment(&a), 1.0);
+	}
+}
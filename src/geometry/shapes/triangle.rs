@@ -0,0 +1,520 @@
+use crate::math::*;
+use crate::geometry::shapes::segment::Segment3;
+
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Triangle2 {
+	pub a: Vec2,
+	pub b: Vec2,
+	pub c: Vec2,
+}
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Triangle3 {
+	pub a: Vec3,
+	pub b: Vec3,
+	pub c: Vec3,
+}
+
+/// Constructors
+impl Triangle2 {
+	pub fn new(a: Vec2, b: Vec2, c: Vec2) -> Triangle2 {
+		Triangle2 { a, b, c }
+	}
+}
+
+/// Properties
+impl Triangle2 {
+	pub fn points(&self) -> [Vec2; 3] {
+		[self.a, self.b, self.c]
+	}
+
+	pub fn bounds(&self) -> Aabb2 {
+		Aabb2::from_points(&self.points())
+	}
+
+	/// Positive for counter-clockwise triangles.
+	pub fn signed_area(&self) -> f32 {
+		(self.b - self.a).wedge(self.c - self.a) / 2.0
+	}
+
+	pub fn area(&self) -> f32 {
+		self.signed_area().abs()
+	}
+
+	pub fn centroid(&self) -> Vec2 {
+		(self.a + self.b + self.c) / 3.0
+	}
+
+	/// The center of the circle through all three points, or `None` if they are collinear.
+	pub fn circumcenter(&self) -> Option<Vec2> {
+		let (ab, ac) = (self.b - self.a, self.c - self.a);
+		let (ab_squared, ac_squared) = (ab.square_length(), ac.square_length());
+		let denominator = 2.0 * ab.wedge(ac);
+		if denominator * denominator <= 4.0 * f32::EPSILON * ab_squared * ac_squared {
+			return None;
+		}
+
+		let offset = Vec2::new(ac.y * ab_squared - ab.y * ac_squared, ab.x * ac_squared - ac.x * ab_squared);
+		Some(self.a + offset / denominator)
+	}
+
+	/// The center of the largest circle that fits inside the triangle.
+	pub fn incenter(&self) -> Vec2 {
+		let weights = incenter_weights(self.a.distance(self.b), self.b.distance(self.c), self.c.distance(self.a));
+		self.from_barycentric(weights)
+	}
+}
+
+/// Queries
+impl Triangle2 {
+	/// The weights of `a`, `b` and `c` that sum to `point`, or `None` if the triangle is degenerate.
+	/// Weights are negative for points outside the triangle.
+	pub fn barycentric(&self, point: Vec2) -> Option<Vec3> {
+		let (ab, ac, ap) = (self.b - self.a, self.c - self.a, point - self.a);
+		let denominator = ab.wedge(ac);
+		if denominator * denominator <= f32::EPSILON * ab.square_length() * ac.square_length() {
+			return None;
+		}
+
+		let (v, w) = (ap.wedge(ac) / denominator, ab.wedge(ap) / denominator);
+		Some(Vec3::new(1.0 - v - w, v, w))
+	}
+
+	pub fn from_barycentric(&self, weights: Vec3) -> Vec2 {
+		self.a * weights.x + self.b * weights.y + self.c * weights.z
+	}
+
+	/// Blends the `values` at `a`, `b` and `c` at `point`, or `None` if the triangle is degenerate.
+	pub fn interpolate<T>(&self, point: Vec2, values: [T; 3]) -> Option<T>
+		where f32: Lerp<T>
+	{
+		self.barycentric(point).map(|weights| lerp_barycentric(weights, values))
+	}
+
+	/// Whether `point` is inside or on the edge of the triangle, regardless of winding.
+	pub fn contains_point(&self, point: Vec2) -> bool {
+		let sides = [
+			(self.b - self.a).wedge(point - self.a),
+			(self.c - self.b).wedge(point - self.b),
+			(self.a - self.c).wedge(point - self.c),
+		];
+
+		sides.iter().all(|&side| side >= 0.0) || sides.iter().all(|&side| side <= 0.0)
+	}
+
+	/// The point within the triangle closest to `point`, which is `point` itself if it is inside.
+	pub fn closest_point(&self, point: Vec2) -> Vec2 {
+		if self.contains_point(point) {
+			return point;
+		}
+
+		self.from_barycentric(self.closest_barycentric(point))
+	}
+
+	/// The weights of `a`, `b` and `c` that give the point on the triangle closest to `point`.
+	pub fn closest_barycentric(&self, point: Vec2) -> Vec3 {
+		closest_barycentric(self.a.extend(0.0), self.b.extend(0.0), self.c.extend(0.0), point.extend(0.0))
+	}
+}
+
+/// Modification
+impl Triangle2 {
+	pub fn transform(&self, transform: &Mat2x3) -> Triangle2 {
+		let [a, b, c] = self.points().map(|point| *transform * point);
+		Triangle2::new(a, b, c)
+	}
+}
+
+
+/// Constructors
+impl Triangle3 {
+	pub fn new(a: Vec3, b: Vec3, c: Vec3) -> Triangle3 {
+		Triangle3 { a, b, c }
+	}
+}
+
+/// Properties
+impl Triangle3 {
+	pub fn points(&self) -> [Vec3; 3] {
+		[self.a, self.b, self.c]
+	}
+
+	pub fn bounds(&self) -> Aabb3 {
+		Aabb3::from_points(&self.points())
+	}
+
+	pub fn area(&self) -> f32 {
+		(self.b - self.a).cross(self.c - self.a).length() / 2.0
+	}
+
+	/// The unit normal, facing the side from which the triangle appears counter-clockwise.
+	pub fn normal(&self) -> Vec3 {
+		(self.b - self.a).cross(self.c - self.a).normalize()
+	}
+
+	pub fn plane(&self) -> Plane3 {
+		Plane3::from_points(self.a, self.b, self.c)
+	}
+
+	pub fn centroid(&self) -> Vec3 {
+		(self.a + self.b + self.c) / 3.0
+	}
+
+	/// The center of the circle through all three points, or `None` if they are collinear.
+	pub fn circumcenter(&self) -> Option<Vec3> {
+		let (ab, ac) = (self.b - self.a, self.c - self.a);
+		let (ab_squared, ac_squared) = (ab.square_length(), ac.square_length());
+		let normal = ab.cross(ac);
+		let denominator = 2.0 * normal.square_length();
+		if denominator <= 2.0 * f32::EPSILON * ab_squared * ac_squared {
+			return None;
+		}
+
+		let offset = normal.cross(ab) * ac_squared + ac.cross(normal) * ab_squared;
+		Some(self.a + offset / denominator)
+	}
+
+	/// The center of the largest circle that fits inside the triangle.
+	pub fn incenter(&self) -> Vec3 {
+		let weights = incenter_weights(self.a.distance(self.b), self.b.distance(self.c), self.c.distance(self.a));
+		self.from_barycentric(weights)
+	}
+
+	/// The tangent, bitangent and normal as columns, with the tangent and bitangent following the u and v directions
+	/// of the texture coordinates `uvs` at `a`, `b` and `c`. Returns `None` if the texture coordinates are degenerate.
+	/// The tangent is made perpendicular to the normal, and the bitangent is flipped if the texture is mirrored.
+	pub fn tangent_frame(&self, uvs: [Vec2; 3]) -> Option<Mat3> {
+		let (ab, ac) = (self.b - self.a, self.c - self.a);
+		let (uv_ab, uv_ac) = (uvs[1] - uvs[0], uvs[2] - uvs[0]);
+		let denominator = uv_ab.wedge(uv_ac);
+		if denominator * denominator <= f32::EPSILON * uv_ab.square_length() * uv_ac.square_length() {
+			return None;
+		}
+
+		let tangent = (ab * uv_ac.y - ac * uv_ab.y) / denominator;
+		let bitangent = (ac * uv_ab.x - ab * uv_ac.x) / denominator;
+
+		let normal = self.normal();
+		let tangent = (tangent - normal * normal.dot(tangent)).normalize();
+		let handedness = if normal.cross(tangent).dot(bitangent) < 0.0 { -1.0 } else { 1.0 };
+
+		Some(Mat3::from_columns([tangent, normal.cross(tangent) * handedness, normal]))
+	}
+}
+
+/// Queries
+impl Triangle3 {
+	/// The weights of `a`, `b` and `c` that sum to `point` projected onto the triangle's plane,
+	/// or `None` if the triangle is degenerate. Weights are negative for points outside the triangle.
+	pub fn barycentric(&self, point: Vec3) -> Option<Vec3> {
+		let (ab, ac, ap) = (self.b - self.a, self.c - self.a, point - self.a);
+		let (d00, d01, d11) = (ab.dot(ab), ab.dot(ac), ac.dot(ac));
+		let (d20, d21) = (ap.dot(ab), ap.dot(ac));
+
+		let denominator = d00 * d11 - d01 * d01;
+		if denominator <= f32::EPSILON * d00 * d11 {
+			return None;
+		}
+
+		let v = (d11 * d20 - d01 * d21) / denominator;
+		let w = (d00 * d21 - d01 * d20) / denominator;
+		Some(Vec3::new(1.0 - v - w, v, w))
+	}
+
+	pub fn from_barycentric(&self, weights: Vec3) -> Vec3 {
+		self.a * weights.x + self.b * weights.y + self.c * weights.z
+	}
+
+	/// Blends the `values` at `a`, `b` and `c` at `point` projected onto the triangle's plane,
+	/// or `None` if the triangle is degenerate.
+	pub fn interpolate<T>(&self, point: Vec3, values: [T; 3]) -> Option<T>
+		where f32: Lerp<T>
+	{
+		self.barycentric(point).map(|weights| lerp_barycentric(weights, values))
+	}
+
+	/// Whether `point` lies on the triangle, allowing for rounding errors.
+	pub fn contains_point(&self, point: Vec3) -> bool {
+		let scale = (self.b - self.a).length().max((self.c - self.a).length()).max(1.0);
+		(self.closest_point(point) - point).length() <= 1.0e-5 * scale
+	}
+
+	pub fn closest_point(&self, point: Vec3) -> Vec3 {
+		self.from_barycentric(self.closest_barycentric(point))
+	}
+
+	/// The weights of `a`, `b` and `c` that give the point on the triangle closest to `point`.
+	pub fn closest_barycentric(&self, point: Vec3) -> Vec3 {
+		closest_barycentric(self.a, self.b, self.c, point)
+	}
+
+	/// The closest points on the triangle and on `segment`, which are the same if the segment passes through it.
+	pub fn closest_points_to_segment(&self, segment: &Segment3) -> (Vec3, Vec3) {
+		let (start_side, end_side) = (self.plane().distance_to(segment.start), self.plane().distance_to(segment.end));
+		let ends = [segment.start, segment.end].map(|end| (self.closest_point(end), end));
+		let edges = [(self.a, self.b), (self.b, self.c), (self.c, self.a)]
+			.map(|(from, to)| Segment3::new(from, to).closest_points(segment));
+
+		// Where the segment crosses the plane, which is on the triangle unless an edge is closer
+		let crossing = ((start_side <= 0.0) != (end_side <= 0.0)).then(|| {
+			let crossing = segment.point_at(start_side / (start_side - end_side));
+			(self.closest_point(crossing), crossing)
+		});
+
+		ends.into_iter()
+			.chain(crossing)
+			.chain(edges)
+			.min_by(|&(a, b), &(c, d)| (b - a).square_length().total_cmp(&(d - c).square_length()))
+			.unwrap()
+	}
+}
+
+/// Modification
+impl Triangle3 {
+	pub fn transform(&self, transform: &Mat3x4) -> Triangle3 {
+		let [a, b, c] = self.points().map(|point| *transform * point);
+		Triangle3::new(a, b, c)
+	}
+}
+
+
+/// Blends three `values` by barycentric `weights` that sum to 1, such as those from [`Triangle3::barycentric`].
+pub fn lerp_barycentric<T>(weights: Vec3, values: [T; 3]) -> T
+	where f32: Lerp<T>
+{
+	// Blend the pair with the largest combined weight first, which is at least 2/3 since the weights sum to 1,
+	// and then blend the result towards the remaining value.
+	let [a, b, c] = values;
+	let Vec3 { x, y, z } = weights;
+	if x + y >= y + z && x + y >= x + z {
+		z.lerp((y / (x + y)).lerp(a, b), c)
+	} else if y + z >= x + z {
+		x.lerp((z / (y + z)).lerp(b, c), a)
+	} else {
+		y.lerp((z / (x + z)).lerp(a, c), b)
+	}
+}
+
+// Each vertex is weighted by the length of the opposite side.
+fn incenter_weights(ab: f32, bc: f32, ca: f32) -> Vec3 {
+	let perimeter = ab + bc + ca;
+	if perimeter <= 0.0 {
+		return Vec3::from_x(1.0);
+	}
+
+	Vec3::new(bc, ca, ab) / perimeter
+}
+
+// Finds which feature of the triangle is closest by checking the point against each vertex and edge region in turn,
+// returning the weights of `a`, `b` and `c` for the closest point. From Real-Time Collision Detection, section 5.1.5.
+fn closest_barycentric(a: Vec3, b: Vec3, c: Vec3, point: Vec3) -> Vec3 {
+	let (ab, ac) = (b - a, c - a);
+
+	let ap = point - a;
+	let (d1, d2) = (ab.dot(ap), ac.dot(ap));
+	if d1 <= 0.0 && d2 <= 0.0 {
+		return Vec3::from_x(1.0);
+	}
+
+	let bp = point - b;
+	let (d3, d4) = (ab.dot(bp), ac.dot(bp));
+	if d3 >= 0.0 && d4 <= d3 {
+		return Vec3::from_y(1.0);
+	}
+
+	let vc = d1 * d4 - d3 * d2;
+	if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+		let t = d1 / (d1 - d3);
+		return Vec3::new(1.0 - t, t, 0.0);
+	}
+
+	let cp = point - c;
+	let (d5, d6) = (ab.dot(cp), ac.dot(cp));
+	if d6 >= 0.0 && d5 <= d6 {
+		return Vec3::from_z(1.0);
+	}
+
+	let vb = d5 * d2 - d1 * d6;
+	if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+		let t = d2 / (d2 - d6);
+		return Vec3::new(1.0 - t, 0.0, t);
+	}
+
+	let va = d3 * d6 - d5 * d4;
+	if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+		let t = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+		return Vec3::new(0.0, 1.0 - t, t);
+	}
+
+	let denominator = va + vb + vc;
+	if denominator <= 0.0 {
+		// Degenerate triangles are handled by the edge regions above, except for when all points coincide
+		return Vec3::from_x(1.0);
+	}
+
+	let (v, w) = (vb / denominator, vc / denominator);
+	Vec3::new(1.0 - v - w, v, w)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn test_triangle2() {
+		let triangle = Triangle2::new(Vec2::zero(), Vec2::new(4.0, 0.0), Vec2::new(0.0, 4.0));
+		assert_almost_eq!(triangle.signed_area(), 8.0);
+		assert_almost_eq!(Triangle2::new(triangle.a, triangle.c, triangle.b).signed_area(), -8.0);
+
+		assert!(triangle.contains_point(Vec2::one()));
+		assert!(triangle.contains_point(Vec2::new(2.0, 2.0)));
+		assert!(!triangle.contains_point(Vec2::new(2.5, 2.5)));
+
+		assert_vec_eq!(triangle.closest_point(Vec2::one()), Vec2::one());
+		assert_vec_eq!(triangle.closest_point(Vec2::new(3.0, 3.0)), Vec2::new(2.0, 2.0));
+		assert_vec_eq!(triangle.closest_point(Vec2::new(-1.0, -1.0)), Vec2::zero());
+		assert_vec_eq!(triangle.closest_point(Vec2::new(2.0, -3.0)), Vec2::from_x(2.0));
+
+		let transformed = triangle.transform(&Mat2x3::scale_translate(0.5, Vec2::one()));
+		assert_almost_eq!(transformed.area(), 2.0);
+		assert_vec_eq!(transformed.bounds().max, Vec2::splat(3.0));
+	}
+
+	#[test]
+	fn test_triangle3() {
+		let triangle = Triangle3::new(Vec3::zero(), Vec3::from_x(2.0), Vec3::from_y(2.0));
+		assert_almost_eq!(triangle.area(), 2.0);
+		assert_vec_eq!(triangle.normal(), Vec3::from_z(1.0));
+		assert_almost_eq!(triangle.plane().distance_to(Vec3::from_z(3.0)), 3.0);
+
+		assert_vec_eq!(triangle.closest_point(Vec3::new(0.5, 0.5, 5.0)), Vec3::new(0.5, 0.5, 0.0));
+		assert_vec_eq!(triangle.closest_point(Vec3::new(3.0, 3.0, 1.0)), Vec3::new(1.0, 1.0, 0.0));
+		assert_vec_eq!(triangle.closest_point(Vec3::new(5.0, -1.0, 0.0)), Vec3::from_x(2.0));
+
+		assert!(triangle.contains_point(Vec3::new(0.5, 0.5, 0.0)));
+		assert!(!triangle.contains_point(Vec3::new(0.5, 0.5, 0.1)));
+
+		let transformed = triangle.transform(&Mat3x4::rotate_x(PI / 2.0));
+		assert_vec_eq!(transformed.normal(), Vec3::from_y(-1.0));
+	}
+
+	#[test]
+	fn test_closest_points() {
+		let triangle = Triangle3::new(Vec3::zero(), Vec3::from_x(2.0), Vec3::from_y(2.0));
+		assert_vec_eq!(triangle.closest_barycentric(Vec3::new(0.5, 0.5, 1.0)), Vec3::new(0.5, 0.25, 0.25));
+		assert_vec_eq!(triangle.closest_barycentric(Vec3::new(3.0, 3.0, 0.0)), Vec3::new(0.0, 0.5, 0.5));
+		assert_vec_eq!(triangle.closest_barycentric(Vec3::from_x(-1.0)), Vec3::from_x(1.0));
+
+		// Segment passing through the triangle
+		let (a, b) = triangle.closest_points_to_segment(&Segment3::new(Vec3::new(0.5, 0.5, -1.0), Vec3::new(0.5, 0.5, 3.0)));
+		assert_vec_eq!(a, Vec3::new(0.5, 0.5, 0.0));
+		assert_vec_eq!(b, a);
+
+		// Segment above the triangle, parallel to it
+		let (a, b) = triangle.closest_points_to_segment(&Segment3::new(Vec3::new(0.5, 0.5, 2.0), Vec3::new(1.0, 0.5, 2.0)));
+		assert_almost_eq!(a.z, 0.0);
+		assert_almost_eq!((b - a).length(), 2.0);
+
+		// Segment beyond the hypotenuse, crossing the plane outside the triangle
+		let (a, b) = triangle.closest_points_to_segment(&Segment3::new(Vec3::new(2.0, 2.0, -1.0), Vec3::new(2.0, 2.0, 1.0)));
+		assert_vec_eq!(a, Vec3::new(1.0, 1.0, 0.0));
+		assert_vec_eq!(b, Vec3::new(2.0, 2.0, 0.0));
+
+		// Segment crossing over an edge
+		let (a, b) = triangle.closest_points_to_segment(&Segment3::new(Vec3::new(1.0, -1.0, 1.0), Vec3::new(1.0, 1.0, 1.0)));
+		assert_almost_eq!((b - a).length(), 1.0);
+		assert_almost_eq!(a.z, 0.0);
+
+		let triangle2 = Triangle2::new(Vec2::zero(), Vec2::from_x(2.0), Vec2::from_y(2.0));
+		assert_vec_eq!(triangle2.closest_barycentric(Vec2::new(1.0, -1.0)), Vec3::new(0.5, 0.5, 0.0));
+	}
+
+	#[test]
+	fn test_barycentric() {
+		let triangle = Triangle2::new(Vec2::zero(), Vec2::from_x(4.0), Vec2::from_y(4.0));
+		assert_vec_eq!(triangle.barycentric(Vec2::new(1.0, 2.0)).unwrap(), Vec3::new(0.25, 0.25, 0.5));
+		assert_vec_eq!(triangle.barycentric(Vec2::new(-4.0, 0.0)).unwrap(), Vec3::new(2.0, -1.0, 0.0));
+		assert_vec_eq!(triangle.from_barycentric(Vec3::new(0.25, 0.25, 0.5)), Vec2::new(1.0, 2.0));
+		assert!(Triangle2::new(Vec2::zero(), Vec2::one(), Vec2::splat(2.0)).barycentric(Vec2::zero()).is_none());
+
+		// Tiny triangles aren't degenerate
+		let tiny = Triangle2::new(Vec2::zero(), Vec2::from_x(4.0e-5), Vec2::from_y(4.0e-5));
+		assert_vec_eq!(tiny.barycentric(Vec2::new(1.0e-5, 2.0e-5)).unwrap(), Vec3::new(0.25, 0.25, 0.5));
+
+		let triangle = Triangle3::new(Vec3::from_x(1.0), Vec3::from_y(1.0), Vec3::from_z(1.0));
+		let point = Vec3::new(0.2, 0.3, 0.5);
+		let weights = triangle.barycentric(point + triangle.normal()).unwrap();
+		assert_vec_eq!(weights, point);
+		assert_vec_eq!(triangle.from_barycentric(weights), point);
+		assert_vec_eq!(triangle.barycentric(Vec3::new(2.0, 0.0, -1.0)).unwrap(), Vec3::new(2.0, 0.0, -1.0));
+
+		for (x, y) in [(0.1, 0.2), (0.9, 0.05), (-1.0, 3.0), (2.0, -0.5), (0.0, 0.0)] {
+			let point = Vec2::new(x, y);
+			let uv = triangle.interpolate(Vec3::new(1.0 - x - y, x, y), [Vec2::zero(), Vec2::from_x(1.0), Vec2::from_y(1.0)]).unwrap();
+			assert_vec_eq!(uv, point);
+		}
+
+		let value = Triangle2::new(Vec2::zero(), Vec2::from_x(2.0), Vec2::from_y(2.0))
+			.interpolate(Vec2::one(), [0.0, 4.0, 8.0])
+			.unwrap();
+		assert_almost_eq!(value, 6.0);
+	}
+
+	#[test]
+	fn test_centers() {
+		let triangle = Triangle2::new(Vec2::zero(), Vec2::from_x(4.0), Vec2::from_y(3.0));
+		assert_vec_eq!(triangle.circumcenter().unwrap(), Vec2::new(2.0, 1.5));
+		assert_vec_eq!(triangle.incenter(), Vec2::one());
+		assert!(Triangle2::new(Vec2::zero(), Vec2::one(), Vec2::splat(3.0)).circumcenter().is_none());
+		assert!(Triangle2::new(Vec2::zero(), Vec2::splat(1.0e-5), Vec2::splat(3.0e-5)).circumcenter().is_none());
+
+		let tiny = Triangle2::new(Vec2::zero(), Vec2::from_x(4.0e-5), Vec2::from_y(3.0e-5));
+		assert_vec_eq!(tiny.circumcenter().unwrap() * 1.0e5, Vec2::new(2.0, 1.5));
+
+		let triangle = Triangle3::new(Vec3::zero(), Vec3::from_z(4.0), Vec3::from_y(3.0));
+		assert_vec_eq!(triangle.circumcenter().unwrap(), Vec3::new(0.0, 1.5, 2.0));
+		assert_vec_eq!(triangle.incenter(), Vec3::new(0.0, 1.0, 1.0));
+
+		let tiny = Triangle3::new(Vec3::zero(), Vec3::from_z(4.0e-3), Vec3::from_y(3.0e-3));
+		assert_vec_eq!(tiny.circumcenter().unwrap() * 1.0e3, Vec3::new(0.0, 1.5, 2.0));
+		assert!(Triangle3::new(Vec3::zero(), Vec3::splat(1.0e-3), Vec3::splat(3.0e-3)).circumcenter().is_none());
+
+		let triangle = Triangle3::new(Vec3::new(1.0, 2.0, 3.0), Vec3::new(-2.0, 0.5, 1.0), Vec3::new(0.0, -1.0, 4.0));
+		let center = triangle.circumcenter().unwrap();
+		assert_almost_eq!(center.distance(triangle.a), center.distance(triangle.b));
+		assert_almost_eq!(center.distance(triangle.a), center.distance(triangle.c));
+		assert_almost_eq!(triangle.plane().distance_to(center), 0.0);
+	}
+
+	#[test]
+	fn test_tangent_frame() {
+		let triangle = Triangle3::new(Vec3::zero(), Vec3::from_x(2.0), Vec3::from_y(2.0));
+		let frame = triangle.tangent_frame([Vec2::zero(), Vec2::from_x(1.0), Vec2::from_y(1.0)]).unwrap();
+		assert_vec_eq!(frame.column_x(), Vec3::from_x(1.0));
+		assert_vec_eq!(frame.column_y(), Vec3::from_y(1.0));
+		assert_vec_eq!(frame.column_z(), Vec3::from_z(1.0));
+
+		// Texture rotated a quarter turn
+		let frame = triangle.tangent_frame([Vec2::zero(), Vec2::from_y(-1.0), Vec2::from_x(1.0)]).unwrap();
+		assert_vec_eq!(frame.column_x(), Vec3::from_y(1.0));
+		assert_vec_eq!(frame.column_y(), Vec3::from_x(-1.0));
+
+		// Mirrored texture, so the bitangent flips to keep following v
+		let frame = triangle.tangent_frame([Vec2::zero(), Vec2::from_y(1.0), Vec2::from_x(1.0)]).unwrap();
+		assert_vec_eq!(frame.column_x(), Vec3::from_y(1.0));
+		assert_vec_eq!(frame.column_y(), Vec3::from_x(1.0));
+		assert_vec_eq!(frame.column_z(), Vec3::from_z(1.0));
+
+		assert!(triangle.tangent_frame([Vec2::zero(), Vec2::one(), Vec2::splat(2.0)]).is_none());
+
+		// A small patch of a large texture
+		let frame = triangle.tangent_frame([Vec2::zero(), Vec2::from_x(1.0e-4), Vec2::from_y(1.0e-4)]).unwrap();
+		assert_vec_eq!(frame.column_x(), Vec3::from_x(1.0));
+		assert_vec_eq!(frame.column_y(), Vec3::from_y(1.0));
+	}
+}
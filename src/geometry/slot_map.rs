@@ -0,0 +1,190 @@
+use std::ops::{Index, IndexMut};
+
+
+/// Identifies a value in a [`SlotMap`]. The generation tells apart values that have used the same slot,
+/// so keys of removed values stay invalid after their slot is reused.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct SlotKey {
+	index: u32,
+	generation: u32,
+}
+
+/// Values stored in reusable slots, for containers that hand out stable handles to what they hold.
+///
+/// Values can also be reached by the index of their slot alone, for links between them that are kept up to date.
+#[derive(Clone, Debug)]
+pub(crate) struct SlotMap<T> {
+	slots: Vec<Slot<T>>,
+	free: Vec<u32>,
+	len: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Slot<T> {
+	generation: u32,
+	value: Option<T>,
+}
+
+impl SlotKey {
+	pub fn index(&self) -> u32 {
+		self.index
+	}
+}
+
+/// Constructors
+impl<T> SlotMap<T> {
+	pub fn new() -> SlotMap<T> {
+		SlotMap {
+			slots: Vec::new(),
+			free: Vec::new(),
+			len: 0,
+		}
+	}
+}
+
+/// Properties
+impl<T> SlotMap<T> {
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	/// The number of slots, whether occupied or not.
+	#[cfg(test)]
+	pub fn capacity(&self) -> usize {
+		self.slots.len()
+	}
+
+	pub fn get(&self, key: SlotKey) -> Option<&T> {
+		let slot = self.slots.get(key.index as usize)?;
+		if slot.generation != key.generation {
+			return None;
+		}
+		slot.value.as_ref()
+	}
+
+	pub fn get_mut(&mut self, key: SlotKey) -> Option<&mut T> {
+		let slot = self.slots.get_mut(key.index as usize)?;
+		if slot.generation != key.generation {
+			return None;
+		}
+		slot.value.as_mut()
+	}
+
+	/// The value in the slot at `index`, if it is occupied.
+	pub fn at(&self, index: u32) -> Option<&T> {
+		self.slots.get(index as usize)?.value.as_ref()
+	}
+
+	/// The key of the value in the slot at `index`, which must be occupied.
+	pub fn key(&self, index: u32) -> SlotKey {
+		SlotKey { index, generation: self.slots[index as usize].generation }
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item=(SlotKey, &T)> + '_ {
+		self.slots.iter()
+			.enumerate()
+			.filter_map(|(index, slot)| Some((SlotKey { index: index as u32, generation: slot.generation }, slot.value.as_ref()?)))
+	}
+}
+
+/// Modification
+impl<T> SlotMap<T> {
+	pub fn insert(&mut self, value: T) -> SlotKey {
+		self.len += 1;
+		match self.free.pop() {
+			Some(index) => {
+				let slot = &mut self.slots[index as usize];
+				slot.value = Some(value);
+				SlotKey { index, generation: slot.generation }
+			}
+
+			None => {
+				self.slots.push(Slot { generation: 0, value: Some(value) });
+				SlotKey { index: self.slots.len() as u32 - 1, generation: 0 }
+			}
+		}
+	}
+
+	/// Removes a value, returning it if the key was valid.
+	pub fn remove(&mut self, key: SlotKey) -> Option<T> {
+		let slot = self.slots.get_mut(key.index as usize)?;
+		if slot.generation != key.generation {
+			return None;
+		}
+
+		let value = slot.value.take()?;
+		slot.generation = slot.generation.wrapping_add(1);
+		self.free.push(key.index);
+		self.len -= 1;
+		Some(value)
+	}
+
+	/// Removes the value in the slot at `index`, which must be occupied.
+	pub fn remove_at(&mut self, index: u32) -> T {
+		self.remove(self.key(index)).unwrap()
+	}
+
+	/// Removes every value, keeping the slots so that existing keys stay invalid.
+	pub fn clear(&mut self) {
+		self.free.clear();
+		for (index, slot) in self.slots.iter_mut().enumerate().rev() {
+			if slot.value.take().is_some() {
+				slot.generation = slot.generation.wrapping_add(1);
+			}
+			self.free.push(index as u32);
+		}
+		self.len = 0;
+	}
+}
+
+impl<T> Index<u32> for SlotMap<T> {
+	type Output = T;
+
+	fn index(&self, index: u32) -> &T {
+		self.slots[index as usize].value.as_ref().unwrap()
+	}
+}
+
+impl<T> IndexMut<u32> for SlotMap<T> {
+	fn index_mut(&mut self, index: u32) -> &mut T {
+		self.slots[index as usize].value.as_mut().unwrap()
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_slot_map() {
+		let mut slots = SlotMap::new();
+		let (a, b) = (slots.insert("a"), slots.insert("b"));
+		assert_eq!(slots.len(), 2);
+		assert_eq!(slots.get(a), Some(&"a"));
+		assert_eq!(slots[b.index()], "b");
+
+		assert_eq!(slots.remove(a), Some("a"));
+		assert_eq!(slots.remove(a), None);
+
+		// The slot is reused, but the old key doesn't reach the new value
+		let c = slots.insert("c");
+		assert_eq!(c.index(), a.index());
+		assert_eq!(slots.get(a), None);
+		assert_eq!(slots.get(c), Some(&"c"));
+		assert_eq!(slots.key(c.index()), c);
+
+		let values: Vec<_> = slots.iter().map(|(_, &value)| value).collect();
+		assert_eq!(values, ["c", "b"]);
+		assert_eq!(slots.capacity(), 2);
+
+		slots.clear();
+		assert_eq!(slots.len(), 0);
+		let d = slots.insert("d");
+		assert_eq!(slots.get(b), None);
+		assert_eq!(slots.get(c), None);
+		assert_eq!(slots.get(d), Some(&"d"));
+		assert_eq!(d.index(), 0);
+	}
+}
@@ -0,0 +1,396 @@
+use std::collections::HashMap;
+
+use crate::math::*;
+use crate::geometry::slot_map::{SlotKey, SlotMap};
+
+
+/// Identifies an item in a [`SpatialHash2`] or [`SpatialHash3`]. Handles of removed items stay invalid, even once their slot is reused.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SpatialHandle(SlotKey);
+
+/// Buckets points into a uniform 2D grid of square cells, storing only the cells that hold something,
+/// for finding nearby items among many small moving ones such as particles or crowds.
+#[derive(Clone, Debug)]
+pub struct SpatialHash2<T> {
+	cell_size: f32,
+	cells: HashMap<Vec2i, Vec<u32>>,
+	entries: SlotMap<Entry<T, Vec2, Vec2i>>,
+}
+
+/// Buckets points into a uniform 3D grid of cubic cells, storing only the cells that hold something,
+/// for finding nearby items among many small moving ones such as particles or crowds.
+#[derive(Clone, Debug)]
+pub struct SpatialHash3<T> {
+	cell_size: f32,
+	cells: HashMap<Vec3i, Vec<u32>>,
+	entries: SlotMap<Entry<T, Vec3, Vec3i>>,
+}
+
+#[derive(Clone, Debug)]
+struct Entry<T, V, C> {
+	position: V,
+	cell: C,
+	item: T,
+}
+
+macro_rules! impl_spatial_hash {
+	($ty:ident, $vec:ident, $cell:ident, $aabb:ident, $to_cell:ident, $to_vec:ident, $cells_between:ident, [$($axis:ident),+]) => {
+		/// Constructors
+		impl<T> $ty<T> {
+			pub fn new(cell_size: f32) -> $ty<T> {
+				assert!(cell_size > 0.0, "cell size must be positive");
+
+				$ty {
+					cell_size,
+					cells: HashMap::new(),
+					entries: SlotMap::new(),
+				}
+			}
+		}
+
+		/// Properties
+		impl<T> $ty<T> {
+			pub fn cell_size(&self) -> f32 {
+				self.cell_size
+			}
+
+			pub fn len(&self) -> usize {
+				self.entries.len()
+			}
+
+			pub fn is_empty(&self) -> bool {
+				self.entries.len() == 0
+			}
+
+			pub fn get(&self, handle: SpatialHandle) -> Option<&T> {
+				self.entry(handle).map(|entry| &entry.item)
+			}
+
+			pub fn get_mut(&mut self, handle: SpatialHandle) -> Option<&mut T> {
+				self.entries.get_mut(handle.0).map(|entry| &mut entry.item)
+			}
+
+			pub fn position(&self, handle: SpatialHandle) -> Option<$vec> {
+				self.entry(handle).map(|entry| entry.position)
+			}
+
+			pub fn iter(&self) -> impl Iterator<Item=(SpatialHandle, &T)> + '_ {
+				self.entries.iter().map(|(key, entry)| (SpatialHandle(key), &entry.item))
+			}
+
+			/// The cells holding at least one item, in no particular order.
+			pub fn occupied_cells(&self) -> impl Iterator<Item=$cell> + '_ {
+				self.cells.keys().copied()
+			}
+
+			fn entry(&self, handle: SpatialHandle) -> Option<&Entry<T, $vec, $cell>> {
+				self.entries.get(handle.0)
+			}
+		}
+
+		/// Conversions
+		impl<T> $ty<T> {
+			/// The cell containing `position`, rounding down so that cells at negative coordinates are the same size as the rest.
+			pub fn cell_of(&self, position: $vec) -> $cell {
+				(position / self.cell_size).floor().$to_cell()
+			}
+
+			/// The region of space covered by `cell`.
+			pub fn cell_bounds(&self, cell: $cell) -> $aabb {
+				let min = cell.$to_vec() * self.cell_size;
+				$aabb::new(min, min + $vec::splat(self.cell_size))
+			}
+
+			/// `cell` and every cell touching it, including diagonally.
+			pub fn neighbor_cells(&self, cell: $cell) -> impl Iterator<Item=$cell> + '_ {
+				// Cells of far off positions are clamped to the edge of the grid, which has nothing beyond it
+				let min = $cell { $($axis: cell.$axis.saturating_sub(1)),+ };
+				let max = $cell { $($axis: cell.$axis.saturating_add(1)),+ };
+				$cells_between(min, max)
+			}
+		}
+
+		/// Queries
+		impl<T> $ty<T> {
+			/// Visits each item in `cell`.
+			pub fn query_cell(&self, cell: $cell, mut visit: impl FnMut(SpatialHandle, &T)) {
+				for &index in self.cells.get(&cell).into_iter().flatten() {
+					visit(SpatialHandle(self.entries.key(index)), &self.entries[index].item);
+				}
+			}
+
+			/// Visits each item in the cell containing `position` and in the cells touching it.
+			/// With cells at least as large as the distance items interact over, this visits every item that could interact with one at `position`.
+			pub fn query_neighbors(&self, position: $vec, mut visit: impl FnMut(SpatialHandle, &T)) {
+				for cell in self.neighbor_cells(self.cell_of(position)) {
+					self.query_cell(cell, &mut visit);
+				}
+			}
+
+			/// Visits each item within `radius` of `center`.
+			pub fn query_radius(&self, center: $vec, radius: f32, mut visit: impl FnMut(SpatialHandle, &T)) {
+				let region = $aabb::from_center_extents(center, radius);
+				self.query_box(&region, |handle, item| {
+					let position = self.entries[handle.0.index()].position;
+					if (position - center).square_length() <= radius * radius {
+						visit(handle, item);
+					}
+				});
+			}
+
+			/// Visits each item inside `region`.
+			pub fn query_box(&self, region: &$aabb, mut visit: impl FnMut(SpatialHandle, &T)) {
+				let (min, max) = (self.cell_of(region.min), self.cell_of(region.max));
+				let mut visit_cell = |indices: &Vec<u32>| for &index in indices {
+					let entry = &self.entries[index];
+					if region.contains_point(entry.position) {
+						visit(SpatialHandle(self.entries.key(index)), &entry.item);
+					}
+				};
+
+				// Large regions may cover more cells than are occupied, or even than fit in an `i32`
+				let cell_count = min.to_array().iter().zip(max.to_array())
+					.map(|(&min, max)| (max as i64 - min as i64 + 1).max(0) as u64)
+					.fold(1, u64::saturating_mul);
+				if cell_count > self.cells.len() as u64 {
+					let (min, max) = (min.to_array(), max.to_array());
+					for (cell, indices) in &self.cells {
+						if cell.to_array().iter().enumerate().all(|(axis, &value)| min[axis] <= value && value <= max[axis]) {
+							visit_cell(indices);
+						}
+					}
+				} else {
+					for cell in $cells_between(min, max) {
+						if let Some(indices) = self.cells.get(&cell) {
+							visit_cell(indices);
+						}
+					}
+				}
+			}
+		}
+
+		/// Modification
+		impl<T> $ty<T> {
+			pub fn insert(&mut self, position: $vec, item: T) -> SpatialHandle {
+				let cell = self.cell_of(position);
+				let key = self.entries.insert(Entry { position, cell, item });
+				self.cells.entry(cell).or_default().push(key.index());
+				SpatialHandle(key)
+			}
+
+			/// Removes an item, returning it if the handle was valid.
+			pub fn remove(&mut self, handle: SpatialHandle) -> Option<T> {
+				let entry = self.entries.remove(handle.0)?;
+				self.remove_from_cell(handle.0.index(), entry.cell);
+				Some(entry.item)
+			}
+
+			/// Moves an item to `position`, returning whether it changed cells.
+			pub fn update(&mut self, handle: SpatialHandle, position: $vec) -> bool {
+				let cell = self.cell_of(position);
+				let Some(entry) = self.entries.get_mut(handle.0) else {
+					return false;
+				};
+
+				entry.position = position;
+				let previous = std::mem::replace(&mut entry.cell, cell);
+				if previous == cell {
+					return false;
+				}
+
+				self.remove_from_cell(handle.0.index(), previous);
+				self.cells.entry(cell).or_default().push(handle.0.index());
+				true
+			}
+
+			pub fn clear(&mut self) {
+				self.cells.clear();
+				self.entries.clear();
+			}
+
+			fn remove_from_cell(&mut self, index: u32, cell: $cell) {
+				let indices = self.cells.get_mut(&cell).unwrap();
+				let position = indices.iter().position(|&other| other == index).unwrap();
+				indices.swap_remove(position);
+
+				if indices.is_empty() {
+					self.cells.remove(&cell);
+				}
+			}
+		}
+	}
+}
+
+impl_spatial_hash!(SpatialHash2, Vec2, Vec2i, Aabb2, to_vec2i, to_vec2, cells_between_2d, [x, y]);
+impl_spatial_hash!(SpatialHash3, Vec3, Vec3i, Aabb3, to_vec3i, to_vec3, cells_between_3d, [x, y, z]);
+
+
+// Every cell from `min` to `max` inclusive.
+fn cells_between_2d(min: Vec2i, max: Vec2i) -> impl Iterator<Item=Vec2i> {
+	(min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Vec2i::new(x, y)))
+}
+
+fn cells_between_3d(min: Vec3i, max: Vec3i) -> impl Iterator<Item=Vec3i> {
+	(min.z..=max.z).flat_map(move |z| cells_between_2d(min.to_xy(), max.to_xy()).map(move |cell| cell.extend(z)))
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+	use rand::{Rng, SeedableRng};
+	use rand::rngs::StdRng;
+
+	fn sorted(mut handles: Vec<SpatialHandle>) -> Vec<SpatialHandle> {
+		handles.sort();
+		handles
+	}
+
+	#[test]
+	fn test_cells() {
+		let hash = SpatialHash2::<()>::new(2.0);
+		assert_eq!(hash.cell_of(Vec2::new(0.5, 3.9)), Vec2i::new(0, 1));
+		assert_eq!(hash.cell_of(Vec2::new(-0.5, -2.0)), Vec2i::new(-1, -1));
+		assert_eq!(hash.cell_of(Vec2::new(-2.1, 4.0)), Vec2i::new(-2, 2));
+
+		let bounds = hash.cell_bounds(Vec2i::new(-1, 3));
+		assert_vec_eq!(bounds.min, Vec2::new(-2.0, 6.0));
+		assert_vec_eq!(bounds.max, Vec2::new(0.0, 8.0));
+
+		let neighbors: Vec<Vec2i> = hash.neighbor_cells(Vec2i::zero()).collect();
+		assert_eq!(neighbors.len(), 9);
+		assert!(neighbors.contains(&Vec2i::new(-1, 1)));
+		assert!(neighbors.contains(&Vec2i::zero()));
+
+		let corner = hash.neighbor_cells(Vec2i::new(i32::MAX, i32::MIN)).collect::<Vec<_>>();
+		assert_eq!(corner.len(), 4);
+		assert!(corner.contains(&Vec2i::new(i32::MAX - 1, i32::MIN + 1)));
+
+		let hash = SpatialHash3::<()>::new(0.5);
+		assert_eq!(hash.cell_of(Vec3::new(-0.1, 0.6, -1.0)), Vec3i::new(-1, 1, -2));
+		assert_eq!(hash.neighbor_cells(Vec3i::new(5, 5, 5)).count(), 27);
+		assert_eq!(hash.neighbor_cells(hash.cell_of(Vec3::new(1.0e30, 0.0, -1.0e30))).count(), 12);
+		assert_vec_eq!(hash.cell_bounds(Vec3i::new(-1, 1, -2)).center(), Vec3::new(-0.25, 0.75, -0.75));
+	}
+
+	#[test]
+	fn test_queries() {
+		let mut rng = StdRng::seed_from_u64(10);
+		let mut hash = SpatialHash2::new(1.5);
+		let points: Vec<Vec2> = (0..500).map(|_| Vec2::new(rng.random_range(-20.0..20.0), rng.random_range(-20.0..20.0))).collect();
+		let handles: Vec<SpatialHandle> = points.iter().enumerate().map(|(index, &point)| hash.insert(point, index)).collect();
+		assert_eq!(hash.len(), 500);
+
+		let brute = |hash: &SpatialHash2<usize>, filter: &dyn Fn(Vec2) -> bool| -> Vec<SpatialHandle> {
+			sorted(hash.iter().map(|(handle, _)| handle).filter(|&handle| filter(hash.position(handle).unwrap())).collect())
+		};
+
+		for _ in 0..20 {
+			let center = Vec2::new(rng.random_range(-25.0..25.0), rng.random_range(-25.0..25.0));
+			let radius = rng.random_range(0.0..8.0);
+
+			let mut found = Vec::new();
+			hash.query_radius(center, radius, |handle, &item| {
+				assert_eq!(handle, handles[item]);
+				found.push(handle);
+			});
+			assert_eq!(sorted(found), brute(&hash, &|point| (point - center).length() <= radius));
+
+			let region = Aabb2::from_center_extents(center, Vec2::new(radius, radius * 0.5));
+			let mut found = Vec::new();
+			hash.query_box(&region, |handle, _| found.push(handle));
+			assert_eq!(sorted(found), brute(&hash, &|point| region.contains_point(point)));
+		}
+
+		// Regions larger than the occupied area
+		let mut found = Vec::new();
+		hash.query_box(&Aabb2::from_center_extents(Vec2::zero(), 1000.0), |handle, _| found.push(handle));
+		assert_eq!(found.len(), 500);
+
+		// Regions with more cells than fit in an `i32`
+		let mut found = 0;
+		hash.query_box(&Aabb2::from_center_extents(Vec2::zero(), 1.0e10), |_, _| found += 1);
+		assert_eq!(found, 500);
+		let mut found = 0;
+		hash.query_radius(Vec2::zero(), f32::INFINITY, |_, _| found += 1);
+		assert_eq!(found, 500);
+
+		// Neighbors cover everything within a cell of the point
+		let center = points[0];
+		let mut found = Vec::new();
+		hash.query_neighbors(center, |handle, _| found.push(handle));
+		let found = sorted(found);
+		for handle in brute(&hash, &|point| (point - center).length() <= 1.5) {
+			assert!(found.binary_search(&handle).is_ok());
+		}
+
+		// Moving items between cells
+		for (&handle, &point) in handles.iter().zip(&points) {
+			let moved = point + Vec2::new(rng.random_range(-3.0..3.0), rng.random_range(-3.0..3.0));
+			assert_eq!(hash.update(handle, moved), hash.cell_of(point) != hash.cell_of(moved));
+		}
+
+		for &handle in &handles[..250] {
+			assert!(hash.remove(handle).is_some());
+			assert!(hash.remove(handle).is_none());
+		}
+		assert_eq!(hash.len(), 250);
+
+		// Handles of removed items don't reach the items reusing their slots
+		let added: Vec<SpatialHandle> = (0..50).map(|index| hash.insert(Vec2::zero(), 1000 + index)).collect();
+		for &handle in &handles[..250] {
+			assert!(hash.get(handle).is_none());
+			assert!(!hash.update(handle, Vec2::splat(100.0)));
+		}
+		for handle in added {
+			hash.remove(handle);
+		}
+
+		let total: usize = hash.occupied_cells().map(|cell| {
+			let mut count = 0;
+			hash.query_cell(cell, |handle, _| {
+				assert_eq!(hash.cell_of(hash.position(handle).unwrap()), cell);
+				count += 1;
+			});
+			count
+		}).sum();
+		assert_eq!(total, 250);
+
+		let region = Aabb2::from_center_extents(Vec2::new(3.0, -4.0), 6.0);
+		let mut found = Vec::new();
+		hash.query_box(&region, |handle, _| found.push(handle));
+		assert_eq!(sorted(found), brute(&hash, &|point| region.contains_point(point)));
+	}
+
+	#[test]
+	fn test_spatial_hash3() {
+		let mut hash = SpatialHash3::new(1.0);
+		let a = hash.insert(Vec3::new(-0.5, -0.5, -0.5), 'a');
+		let b = hash.insert(Vec3::new(0.5, 0.5, 0.5), 'b');
+		hash.insert(Vec3::new(3.5, 0.5, 0.5), 'c');
+
+		let mut found = Vec::new();
+		hash.query_neighbors(Vec3::new(0.2, 0.2, 0.2), |handle, _| found.push(handle));
+		assert_eq!(sorted(found), vec![a, b]);
+
+		let mut found = Vec::new();
+		hash.query_radius(Vec3::zero(), 0.9, |_, &item| found.push(item));
+		found.sort();
+		assert_eq!(found, vec!['a', 'b']);
+
+		assert!(hash.update(a, Vec3::new(3.2, 0.2, 0.8)));
+		let mut found = Vec::new();
+		hash.query_cell(Vec3i::new(3, 0, 0), |_, &item| found.push(item));
+		found.sort();
+		assert_eq!(found, vec!['a', 'c']);
+
+		hash.clear();
+		assert!(hash.is_empty());
+		assert_eq!(hash.occupied_cells().count(), 0);
+
+		let d = hash.insert(Vec3::zero(), 'd');
+		assert!(hash.get(a).is_none());
+		assert_eq!(hash.get(d), Some(&'d'));
+	}
+}
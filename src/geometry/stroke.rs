@@ -0,0 +1,329 @@
+use crate::math::*;
+use crate::geometry::path::arc_segment_count;
+
+
+/// How consecutive segments of a stroke are connected.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineJoin {
+	/// Extend the outer edges until they meet, falling back to `Bevel` past `StrokeStyle::miter_limit`.
+	#[default]
+	Miter,
+	Round,
+	Bevel,
+}
+
+/// How the ends of open strokes are drawn.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineCap {
+	/// End exactly at the end point.
+	#[default]
+	Butt,
+
+	/// Extend past the end point by half the stroke width.
+	Square,
+	Round,
+}
+
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StrokeStyle {
+	pub width: f32,
+	pub join: LineJoin,
+	pub cap: LineCap,
+
+	/// The maximum ratio of miter length to half the stroke width before miter joins are beveled.
+	pub miter_limit: f32,
+
+	/// Maximum error when approximating round joins and caps, and when flattening curves in `Path2::stroke`.
+	pub tolerance: f32,
+}
+
+impl StrokeStyle {
+	pub fn new(width: f32) -> StrokeStyle {
+		StrokeStyle {
+			width,
+			join: LineJoin::Miter,
+			cap: LineCap::Butt,
+			miter_limit: 4.0,
+			tolerance: 0.1,
+		}
+	}
+
+	pub fn with_join(self, join: LineJoin) -> StrokeStyle {
+		StrokeStyle { join, ..self }
+	}
+
+	pub fn with_cap(self, cap: LineCap) -> StrokeStyle {
+		StrokeStyle { cap, ..self }
+	}
+
+	pub fn with_miter_limit(self, miter_limit: f32) -> StrokeStyle {
+		StrokeStyle { miter_limit, ..self }
+	}
+
+	pub fn with_tolerance(self, tolerance: f32) -> StrokeStyle {
+		StrokeStyle { tolerance, ..self }
+	}
+}
+
+impl Default for StrokeStyle {
+	fn default() -> StrokeStyle {
+		StrokeStyle::new(1.0)
+	}
+}
+
+
+/// Appends triangles covering a stroke along `points` to `triangles`.
+/// Triangles may overlap at joins, so translucent strokes should be drawn with a stencil or depth test.
+///
+/// Repeated points are ignored. A single point is drawn as a dot with round and square caps.
+pub fn stroke_polyline(points: &[Vec2], closed: bool, style: &StrokeStyle, triangles: &mut Vec<[Vec2; 3]>) {
+	let half_width = style.width / 2.0;
+	if half_width <= 0.0 {
+		return;
+	}
+
+	let mut unique: Vec<Vec2> = Vec::with_capacity(points.len());
+	for &point in points {
+		if unique.last().is_none_or(|&last| (last - point).square_length() > 1.0e-12) {
+			unique.push(point);
+		}
+	}
+
+	if closed && unique.len() > 2 && (unique[0] - unique[unique.len() - 1]).square_length() <= 1.0e-12 {
+		unique.pop();
+	}
+
+	let stroker = Stroker { style, half_width, triangles };
+
+	match unique.len() {
+		0 => {}
+		1 => stroker.dot(unique[0]),
+		2 => stroker.open(&unique),
+		_ if closed => stroker.closed(&unique),
+		_ => stroker.open(&unique),
+	}
+}
+
+
+struct Stroker<'a> {
+	style: &'a StrokeStyle,
+	half_width: f32,
+	triangles: &'a mut Vec<[Vec2; 3]>,
+}
+
+impl Stroker<'_> {
+	fn open(mut self, points: &[Vec2]) {
+		for pair in points.windows(2) {
+			self.segment(pair[0], pair[1]);
+		}
+
+		for triple in points.windows(3) {
+			self.join(triple[0], triple[1], triple[2]);
+		}
+
+		let last = points.len() - 1;
+		self.cap(points[0], (points[0] - points[1]).normalize());
+		self.cap(points[last], (points[last] - points[last - 1]).normalize());
+	}
+
+	fn closed(mut self, points: &[Vec2]) {
+		let count = points.len();
+
+		for i in 0..count {
+			let previous = points[(i + count - 1) % count];
+			let next = points[(i + 1) % count];
+
+			self.segment(points[i], next);
+			self.join(previous, points[i], next);
+		}
+	}
+
+	fn dot(mut self, point: Vec2) {
+		let hw = self.half_width;
+
+		match self.style.cap {
+			LineCap::Butt => {}
+			LineCap::Square => self.quad([
+				point + Vec2::new(-hw, -hw),
+				point + Vec2::new(hw, -hw),
+				point + Vec2::new(hw, hw),
+				point + Vec2::new(-hw, hw),
+			]),
+			LineCap::Round => self.fan(point, Vec2::from_x(hw), TAU),
+		}
+	}
+
+	fn segment(&mut self, start: Vec2, end: Vec2) {
+		let offset = (end - start).normalize().perp() * self.half_width;
+		self.quad([start - offset, end - offset, end + offset, start + offset]);
+	}
+
+	fn join(&mut self, previous: Vec2, point: Vec2, next: Vec2) {
+		let incoming = (point - previous).normalize();
+		let outgoing = (next - point).normalize();
+
+		let turn = incoming.wedge(outgoing);
+		if turn.abs() < 1.0e-6 && incoming.dot(outgoing) > 0.0 {
+			return;
+		}
+
+		// The gap between segments is on the outside of the turn
+		let side = if turn > 0.0 { -1.0 } else { 1.0 };
+		let from = incoming.perp() * (self.half_width * side);
+		let to = outgoing.perp() * (self.half_width * side);
+
+		match self.style.join {
+			LineJoin::Bevel => self.triangles.push([point, point + from, point + to]),
+
+			LineJoin::Round => {
+				let sweep = from.wedge(to).atan2(from.dot(to));
+				self.fan(point, from, sweep);
+			}
+
+			LineJoin::Miter => {
+				let bisector = from + to;
+				let cos_half_angle = bisector.length() / (2.0 * self.half_width);
+				let miter_ratio = 1.0 / cos_half_angle.max(1.0e-6);
+
+				if miter_ratio <= self.style.miter_limit {
+					let tip = point + bisector.normalize() * (self.half_width * miter_ratio);
+					self.triangles.push([point, point + from, tip]);
+					self.triangles.push([point, tip, point + to]);
+				} else {
+					self.triangles.push([point, point + from, point + to]);
+				}
+			}
+		}
+	}
+
+	// `direction` points away from the stroke.
+	fn cap(&mut self, point: Vec2, direction: Vec2) {
+		let offset = direction.perp() * self.half_width;
+
+		match self.style.cap {
+			LineCap::Butt => {}
+			LineCap::Square => {
+				let extension = direction * self.half_width;
+				self.quad([point - offset, point + offset, point + offset + extension, point - offset + extension]);
+			}
+			LineCap::Round => self.fan(point, offset, -PI),
+		}
+	}
+
+	// Triangle fan around `center` from `center + from`, rotating `sweep` radians.
+	fn fan(&mut self, center: Vec2, from: Vec2, sweep: f32) {
+		let steps = arc_segment_count(self.half_width, sweep, self.style.tolerance);
+		let start_angle = from.to_angle();
+
+		let mut previous = center + from;
+		for step in 1..=steps {
+			let angle = start_angle + sweep * step as f32 / steps as f32;
+			let point = center + Vec2::from_angle(angle) * self.half_width;
+			self.triangles.push([center, previous, point]);
+			previous = point;
+		}
+	}
+
+	fn quad(&mut self, [a, b, c, d]: [Vec2; 4]) {
+		self.triangles.push([a, b, c]);
+		self.triangles.push([a, c, d]);
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn area(triangles: &[[Vec2; 3]]) -> f32 {
+		triangles.iter()
+			.map(|[a, b, c]| (*b - *a).wedge(*c - *a).abs() / 2.0)
+			.sum()
+	}
+
+	#[test]
+	fn test_caps() {
+		let line = [Vec2::zero(), Vec2::from_x(1.0), Vec2::from_x(4.0)];
+		let mut triangles = Vec::new();
+
+		stroke_polyline(&line, false, &StrokeStyle::new(2.0), &mut triangles);
+		assert_almost_eq!(area(&triangles), 8.0);
+
+		triangles.clear();
+		stroke_polyline(&line, false, &StrokeStyle::new(2.0).with_cap(LineCap::Square), &mut triangles);
+		assert_almost_eq!(area(&triangles), 12.0);
+
+		triangles.clear();
+		stroke_polyline(&line, false, &StrokeStyle::new(2.0).with_cap(LineCap::Round).with_tolerance(0.0001), &mut triangles);
+		assert!((area(&triangles) - (8.0 + PI)).abs() < 0.01);
+
+		// Everything stays within the stroke
+		for &point in triangles.iter().flatten() {
+			assert!(point.x >= -1.001 && point.x <= 5.001 && point.y.abs() <= 1.001);
+		}
+	}
+
+	#[test]
+	fn test_joins() {
+		let corner = [Vec2::zero(), Vec2::from_x(2.0), Vec2::new(2.0, 2.0)];
+		let outer_corner = Vec2::new(2.5, -0.5);
+
+		let vertices = |join| {
+			let mut triangles = Vec::new();
+			stroke_polyline(&corner, false, &StrokeStyle::new(1.0).with_join(join), &mut triangles);
+			triangles.into_iter().flatten().collect::<Vec<Vec2>>()
+		};
+
+		let has_outer_corner = |vertices: &[Vec2]| vertices.iter().any(|&v| (v - outer_corner).length() < 0.001);
+
+		assert!(has_outer_corner(&vertices(LineJoin::Miter)));
+		assert!(!has_outer_corner(&vertices(LineJoin::Bevel)));
+		assert!(!has_outer_corner(&vertices(LineJoin::Round)));
+
+		// Round joins stay on the circle around the corner
+		for v in vertices(LineJoin::Round) {
+			assert!((v - corner[1]).length() <= 0.5 + 0.001 || v.x <= 2.0 || v.y >= 0.0);
+		}
+
+		// Sharp angles exceed the miter limit and are beveled
+		let spike = [Vec2::zero(), Vec2::from_x(2.0), Vec2::new(0.0, 0.1)];
+		let mut triangles = Vec::new();
+		stroke_polyline(&spike, false, &StrokeStyle::new(1.0), &mut triangles);
+		for &point in triangles.iter().flatten() {
+			assert!(point.x < 2.5);
+		}
+	}
+
+	#[test]
+	fn test_closed_and_degenerate() {
+		let square = [Vec2::zero(), Vec2::from_x(2.0), Vec2::splat(2.0), Vec2::from_y(2.0), Vec2::zero()];
+		let mut triangles = Vec::new();
+
+		// Each side and each mitered corner
+		stroke_polyline(&square, true, &StrokeStyle::new(1.0), &mut triangles);
+		assert_eq!(triangles.len(), 4 * 2 + 4 * 2);
+		assert_almost_eq!(area(&triangles), 4.0 * 2.0 + 4.0 * 0.25);
+
+		triangles.clear();
+		stroke_polyline(&[Vec2::one(), Vec2::one()], false, &StrokeStyle::new(2.0).with_cap(LineCap::Square), &mut triangles);
+		assert_almost_eq!(area(&triangles), 4.0);
+
+		triangles.clear();
+		stroke_polyline(&[Vec2::one()], false, &StrokeStyle::new(2.0), &mut triangles);
+		assert!(triangles.is_empty());
+
+		// Paths stroke each subpath
+		let path = Path2::new()
+			.move_to(Vec2::zero())
+			.line_to(Vec2::from_x(1.0))
+			.move_to(Vec2::from_y(5.0))
+			.line_to(Vec2::new(1.0, 5.0));
+
+		assert_eq!(path.stroke(&StrokeStyle::new(0.5)).len(), 4);
+	}
+}
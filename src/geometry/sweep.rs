@@ -0,0 +1,435 @@
+use crate::math::*;
+use crate::geometry::shapes::*;
+use crate::geometry::gjk::*;
+
+
+/// Where a moving shape first touches another.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hit<V> {
+	/// The fraction of the motion completed before touching, from 0 to 1.
+	pub time: f32,
+
+	/// The unit normal of the surface that was hit, facing back towards the moving shape.
+	pub normal: V,
+
+	/// Where the shapes touch.
+	pub point: V,
+}
+
+pub type Hit2 = Hit<Vec2>;
+pub type Hit3 = Hit<Vec3>;
+
+
+/// Tests for moving shapes against static ones, to catch collisions that happen part way through a motion.
+///
+/// To sweep two moving shapes, move the first by the difference between their motions.
+/// Shapes that already overlap and are moving further in hit at time 0, and shapes that are moving apart don't hit at all.
+pub trait Sweep<Target: ?Sized> {
+	type Vector;
+
+	fn sweep(&self, motion: Self::Vector, target: &Target) -> Option<Hit<Self::Vector>>;
+}
+
+pub fn sweep<S, T>(shape: &S, motion: S::Vector, target: &T) -> Option<Hit<S::Vector>>
+	where S: Sweep<T>
+{
+	shape.sweep(motion, target)
+}
+
+
+impl Sweep<Aabb2> for Aabb2 {
+	type Vector = Vec2;
+
+	fn sweep(&self, motion: Vec2, target: &Aabb2) -> Option<Hit2> {
+		// Sweeping the center against the target grown by this box's extents gives the same time of impact
+		let expanded = target.grow(self.extents());
+		let start = self.center();
+
+		let mut entry = (-f32::INFINITY, Vec2::zero());
+		let mut exit = f32::INFINITY;
+		let mut overlap = (f32::INFINITY, Vec2::zero());
+
+		for (start, motion, min, max, axis) in [
+			(start.x, motion.x, expanded.min.x, expanded.max.x, Vec2::from_x(1.0)),
+			(start.y, motion.y, expanded.min.y, expanded.max.y, Vec2::from_y(1.0)),
+		] {
+			// The shallowest way out, should the boxes already overlap
+			let (below, above) = (start - min, max - start);
+			let depth = below.min(above);
+			if depth < overlap.0 {
+				overlap = (depth, if below < above { -axis } else { axis });
+			}
+
+			if motion == 0.0 {
+				if start < min || start > max {
+					return None;
+				}
+				continue
+			}
+
+			let (near, far) = if motion > 0.0 { (min, max) } else { (max, min) };
+			let (enter, leave) = ((near - start) / motion, (far - start) / motion);
+			if enter > entry.0 {
+				entry = (enter, if motion > 0.0 { -axis } else { axis });
+			}
+			exit = exit.min(leave);
+		}
+
+		let (time, normal) = if overlap.0 > 0.0 {
+			if motion.dot(overlap.1) >= 0.0 {
+				return None;
+			}
+			(0.0, overlap.1)
+		} else if entry.0 <= exit && (0.0..=1.0).contains(&entry.0) {
+			entry
+		} else {
+			return None;
+		};
+
+		// The middle of the region where the boxes touch
+		let moved = self.translate(motion * time);
+		let point = Aabb2::new(
+			Vec2::new(moved.min.x.max(target.min.x), moved.min.y.max(target.min.y)),
+			Vec2::new(moved.max.x.min(target.max.x), moved.max.y.min(target.max.y)),
+		).center();
+
+		Some(Hit { time, normal, point })
+	}
+}
+
+
+/// Sweeps a convex shape by `motion` against another, using GJK to advance it until the two touch.
+///
+/// The time of impact is found to within a ten thousandth of a unit of motion, erring on the side of not touching.
+pub fn gjk_sweep<S, T>(shape: &S, motion: S::Vector, target: &T) -> Option<Hit<S::Vector>>
+	where S: SupportMap + ?Sized, T: SupportMap<Vector=S::Vector> + ?Sized
+{
+	let mut time = 0.0;
+	let mut normal = S::Vector::default();
+	let mut point = S::Vector::default();
+
+	for _ in 0..SWEEP_ITERATIONS {
+		let moved = Translated { shape, offset: motion * time };
+
+		let Some(separation) = gjk_distance(&moved, target) else {
+			// Touching after advancing, or overlapping from the start
+			let Some(contact) = epa_contact(&moved, target) else {
+				return (time > 0.0).then_some(Hit { time, normal, point });
+			};
+
+			if time == 0.0 && motion.dot(contact.normal) <= 0.0 {
+				return None;
+			}
+
+			return Some(Hit { time, normal: -contact.normal, point: contact.point });
+		};
+
+		// The plane between the closest points separates the shapes, so they can't touch before reaching it
+		normal = -separation.normal;
+		point = separation.point_b;
+
+		// Checked before treating the shapes as touching, so that one resting against the target can move away
+		let approach = -motion.dot(normal);
+		if approach <= 0.0 {
+			return None;
+		}
+
+		if separation.distance <= SWEEP_TOLERANCE {
+			break
+		}
+
+		time += separation.distance / approach;
+		if time > 1.0 {
+			return None;
+		}
+	}
+
+	Some(Hit { time, normal, point })
+}
+
+const SWEEP_ITERATIONS: usize = 64;
+const SWEEP_TOLERANCE: f32 = 1.0e-4;
+
+// A shape moved by `offset`.
+struct Translated<'a, S: ?Sized, V> {
+	shape: &'a S,
+	offset: V,
+}
+
+impl<S: SupportMap + ?Sized> SupportMap for Translated<'_, S, S::Vector> {
+	type Vector = S::Vector;
+
+	fn support(&self, direction: S::Vector) -> S::Vector {
+		self.shape.support(direction) + self.offset
+	}
+
+	fn core_support(&self, direction: S::Vector) -> S::Vector {
+		self.shape.core_support(direction) + self.offset
+	}
+
+	fn radius(&self) -> f32 {
+		self.shape.radius()
+	}
+}
+
+
+// Sweeps any convex shape against a plane. Planes are solid behind, so only the deepest point of the shape can touch first.
+macro_rules! impl_plane_sweep {
+	($name:ident, $vector:ident, $plane:ident) => {
+		fn $name<S>(shape: &S, motion: $vector, plane: &$plane) -> Option<Hit<$vector>>
+			where S: SupportMap<Vector=$vector> + ?Sized
+		{
+			let speed = motion.dot(plane.normal);
+			if speed >= -PLANE_SWEEP_TOLERANCE * motion.length() {
+				return None;
+			}
+
+			let deepest = shape.support(-plane.normal);
+			let time = (plane.distance_to(deepest) / -speed).max(0.0);
+			if time > 1.0 {
+				return None;
+			}
+
+			Some(Hit { time, normal: plane.normal, point: deepest + motion * time })
+		}
+	}
+}
+
+// Motion this close to parallel with a plane slides along it rather than into it
+const PLANE_SWEEP_TOLERANCE: f32 = 1.0e-6;
+
+impl_plane_sweep!(sweep_plane_2d, Vec2, Plane2);
+impl_plane_sweep!(sweep_plane_3d, Vec3, Plane3);
+
+// Rounded shapes are swept against planes directly and against other shapes with GJK.
+macro_rules! impl_sweep {
+	($vector:ident, $plane:ident, $sweep_plane:ident, [$($shape:ty),+], $targets:tt) => {
+		$( impl_sweep!(@one $vector, $plane, $sweep_plane, $shape, $targets); )+
+	};
+
+	(@one $vector:ident, $plane:ident, $sweep_plane:ident, $shape:ty, [$($target:ty),+]) => {
+		impl Sweep<$plane> for $shape {
+			type Vector = $vector;
+
+			fn sweep(&self, motion: $vector, plane: &$plane) -> Option<Hit<$vector>> {
+				$sweep_plane(self, motion, plane)
+			}
+		}
+
+		$(
+			impl Sweep<$target> for $shape {
+				type Vector = $vector;
+
+				fn sweep(&self, motion: $vector, target: &$target) -> Option<Hit<$vector>> {
+					gjk_sweep(self, motion, target)
+				}
+			}
+		)+
+	};
+}
+
+impl_sweep!(Vec2, Plane2, sweep_plane_2d, [Circle, Capsule2], [Triangle2, Aabb2, Obb2]);
+impl_sweep!(Vec3, Plane3, sweep_plane_3d, [Sphere, Capsule3], [Triangle3, Aabb3, Obb3]);
+
+
+/// The result of `collide_and_slide`.
+#[derive(Clone, Debug, Default)]
+pub struct Slide {
+	/// How far the shape actually moved.
+	pub motion: Vec3,
+
+	/// Each plane hit along the way, with times relative to the part of the motion left when it was hit.
+	pub hits: Vec<Hit3>,
+}
+
+/// Moves a convex shape by `motion`, sliding along any of `planes` it runs into, until it has hit them `max_hits` times.
+///
+/// Planes are solid behind, as for `Sweep`. A shape that starts out behind a plane can slide along it but not into it.
+pub fn collide_and_slide<S>(shape: &S, motion: Vec3, planes: &[Plane3], max_hits: usize) -> Slide
+	where S: SupportMap<Vector=Vec3> + ?Sized
+{
+	let mut slide = Slide::default();
+	let mut remaining = motion;
+
+	while remaining.square_length() > 0.0 {
+		let moved = Translated { shape, offset: slide.motion };
+		let hit = planes.iter()
+			.filter_map(|plane| sweep_plane_3d(&moved, remaining, plane))
+			.min_by(|a, b| a.time.total_cmp(&b.time));
+
+		let Some(hit) = hit else {
+			slide.motion += remaining;
+			break
+		};
+
+		slide.motion += remaining * hit.time;
+		slide.hits.push(hit);
+		if slide.hits.len() >= max_hits {
+			break
+		}
+
+		// Slide along the plane with whatever motion is left, or along the crease with the previous plane
+		remaining *= 1.0 - hit.time;
+		remaining -= hit.normal * remaining.dot(hit.normal);
+		if let [.., previous, _] = slide.hits[..] {
+			if remaining.dot(previous.normal) < 0.0 {
+				let crease = previous.normal.cross(hit.normal);
+				remaining = if crease.square_length() > 0.0 { crease * (remaining.dot(crease) / crease.square_length()) } else { Vec3::zero() };
+			}
+		}
+	}
+
+	slide
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+	use rand::{Rng, SeedableRng};
+	use rand::rngs::StdRng;
+
+	#[test]
+	fn test_aabb() {
+		let unit = Aabb2::from_center_extents(Vec2::zero(), 0.5);
+		let target = Aabb2::from_center_extents(Vec2::from_x(5.0), 0.5);
+
+		let hit = unit.sweep(Vec2::from_x(10.0), &target).unwrap();
+		assert_almost_eq!(hit.time, 0.4);
+		assert_vec_eq!(hit.normal, Vec2::from_x(-1.0));
+		assert_vec_eq!(hit.point, Vec2::from_x(4.5));
+
+		assert!(unit.sweep(Vec2::new(10.0, 5.0), &target).is_none());
+		assert!(unit.sweep(Vec2::from_x(3.0), &target).is_none());
+		assert!(unit.sweep(Vec2::from_x(-10.0), &target).is_none());
+
+		// Diagonal motion onto the top face
+		let hit = unit.sweep(Vec2::new(4.0, -4.0), &Aabb2::new(Vec2::new(2.0, -10.0), Vec2::new(10.0, -3.0))).unwrap();
+		assert_almost_eq!(hit.time, 0.625);
+		assert_vec_eq!(hit.normal, Vec2::from_y(1.0));
+		assert_vec_eq!(hit.point, Vec2::new(2.5, -3.0));
+
+		// Thin walls can't be skipped over
+		let wall = Aabb2::new(Vec2::new(50.0, -10.0), Vec2::new(50.01, 10.0));
+		let hit = unit.sweep(Vec2::from_x(1000.0), &wall).unwrap();
+		assert_almost_eq!(hit.time * 1000.0, 49.5);
+
+		// Overlapping boxes only hit when moving further in
+		let overlapping = Aabb2::from_center_extents(Vec2::new(0.8, 0.2), 0.5);
+		let hit = unit.sweep(Vec2::from_x(1.0), &overlapping).unwrap();
+		assert_almost_eq!(hit.time, 0.0);
+		assert_vec_eq!(hit.normal, Vec2::from_x(-1.0));
+		assert!(unit.sweep(Vec2::from_x(-1.0), &overlapping).is_none());
+	}
+
+	#[test]
+	fn test_sphere_cast() {
+		let sphere = Sphere::new(Vec3::from_y(5.0), 1.0);
+		let floor = Plane3::new(Vec3::from_y(1.0), 0.0);
+		let motion = Vec3::new(2.0, -10.0, 0.0);
+
+		let hit = sphere.sweep(motion, &floor).unwrap();
+		assert_almost_eq!(hit.time, 0.4);
+		assert_vec_eq!(hit.normal, Vec3::from_y(1.0));
+		assert_vec_eq!(hit.point, Vec3::from_x(0.8));
+		assert!(sphere.sweep(-motion, &floor).is_none());
+
+		let triangle = Triangle3::new(Vec3::new(-10.0, 0.0, -10.0), Vec3::new(-10.0, 0.0, 10.0), Vec3::new(10.0, 0.0, 0.0));
+		let hit = sphere.sweep(motion, &triangle).unwrap();
+		assert_almost_eq!(hit.time, 0.4);
+		assert_vec_eq!(hit.normal, Vec3::from_y(1.0));
+		assert_vec_eq!(hit.point, Vec3::from_x(0.8));
+		assert!(sphere.sweep(Vec3::new(0.0, -10.0, 20.0), &triangle).is_none());
+
+		// Glancing off an edge
+		let edge = Triangle3::new(Vec3::from_x(-5.0), Vec3::from_x(5.0), Vec3::new(0.0, -5.0, 0.0));
+		let hit = Sphere::new(Vec3::new(0.0, 0.5, 5.0), 1.0).sweep(Vec3::from_z(-10.0), &edge).unwrap();
+		assert_almost_eq!(hit.time, (5.0 - 0.75f32.sqrt()) / 10.0);
+		assert_vec_eq!(hit.point, Vec3::zero());
+
+		let hit = sphere.sweep(motion, &Aabb3::new(Vec3::new(-5.0, -1.0, -5.0), Vec3::new(5.0, 0.0, 5.0))).unwrap();
+		assert_almost_eq!(hit.time, 0.4);
+	}
+
+	#[test]
+	fn test_capsule_cast() {
+		let capsule = Capsule3::new(Vec3::new(-1.0, 3.0, 0.0), Vec3::new(1.0, 3.0, 0.0), 0.5);
+		let block = Aabb3::new(Vec3::new(0.5, -1.0, -1.0), Vec3::new(2.0, 1.0, 1.0));
+
+		// The end of the capsule lands on the top of the block
+		let hit = capsule.sweep(Vec3::from_y(-4.0), &block).unwrap();
+		assert_almost_eq!(hit.time, 0.375);
+		assert_vec_eq!(hit.normal, Vec3::from_y(1.0));
+
+		let obb = Obb3::from_aabb(block);
+		assert_almost_eq!(capsule.sweep(Vec3::from_y(-4.0), &obb).unwrap().time, 0.375);
+
+		let hit = capsule.sweep(Vec3::new(0.0, -4.0, 0.0), &Plane3::new(Vec3::new(1.0, 1.0, 0.0), 0.0)).unwrap();
+		assert_vec_eq!(hit.point, Vec3::new(-1.0, 3.0, 0.0) - Vec3::new(1.0, 1.0, 0.0).normalize() * 0.5 + Vec3::from_y(-4.0) * hit.time);
+	}
+
+	#[test]
+	fn test_resting_contact() {
+		// Within the sweep tolerance of the box, but moving away from or along it
+		let sphere = Sphere::new(Vec3::from_z(-1.00005), 1.0);
+		let block = Aabb3::new(Vec3::new(-2.0, -2.0, 0.0), Vec3::new(2.0, 2.0, 1.0));
+
+		assert!(sphere.sweep(Vec3::from_z(-1.0), &block).is_none());
+		assert!(sphere.sweep(Vec3::from_x(1.0), &block).is_none());
+		assert_almost_eq!(sphere.sweep(Vec3::from_z(1.0), &block).unwrap().time, 0.0);
+	}
+
+	// Shapes are never found overlapping before the time of impact, and are touching at it
+	#[test]
+	fn test_random() {
+		let mut rng = StdRng::seed_from_u64(11);
+		let mut random_vec3 = |range: f32| Vec3::new(rng.random_range(-range..range), rng.random_range(-range..range), rng.random_range(-range..range));
+
+		for _ in 0..100 {
+			let capsule = Capsule3::new(random_vec3(3.0), random_vec3(3.0), 0.3);
+			let obb = Obb3::new(random_vec3(1.0), Quat::from_axis_angle(random_vec3(1.0).normalize(), 1.0), Vec3::new(1.0, 0.5, 0.25));
+			let motion = random_vec3(6.0);
+			if gjk_intersects(&capsule, &obb) {
+				continue
+			}
+
+			let moved = |time: f32| Capsule3::new(capsule.start + motion * time, capsule.end + motion * time, capsule.radius);
+			let hit = capsule.sweep(motion, &obb);
+			let end = hit.map_or(1.0, |hit| hit.time);
+
+			for step in 0..100 {
+				assert!(!gjk_intersects(&moved((end - 0.001) * step as f32 / 100.0), &obb));
+			}
+
+			if let Some(hit) = hit {
+				assert!(gjk_distance(&moved(hit.time), &obb).is_none_or(|separation| separation.distance < 0.001));
+				assert_almost_eq!(hit.normal.length(), 1.0);
+			}
+		}
+	}
+
+	#[test]
+	fn test_collide_and_slide() {
+		let sphere = Sphere::new(Vec3::from_y(2.0), 1.0);
+		let floor = Plane3::new(Vec3::from_y(1.0), 0.0);
+		let wall = Plane3::new(Vec3::from_x(-1.0), -5.0);
+
+		let slide = collide_and_slide(&sphere, Vec3::new(10.0, -5.0, 0.0), &[floor], 4);
+		assert_vec_eq!(slide.motion, Vec3::new(10.0, -1.0, 0.0));
+		assert_eq!(slide.hits.len(), 1);
+
+		let slide = collide_and_slide(&sphere, Vec3::new(10.0, -5.0, 0.0), &[floor, wall], 4);
+		assert_vec_eq!(slide.motion, Vec3::new(4.0, -1.0, 0.0));
+		assert_eq!(slide.hits.len(), 2);
+
+		// Pushed into the corner, the sphere slides along the crease
+		let slide = collide_and_slide(&sphere, Vec3::new(10.0, -5.0, 3.0), &[floor, wall], 4);
+		assert_vec_eq!(slide.motion, Vec3::new(4.0, -1.0, 3.0));
+
+		// Stopping after the first hit
+		let slide = collide_and_slide(&sphere, Vec3::new(10.0, -5.0, 0.0), &[floor, wall], 1);
+		assert_vec_eq!(slide.motion, Vec3::new(2.0, -1.0, 0.0));
+	}
+}
@@ -0,0 +1,468 @@
+use crate::math::*;
+
+
+/// Triangulates a simple polygon by ear clipping, returning counter-clockwise triangles as indices into `points`.
+/// The polygon may be wound either way, and may contain duplicate and collinear points.
+pub fn triangulate_polygon(points: &[Vec2]) -> Vec<[u32; 3]> {
+	triangulate_polygon_with_holes(points, &[])
+}
+
+/// Triangulates a polygon with holes by ear clipping, returning counter-clockwise triangles.
+/// Indices refer to the points of `outer` followed by the points of each hole in order, as if they were concatenated.
+///
+/// Holes are joined to the outer boundary by bridge edges, so they should lie inside the outer boundary and not
+/// overlap each other. Holes outside of the outer boundary are ignored.
+pub fn triangulate_polygon_with_holes(outer: &[Vec2], holes: &[&[Vec2]]) -> Vec<[u32; 3]> {
+	let mut points = outer.to_vec();
+	let mut ring = oriented_ring(&points, 0..outer.len(), true);
+
+	let mut hole_rings = Vec::with_capacity(holes.len());
+	for hole in holes {
+		let start = points.len();
+		points.extend_from_slice(hole);
+		hole_rings.push(oriented_ring(&points, start..points.len(), false));
+	}
+
+	hole_rings.retain(|hole| hole.len() >= 3 && hole.iter().any(|&i| ring_contains(&points, &ring, points[i as usize])));
+
+	// Holes are bridged from right to left, so that earlier bridges never cross later holes
+	let max_x = |hole: &Vec<u32>| hole.iter().map(|&i| points[i as usize].x).fold(f32::NEG_INFINITY, f32::max);
+	hole_rings.sort_by(|a, b| max_x(b).total_cmp(&max_x(a)));
+
+	for hole in hole_rings {
+		if ring.len() < 3 {
+			break
+		}
+
+		ring = bridge_hole(&points, ring, &hole);
+	}
+
+	clip_ears(&points, &ring)
+}
+
+
+// Collects the indices in `range`, skipping consecutive duplicates and wound in the requested direction.
+fn oriented_ring(points: &[Vec2], range: std::ops::Range<usize>, counter_clockwise: bool) -> Vec<u32> {
+	let mut ring: Vec<u32> = Vec::with_capacity(range.len());
+
+	for index in range {
+		if ring.last().is_none_or(|&last| !same_point(points[last as usize], points[index])) {
+			ring.push(index as u32);
+		}
+	}
+
+	while ring.len() > 1 && same_point(points[ring[0] as usize], points[ring[ring.len() - 1] as usize]) {
+		ring.pop();
+	}
+
+	let area: f32 = (0..ring.len())
+		.map(|i| points[ring[i] as usize].wedge(points[ring[(i + 1) % ring.len()] as usize]))
+		.sum();
+
+	if (area > 0.0) != counter_clockwise {
+		ring.reverse();
+	}
+
+	ring
+}
+
+// Splices `hole` into `ring` by connecting its rightmost vertex to a visible vertex of `ring`.
+// https://www.geometrictools.com/Documentation/TriangulationByEarClipping.pdf
+fn bridge_hole(points: &[Vec2], ring: Vec<u32>, hole: &[u32]) -> Vec<u32> {
+	let position = |index: u32| points[index as usize];
+
+	let hole_start = (0..hole.len())
+		.max_by(|&a, &b| position(hole[a]).x.total_cmp(&position(hole[b]).x))
+		.unwrap();
+
+	let m = position(hole[hole_start]);
+
+	// Cast a ray in +x from m to find the nearest edge of the ring it hits
+	let mut nearest_x = f32::INFINITY;
+	let mut candidate = None;
+
+	for i in 0..ring.len() {
+		let a = position(ring[i]);
+		let b = position(ring[(i + 1) % ring.len()]);
+
+		// The ring is counter-clockwise, so only edges heading up can be seen from inside
+		if a.y > m.y || b.y < m.y || a.y == b.y {
+			continue
+		}
+
+		let x = a.x + (m.y - a.y) / (b.y - a.y) * (b.x - a.x);
+		if x >= m.x && x < nearest_x {
+			nearest_x = x;
+			candidate = Some(if a.x > b.x { i } else { (i + 1) % ring.len() });
+
+			if x == a.x && a.y == m.y {
+				candidate = Some(i);
+			} else if x == b.x && b.y == m.y {
+				candidate = Some((i + 1) % ring.len());
+			}
+		}
+	}
+
+	let Some(mut bridge) = candidate else {
+		return ring;
+	};
+
+	// Any vertex inside the triangle between m, the hit point and the candidate would block the bridge.
+	// The blocking vertex closest in angle to the ray is visible instead.
+	let hit = Vec2::new(nearest_x, m.y);
+	let p = position(ring[bridge]);
+
+	if !same_point(p, hit) {
+		let mut best_tangent = f32::INFINITY;
+
+		for i in 0..ring.len() {
+			let v = position(ring[i]);
+			if i == bridge || v.x < m.x || v.x > p.x || same_point(v, m) {
+				continue
+			}
+
+			let (t0, t1, t2) = if m.y < p.y { (m, hit, p) } else { (hit, m, p) };
+			if !point_in_triangle(t0, t1, t2, v) {
+				continue
+			}
+
+			let tangent = (m.y - v.y).abs() / (v.x - m.x);
+			let better = tangent < best_tangent || (tangent == best_tangent && v.x > position(ring[bridge]).x);
+
+			if better && locally_inside(points, &ring, i, m) {
+				best_tangent = tangent;
+				bridge = i;
+			}
+		}
+	}
+
+	let mut merged = Vec::with_capacity(ring.len() + hole.len() + 2);
+	merged.extend_from_slice(&ring[..=bridge]);
+	merged.extend((0..=hole.len()).map(|i| hole[(hole_start + i) % hole.len()]));
+	merged.extend_from_slice(&ring[bridge..]);
+	merged
+}
+
+// Whether `target` is inside `ring` by the even-odd rule.
+fn ring_contains(points: &[Vec2], ring: &[u32], target: Vec2) -> bool {
+	let mut inside = false;
+
+	for i in 0..ring.len() {
+		let a = points[ring[i] as usize];
+		let b = points[ring[(i + 1) % ring.len()] as usize];
+
+		if (a.y > target.y) != (b.y > target.y) && target.x < a.x + (target.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+			inside = !inside;
+		}
+	}
+
+	inside
+}
+
+// Whether `target` lies within the interior angle of the counter-clockwise `ring` at `ring[i]`.
+fn locally_inside(points: &[Vec2], ring: &[u32], i: usize, target: Vec2) -> bool {
+	let position = |index: usize| points[ring[index % ring.len()] as usize];
+	let previous = position(i + ring.len() - 1);
+	let vertex = position(i);
+	let next = position(i + 1);
+
+	let left_of_incoming = signed_area(previous, vertex, target) >= 0.0;
+	let left_of_outgoing = signed_area(vertex, next, target) >= 0.0;
+
+	if signed_area(previous, vertex, next) >= 0.0 {
+		left_of_incoming && left_of_outgoing
+	} else {
+		left_of_incoming || left_of_outgoing
+	}
+}
+
+fn clip_ears(points: &[Vec2], ring: &[u32]) -> Vec<[u32; 3]> {
+	let count = ring.len();
+	let mut triangles = Vec::with_capacity(count.saturating_sub(2));
+
+	if count < 3 {
+		return triangles;
+	}
+
+	let position = |node: usize| points[ring[node] as usize];
+
+	let mut next: Vec<usize> = (0..count).map(|i| (i + 1) % count).collect();
+	let mut previous: Vec<usize> = (0..count).map(|i| (i + count - 1) % count).collect();
+
+	let mut remaining = count;
+	let mut node = 0;
+
+	// If a full lap finds no ears, the polygon is degenerate or self intersecting, so ear tests are progressively relaxed
+	let mut strictness = 0;
+	let mut since_last_clip = 0;
+
+	while remaining > 3 {
+		let (prev_node, next_node) = (previous[node], next[node]);
+		let (a, b, c) = (position(prev_node), position(node), position(next_node));
+		let area = signed_area(a, b, c);
+
+		let is_degenerate = area.abs() <= 1.0e-6 * (b - a).length() * (c - b).length();
+		let is_ear = !is_degenerate && match strictness {
+			0 => area > 0.0 && !contains_other_vertex(&next, prev_node, node, next_node, position),
+			1 => area > 0.0,
+			_ => true,
+		};
+
+		if is_degenerate || is_ear {
+			if is_ear {
+				triangles.push([ring[prev_node], ring[node], ring[next_node]]);
+			}
+
+			next[prev_node] = next_node;
+			previous[next_node] = prev_node;
+			remaining -= 1;
+
+			node = next_node;
+			strictness = 0;
+			since_last_clip = 0;
+			continue
+		}
+
+		node = next_node;
+		since_last_clip += 1;
+
+		if since_last_clip > remaining {
+			strictness += 1;
+			since_last_clip = 0;
+		}
+	}
+
+	let (a, b, c) = (previous[node], node, next[node]);
+	if signed_area(position(a), position(b), position(c)) > 0.0 {
+		triangles.push([ring[a], ring[b], ring[c]]);
+	}
+
+	triangles
+}
+
+fn contains_other_vertex(next: &[usize], a: usize, b: usize, c: usize, position: impl Fn(usize) -> Vec2) -> bool {
+	let (pa, pb, pc) = (position(a), position(b), position(c));
+
+	let mut node = next[c];
+	while node != a {
+		let p = position(node);
+
+		// Vertices shared through bridges or self touching points don't obstruct
+		if ![pa, pb, pc].iter().any(|&v| same_point(v, p)) && point_in_triangle(pa, pb, pc, p) {
+			return true;
+		}
+
+		node = next[node];
+	}
+
+	false
+}
+
+fn same_point(a: Vec2, b: Vec2) -> bool {
+	a.x == b.x && a.y == b.y
+}
+
+fn signed_area(a: Vec2, b: Vec2, c: Vec2) -> f32 {
+	(b - a).wedge(c - a)
+}
+
+// Inclusive of edges. Expects a counter-clockwise triangle.
+fn point_in_triangle(a: Vec2, b: Vec2, c: Vec2, p: Vec2) -> bool {
+	signed_area(a, b, p) >= 0.0 && signed_area(b, c, p) >= 0.0 && signed_area(c, a, p) >= 0.0
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn polygon_area(points: &[Vec2]) -> f32 {
+		let sum: f32 = (0..points.len())
+			.map(|i| points[i].wedge(points[(i + 1) % points.len()]))
+			.sum();
+
+		sum.abs() / 2.0
+	}
+
+	// Checks that triangles are counter-clockwise and exactly cover the polygon.
+	fn check(outer: &[Vec2], holes: &[&[Vec2]]) -> Vec<[u32; 3]> {
+		let points: Vec<Vec2> = holes.iter().fold(outer.to_vec(), |mut points, hole| {
+			points.extend_from_slice(hole);
+			points
+		});
+
+		let triangles = triangulate_polygon_with_holes(outer, holes);
+
+		let mut total_area = 0.0;
+		for &[a, b, c] in triangles.iter() {
+			let [a, b, c] = [a, b, c].map(|i| points[i as usize]);
+			let area = (b - a).wedge(c - a) / 2.0;
+			assert!(area >= 0.0, "clockwise triangle {:?}", [a, b, c]);
+			total_area += area;
+		}
+
+		// Triangles never overlap
+		for x in 0..40 {
+			for y in 0..40 {
+				let sample = Vec2::new(x as f32 * 0.5 - 10.0 + 0.1234, y as f32 * 0.5 - 10.0 + 0.0567);
+				let covering = triangles.iter()
+					.filter(|&&[a, b, c]| {
+						let [a, b, c] = [a, b, c].map(|i| points[i as usize]);
+						(b - a).wedge(sample - a) > 0.0 && (c - b).wedge(sample - b) > 0.0 && (a - c).wedge(sample - c) > 0.0
+					})
+					.count();
+
+				assert!(covering <= 1, "overlapping triangles at {:?}", sample);
+			}
+		}
+
+		let expected_area = polygon_area(outer) - holes.iter().map(|hole| polygon_area(hole)).sum::<f32>();
+		assert!((total_area - expected_area).abs() < 0.001 * expected_area.max(1.0), "{} != {}", total_area, expected_area);
+
+		triangles
+	}
+
+	fn square(center: Vec2, extent: f32) -> Vec<Vec2> {
+		vec![
+			center + Vec2::new(-extent, -extent),
+			center + Vec2::new(extent, -extent),
+			center + Vec2::new(extent, extent),
+			center + Vec2::new(-extent, extent),
+		]
+	}
+
+	#[test]
+	fn test_convex() {
+		assert_eq!(check(&square(Vec2::zero(), 1.0), &[]).len(), 2);
+
+		let circle: Vec<Vec2> = (0..64).map(|i| Vec2::from_angle(i as f32 / 64.0 * TAU) * 10.0).collect();
+		assert_eq!(check(&circle, &[]).len(), 62);
+
+		// Clockwise input gives counter-clockwise output
+		let mut clockwise = circle.clone();
+		clockwise.reverse();
+		assert_eq!(check(&clockwise, &[]).len(), 62);
+
+		assert!(triangulate_polygon(&[Vec2::zero(), Vec2::one()]).is_empty());
+	}
+
+	#[test]
+	fn test_concave() {
+		let l_shape = [
+			Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 1.0),
+			Vec2::new(1.0, 1.0), Vec2::new(1.0, 2.0), Vec2::new(0.0, 2.0),
+		];
+		assert_eq!(check(&l_shape, &[]).len(), 4);
+
+		// A comb with deep teeth
+		let mut comb = vec![Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0)];
+		for i in (0..5).rev() {
+			let x = i as f32 * 2.0;
+			comb.extend([Vec2::new(x + 2.0, 5.0), Vec2::new(x + 1.0, 5.0), Vec2::new(x + 1.0, 1.0), Vec2::new(x, 1.0)]);
+		}
+		assert!(check(&comb, &[]).len() <= comb.len() - 2);
+
+		// A star
+		let star: Vec<Vec2> = (0..10)
+			.map(|i| Vec2::from_angle(i as f32 / 10.0 * TAU) * if i % 2 == 0 { 5.0 } else { 1.0 })
+			.collect();
+		assert_eq!(check(&star, &[]).len(), 8);
+
+		// A spiral
+		let mut spiral: Vec<Vec2> = (0..40).map(|i| Vec2::from_angle(i as f32 * 0.3) * (2.0 + i as f32 * 0.2)).collect();
+		spiral.extend((0..40).rev().map(|i| Vec2::from_angle(i as f32 * 0.3) * (1.5 + i as f32 * 0.2)));
+		check(&spiral, &[]);
+	}
+
+	#[test]
+	fn test_degenerate_points() {
+		// Duplicate and collinear points
+		let points = [
+			Vec2::new(0.0, 0.0), Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(2.0, 0.0),
+			Vec2::new(2.0, 2.0), Vec2::new(2.0, 2.0), Vec2::new(1.0, 2.0), Vec2::new(0.0, 2.0),
+			Vec2::new(0.0, 1.0), Vec2::new(0.0, 0.0),
+		];
+
+		let triangles = check(&points, &[]);
+		assert!(triangles.len() <= 6);
+
+		// A zero width spike
+		let spike = [Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 1.0), Vec2::new(4.0, 1.0), Vec2::new(2.0, 1.0), Vec2::new(0.0, 1.0)];
+		check(&spike, &[]);
+
+		// Entirely degenerate
+		let line = [Vec2::zero(), Vec2::from_x(1.0), Vec2::from_x(2.0)];
+		assert!(triangulate_polygon(&line).is_empty());
+	}
+
+	#[test]
+	fn test_self_touching() {
+		// Two triangles touching at a vertex
+		let bowtie = [
+			Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(1.0, 1.0),
+			Vec2::new(2.0, 2.0), Vec2::new(0.0, 2.0), Vec2::new(1.0, 1.0),
+		];
+		assert_eq!(check(&bowtie, &[]).len(), 2);
+
+		// A notch reaching back to touch the outer boundary
+		let notch = [
+			Vec2::new(0.0, 0.0), Vec2::new(4.0, 0.0), Vec2::new(4.0, 4.0), Vec2::new(2.0, 4.0),
+			Vec2::new(3.0, 2.0), Vec2::new(1.0, 2.0), Vec2::new(2.0, 4.0), Vec2::new(0.0, 4.0),
+		];
+		check(&notch, &[]);
+	}
+
+	#[test]
+	fn test_holes() {
+		let outer = square(Vec2::zero(), 4.0);
+		let hole = square(Vec2::zero(), 1.0);
+
+		let triangles = check(&outer, &[&hole]);
+		assert_eq!(triangles.len(), 8);
+		assert!(triangles.iter().flatten().all(|&i| i < 8));
+
+		// Several holes, wound either way
+		let mut reversed = square(Vec2::new(-2.0, 2.0), 0.5);
+		reversed.reverse();
+		let holes: [&[Vec2]; 3] = [&square(Vec2::new(2.0, 2.0), 0.5), &reversed, &square(Vec2::new(0.0, -2.0), 1.0)];
+		check(&outer, &holes);
+
+		// Holes sharing a column, so one blocks the other's direct bridge
+		let holes: [&[Vec2]; 2] = [&square(Vec2::new(-2.0, 0.0), 0.5), &square(Vec2::new(2.0, 0.0), 0.5)];
+		check(&outer, &holes);
+
+		// Hole inside a concave outer
+		let c_shape = [
+			Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0), Vec2::new(10.0, 3.0), Vec2::new(3.0, 3.0),
+			Vec2::new(3.0, 7.0), Vec2::new(10.0, 7.0), Vec2::new(10.0, 10.0), Vec2::new(0.0, 10.0),
+		];
+		check(&c_shape, &[&square(Vec2::new(1.5, 5.0), 0.5)]);
+		check(&c_shape, &[&square(Vec2::new(8.0, 1.5), 0.5), &square(Vec2::new(1.5, 8.5), 0.5)]);
+	}
+
+	#[test]
+	fn test_holes_outside() {
+		let outer = square(Vec2::zero(), 4.0);
+		let beside = square(Vec2::new(7.0, 0.0), 1.0);
+		let inside = square(Vec2::new(-2.0, 0.0), 1.0);
+
+		// Only the outer boundary is covered, as if the hole weren't there
+		let triangles = triangulate_polygon_with_holes(&outer, &[&beside]);
+		assert_eq!(triangles.len(), 2);
+		assert!(triangles.iter().flatten().all(|&i| i < 4));
+
+		// In the notch of a concave outer boundary
+		let c_shape = [
+			Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0), Vec2::new(10.0, 3.0), Vec2::new(3.0, 3.0),
+			Vec2::new(3.0, 7.0), Vec2::new(10.0, 7.0), Vec2::new(10.0, 10.0), Vec2::new(0.0, 10.0),
+		];
+		let triangles = triangulate_polygon_with_holes(&c_shape, &[&square(Vec2::new(7.0, 5.0), 1.0)]);
+		assert!(triangles.iter().flatten().all(|&i| i < 8));
+
+		// Alongside a hole that is inside
+		let triangles = triangulate_polygon_with_holes(&outer, &[&beside, &inside]);
+		assert!(triangles.iter().flatten().all(|&i| !(4..8).contains(&i)));
+		assert_eq!(triangles.len(), 8);
+	}
+}
@@ -0,0 +1,198 @@
+use crate::math::*;
+use crate::geometry::delaunay::Delaunay2;
+use crate::geometry::polygon::Polygon2;
+
+
+/// The region of a Voronoi diagram closer to one point than to any other.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VoronoiCell {
+	/// The counter-clockwise boundary of the cell. Empty for duplicate points and points whose cell lies outside the bounds.
+	pub polygon: Polygon2,
+
+	/// The points whose cells share an edge with this one, in ascending order.
+	pub neighbors: Vec<u32>,
+}
+
+impl Delaunay2 {
+	/// Computes the Voronoi cell of each point, clipped to `bounds`.
+	///
+	/// Cells are built from the edges of the triangulation, so constrained edges that aren't Delaunay edges will give
+	/// overlapping cells. Use a triangulation without constraints for a true Voronoi diagram.
+	pub fn voronoi_cells(&self, bounds: Aabb2) -> Vec<VoronoiCell> {
+		let point_neighbors = self.point_neighbors();
+
+		// Collinear points have no triangles, so each cell is clipped against every other point instead
+		let collinear = self.triangles.is_empty();
+		let all_points: Vec<u32> = if collinear { (0..self.points.len() as u32).collect() } else { Vec::new() };
+
+		(0..self.points.len())
+			.map(|index| {
+				let site = self.points[index];
+				let candidates = if collinear { &all_points } else { &point_neighbors[index] };
+				let others = candidates.iter()
+					.copied()
+					.filter(|&other| other as usize != index && !same_point(self.points[other as usize], site));
+
+				// Duplicates are left out of the triangulation, so only the first of them gets a cell
+				let is_duplicate = if collinear {
+					self.points[..index].iter().any(|&p| same_point(p, site))
+				} else {
+					point_neighbors[index].is_empty()
+				};
+
+				if is_duplicate {
+					return VoronoiCell::default();
+				}
+
+				voronoi_cell(site, others.map(|other| (other, self.points[other as usize])), bounds)
+			})
+			.collect()
+	}
+}
+
+/// Computes the Voronoi cell of each point, clipped to `bounds`. See `Delaunay2::voronoi_cells`.
+pub fn voronoi_cells(points: &[Vec2], bounds: Aabb2) -> Vec<VoronoiCell> {
+	Delaunay2::new(points.to_vec()).voronoi_cells(bounds)
+}
+
+
+// Clips `bounds` by the bisector between `site` and each of `others`.
+// Each vertex is paired with the point whose bisector the following edge lies on, if any.
+fn voronoi_cell(site: Vec2, others: impl Iterator<Item=(u32, Vec2)>, bounds: Aabb2) -> VoronoiCell {
+	let mut cell: Vec<(Vec2, Option<u32>)> = Polygon2::from_aabb(bounds).points.into_iter()
+		.map(|point| (point, None))
+		.collect();
+
+	let mut clipped = Vec::with_capacity(cell.len() + 1);
+
+	for (other, position) in others {
+		let midpoint = (site + position) / 2.0;
+		let normal = position - site;
+		let side = |point: Vec2| (point - midpoint).dot(normal);
+
+		clipped.clear();
+		for i in 0..cell.len() {
+			let (from, label) = cell[i];
+			let (to, _) = cell[(i + 1) % cell.len()];
+			let (from_side, to_side) = (side(from), side(to));
+
+			if from_side <= 0.0 {
+				clipped.push((from, label));
+			}
+
+			if (from_side <= 0.0) != (to_side <= 0.0) {
+				let crossing = (from_side / (from_side - to_side)).lerp(from, to);
+
+				// Leaving the cell continues along the bisector, entering continues along the original edge
+				let crossing_label = if from_side <= 0.0 { Some(other) } else { label };
+				clipped.push((crossing, crossing_label));
+			}
+		}
+
+		std::mem::swap(&mut cell, &mut clipped);
+		if cell.is_empty() {
+			break
+		}
+	}
+
+	// Vertices can be duplicated where bisectors pass through corners. The label of the edge that follows is kept.
+	cell.dedup_by(|next, previous| {
+		let duplicate = (next.0 - previous.0).square_length() < 1.0e-12;
+		if duplicate {
+			previous.1 = next.1;
+		}
+		duplicate
+	});
+	while cell.len() > 1 && (cell[0].0 - cell[cell.len() - 1].0).square_length() < 1.0e-12 {
+		cell.pop();
+	}
+
+	if cell.len() < 3 {
+		return VoronoiCell::default();
+	}
+
+	let mut neighbors: Vec<u32> = cell.iter().filter_map(|&(_, label)| label).collect();
+	neighbors.sort();
+	neighbors.dedup();
+
+	VoronoiCell {
+		polygon: cell.into_iter().map(|(point, _)| point).collect(),
+		neighbors,
+	}
+}
+
+fn same_point(a: Vec2, b: Vec2) -> bool {
+	a.x == b.x && a.y == b.y
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+	use rand::{Rng, SeedableRng};
+	use rand::rngs::StdRng;
+
+	#[test]
+	fn test_grid() {
+		let points: Vec<Vec2> = (0..9).map(|i| Vec2::new((i % 3) as f32, (i / 3) as f32)).collect();
+		let bounds = Aabb2::new(Vec2::splat(-0.5), Vec2::splat(2.5));
+		let cells = voronoi_cells(&points, bounds);
+
+		for cell in cells.iter() {
+			assert_almost_eq!(cell.polygon.area(), 1.0);
+			assert_eq!(cell.polygon.winding(), Winding::CounterClockwise);
+		}
+
+		// Diagonal neighbors only touch at a corner
+		assert_eq!(cells[4].neighbors, [1, 3, 5, 7]);
+		assert_eq!(cells[0].neighbors, [1, 3]);
+		assert_eq!(cells[4].polygon.len(), 4);
+	}
+
+	#[test]
+	fn test_random() {
+		let mut rng = StdRng::seed_from_u64(5);
+		let bounds = Aabb2::new(Vec2::splat(-10.0), Vec2::splat(10.0));
+
+		for _ in 0..10 {
+			let mut points: Vec<Vec2> = (0..200).map(|_| Vec2::new(rng.random_range(-12.0..12.0), rng.random_range(-12.0..12.0))).collect();
+			points.push(points[0]);
+
+			let cells = voronoi_cells(&points, bounds);
+			let area: f32 = cells.iter().map(|cell| cell.polygon.area()).sum();
+			assert!((area - 400.0).abs() < 0.01);
+			assert!(cells[200].polygon.is_empty());
+
+			for (index, cell) in cells.iter().enumerate() {
+				if bounds.contains_point(points[index]) && index != 200 {
+					assert!(cell.polygon.contains_point(points[index]));
+				}
+
+				// Every point in the cell is closest to its site
+				if !cell.polygon.is_empty() {
+					let centroid = cell.polygon.centroid();
+					let distance = (centroid - points[index]).length();
+					assert!(points.iter().all(|&p| (centroid - p).length() > distance - 1.0e-3));
+				}
+
+				for &neighbor in cell.neighbors.iter() {
+					assert!(cells[neighbor as usize].neighbors.contains(&(index as u32)));
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_collinear() {
+		let points: Vec<Vec2> = (0..4).map(|i| Vec2::from_x(i as f32)).collect();
+		let cells = voronoi_cells(&points, Aabb2::new(Vec2::new(-0.5, -1.0), Vec2::new(3.5, 1.0)));
+
+		for cell in cells.iter() {
+			assert_almost_eq!(cell.polygon.area(), 2.0);
+		}
+
+		assert_eq!(cells[1].neighbors, [0, 2]);
+	}
+}
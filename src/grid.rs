@@ -0,0 +1,492 @@
+use std::ops::{Index, IndexMut};
+
+use crate::math::*;
+
+
+/// A dense 2D array indexed by `Vec2i`, stored row by row, for things like tile maps and heightfields.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "GridDesc<Vec2i, T>"))]
+pub struct Grid2<T> {
+	size: Vec2i,
+	cells: Vec<T>,
+}
+
+/// A dense 3D array indexed by `Vec3i`, stored row by row and then slice by slice, for things like voxel chunks.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "GridDesc<Vec3i, T>"))]
+pub struct Grid3<T> {
+	size: Vec3i,
+	cells: Vec<T>,
+}
+
+// Deserialized cells may not fill the grid, so deserialization goes through the same checks as `from_vec`.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct GridDesc<V, T> {
+	size: V,
+	cells: Vec<T>,
+}
+
+const NEIGHBORS_4: [Vec2i; 4] = [
+	Vec2i::new(1, 0), Vec2i::new(0, 1), Vec2i::new(-1, 0), Vec2i::new(0, -1),
+];
+
+const NEIGHBORS_8: [Vec2i; 8] = [
+	Vec2i::new(1, 0), Vec2i::new(1, 1), Vec2i::new(0, 1), Vec2i::new(-1, 1),
+	Vec2i::new(-1, 0), Vec2i::new(-1, -1), Vec2i::new(0, -1), Vec2i::new(1, -1),
+];
+
+const NEIGHBORS_6: [Vec3i; 6] = [
+	Vec3i::new(1, 0, 0), Vec3i::new(-1, 0, 0),
+	Vec3i::new(0, 1, 0), Vec3i::new(0, -1, 0),
+	Vec3i::new(0, 0, 1), Vec3i::new(0, 0, -1),
+];
+
+macro_rules! impl_grid {
+	($ty:ident, $vec:ident, $aabb:ident, $positions:ident) => {
+		/// Constructors
+		impl<T> $ty<T> {
+			pub fn new(size: $vec, fill: T) -> $ty<T>
+				where T: Clone
+			{
+				$ty::from_fn(size, |_| fill.clone())
+			}
+
+			/// Creates a grid by calling `f` with each position in turn.
+			pub fn from_fn(size: $vec, f: impl FnMut($vec) -> T) -> $ty<T> {
+				let size = size.to_array().map(|size| size.max(0));
+				let size = $vec::from(size);
+				$ty { size, cells: $positions($aabb::new($vec::zero(), size)).map(f).collect() }
+			}
+
+			/// Creates a grid from cells stored row by row, panicking if there aren't exactly enough.
+			pub fn from_vec(size: $vec, cells: Vec<T>) -> $ty<T> {
+				match $ty::checked_from_vec(size, cells) {
+					Ok(grid) => grid,
+					Err(message) => panic!("{message}"),
+				}
+			}
+
+			fn checked_from_vec(size: $vec, cells: Vec<T>) -> Result<$ty<T>, String> {
+				if size.to_array().iter().any(|&size| size < 0) {
+					return Err(format!("grid size {size:?} is negative"));
+				}
+
+				let count = size.to_array().iter().try_fold(1usize, |count, &size| count.checked_mul(size as usize));
+				if count != Some(cells.len()) {
+					return Err(format!("wrong number of cells for grid of size {size:?}"));
+				}
+
+				Ok($ty { size, cells })
+			}
+		}
+
+		/// Properties
+		impl<T> $ty<T> {
+			pub fn size(&self) -> $vec {
+				self.size
+			}
+
+			/// The range of valid positions, from zero up to the size.
+			pub fn bounds(&self) -> $aabb {
+				$aabb::new($vec::zero(), self.size)
+			}
+
+			pub fn len(&self) -> usize {
+				self.cells.len()
+			}
+
+			pub fn is_empty(&self) -> bool {
+				self.cells.is_empty()
+			}
+
+			pub fn as_slice(&self) -> &[T] {
+				&self.cells
+			}
+
+			pub fn as_mut_slice(&mut self) -> &mut [T] {
+				&mut self.cells
+			}
+
+			pub fn into_vec(self) -> Vec<T> {
+				self.cells
+			}
+
+			pub fn iter(&self) -> impl Iterator<Item=($vec, &T)> + '_ {
+				$positions(self.bounds()).zip(&self.cells)
+			}
+
+			pub fn iter_mut(&mut self) -> impl Iterator<Item=($vec, &mut T)> + '_ {
+				$positions(self.bounds()).zip(&mut self.cells)
+			}
+		}
+
+		/// Queries
+		impl<T> $ty<T> {
+			pub fn contains(&self, position: $vec) -> bool {
+				self.bounds().contains_point(position)
+			}
+
+			pub fn get(&self, position: $vec) -> Option<&T> {
+				let index = self.index_of(position)?;
+				Some(&self.cells[index])
+			}
+
+			pub fn get_mut(&mut self, position: $vec) -> Option<&mut T> {
+				let index = self.index_of(position)?;
+				Some(&mut self.cells[index])
+			}
+
+			/// Copies the cells within `region` into a new grid, leaving out any part of `region` outside of this grid.
+			pub fn copy_region(&self, region: $aabb) -> $ty<T>
+				where T: Clone
+			{
+				let region = region.intersection(self.bounds());
+				$ty::from_fn(region.size(), |position| self[position + region.lower].clone())
+			}
+
+			/// Creates a grid of the results of `f` for each cell.
+			pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> $ty<U> {
+				$ty { size: self.size, cells: self.cells.iter().map(|cell| f(cell)).collect() }
+			}
+
+			/// Creates a grid of the results of `f` for each pair of cells at the same position, panicking if the grids are different sizes.
+			pub fn zip<U, V>(&self, other: &$ty<U>, mut f: impl FnMut(&T, &U) -> V) -> $ty<V> {
+				assert_eq!(self.size, other.size, "zipped grids must be the same size");
+				$ty { size: self.size, cells: self.cells.iter().zip(&other.cells).map(|(a, b)| f(a, b)).collect() }
+			}
+		}
+
+		/// Modification
+		impl<T> $ty<T> {
+			pub fn fill(&mut self, value: T)
+				where T: Clone
+			{
+				self.cells.fill(value);
+			}
+
+			/// Changes the size of the grid, keeping cells at the same positions and filling new ones with `fill`.
+			pub fn resize(&mut self, size: $vec, fill: T)
+				where T: Clone
+			{
+				*self = $ty::from_fn(size, |position| match self.get(position) {
+					Some(cell) => cell.clone(),
+					None => fill.clone(),
+				});
+			}
+
+			/// Copies all of `source` into this grid with its origin at `position`, leaving out any part that doesn't fit.
+			pub fn blit(&mut self, source: &$ty<T>, position: $vec)
+				where T: Clone
+			{
+				let region = $aabb::new(position, position + source.size).intersection(self.bounds());
+				for target in $positions(region) {
+					self[target] = source[target - position].clone();
+				}
+			}
+		}
+
+		#[cfg(feature = "serde")]
+		impl<T> TryFrom<GridDesc<$vec, T>> for $ty<T> {
+			type Error = String;
+
+			fn try_from(GridDesc{size, cells}: GridDesc<$vec, T>) -> Result<$ty<T>, String> {
+				$ty::checked_from_vec(size, cells)
+			}
+		}
+
+		impl<T> Index<$vec> for $ty<T> {
+			type Output = T;
+
+			fn index(&self, position: $vec) -> &T {
+				match self.index_of(position) {
+					Some(index) => &self.cells[index],
+					None => panic!("position {position:?} is outside of grid of size {:?}", self.size),
+				}
+			}
+		}
+
+		impl<T> IndexMut<$vec> for $ty<T> {
+			fn index_mut(&mut self, position: $vec) -> &mut T {
+				match self.index_of(position) {
+					Some(index) => &mut self.cells[index],
+					None => panic!("position {position:?} is outside of grid of size {:?}", self.size),
+				}
+			}
+		}
+	}
+}
+
+impl_grid!(Grid2, Vec2i, Aabb2i, positions_2d);
+impl_grid!(Grid3, Vec3i, Aabb3i, positions_3d);
+
+
+impl<T> Grid2<T> {
+	pub fn width(&self) -> i32 {
+		self.size.x
+	}
+
+	pub fn height(&self) -> i32 {
+		self.size.y
+	}
+
+	pub fn row(&self, y: i32) -> &[T] {
+		let start = self.row_start(y);
+		&self.cells[start..start + self.size.x as usize]
+	}
+
+	pub fn row_mut(&mut self, y: i32) -> &mut [T] {
+		let start = self.row_start(y);
+		&mut self.cells[start..start + self.size.x as usize]
+	}
+
+	pub fn rows(&self) -> impl Iterator<Item=&[T]> + '_ {
+		(0..self.size.y).map(|y| self.row(y))
+	}
+
+	pub fn column(&self, x: i32) -> impl Iterator<Item=&T> + '_ {
+		assert!(0 <= x && x < self.size.x, "column {x} is outside of grid of size {:?}", self.size);
+		self.cells.iter().skip(x as usize).step_by(self.size.x as usize)
+	}
+
+	pub fn column_mut(&mut self, x: i32) -> impl Iterator<Item=&mut T> + '_ {
+		assert!(0 <= x && x < self.size.x, "column {x} is outside of grid of size {:?}", self.size);
+		self.cells.iter_mut().skip(x as usize).step_by(self.size.x as usize)
+	}
+
+	/// The positions next to `position` along each axis that are within the grid.
+	pub fn neighbors_4(&self, position: Vec2i) -> impl Iterator<Item=Vec2i> + '_ {
+		self.neighbors(position, &NEIGHBORS_4)
+	}
+
+	/// The positions next to `position`, including diagonally, that are within the grid.
+	pub fn neighbors_8(&self, position: Vec2i) -> impl Iterator<Item=Vec2i> + '_ {
+		self.neighbors(position, &NEIGHBORS_8)
+	}
+
+	fn neighbors<'a>(&'a self, position: Vec2i, offsets: &'a [Vec2i]) -> impl Iterator<Item=Vec2i> + 'a {
+		offsets.iter()
+			.map(move |&offset| position + offset)
+			.filter(|&neighbor| self.contains(neighbor))
+	}
+
+	fn index_of(&self, position: Vec2i) -> Option<usize> {
+		self.contains(position).then(|| (position.x + position.y * self.size.x) as usize)
+	}
+
+	fn row_start(&self, y: i32) -> usize {
+		assert!(0 <= y && y < self.size.y, "row {y} is outside of grid of size {:?}", self.size);
+		(y * self.size.x) as usize
+	}
+}
+
+impl<T> Grid3<T> {
+	pub fn width(&self) -> i32 {
+		self.size.x
+	}
+
+	pub fn height(&self) -> i32 {
+		self.size.y
+	}
+
+	pub fn depth(&self) -> i32 {
+		self.size.z
+	}
+
+	/// The cells along x at height `y` and depth `z`.
+	pub fn row(&self, y: i32, z: i32) -> &[T] {
+		let start = self.row_start(y, z);
+		&self.cells[start..start + self.size.x as usize]
+	}
+
+	pub fn row_mut(&mut self, y: i32, z: i32) -> &mut [T] {
+		let start = self.row_start(y, z);
+		&mut self.cells[start..start + self.size.x as usize]
+	}
+
+	/// The cells at depth `z`, stored row by row.
+	pub fn slice(&self, z: i32) -> &[T] {
+		let (start, length) = self.slice_range(z);
+		&self.cells[start..start + length]
+	}
+
+	pub fn slice_mut(&mut self, z: i32) -> &mut [T] {
+		let (start, length) = self.slice_range(z);
+		&mut self.cells[start..start + length]
+	}
+
+	/// The positions next to `position` along each axis that are within the grid.
+	pub fn neighbors_6(&self, position: Vec3i) -> impl Iterator<Item=Vec3i> + '_ {
+		NEIGHBORS_6.iter()
+			.map(move |&offset| position + offset)
+			.filter(|&neighbor| self.contains(neighbor))
+	}
+
+	/// The positions next to `position`, including diagonally, that are within the grid.
+	pub fn neighbors_26(&self, position: Vec3i) -> impl Iterator<Item=Vec3i> + '_ {
+		positions_3d(Aabb3i::new(Vec3i::splat(-1), Vec3i::splat(2)))
+			.filter(|&offset| offset != Vec3i::zero())
+			.map(move |offset| position + offset)
+			.filter(|&neighbor| self.contains(neighbor))
+	}
+
+	fn index_of(&self, position: Vec3i) -> Option<usize> {
+		self.contains(position).then(|| (position.x + (position.y + position.z * self.size.y) * self.size.x) as usize)
+	}
+
+	fn row_start(&self, y: i32, z: i32) -> usize {
+		assert!(0 <= y && y < self.size.y && 0 <= z && z < self.size.z, "row {y}, {z} is outside of grid of size {:?}", self.size);
+		((y + z * self.size.y) * self.size.x) as usize
+	}
+
+	fn slice_range(&self, z: i32) -> (usize, usize) {
+		assert!(0 <= z && z < self.size.z, "slice {z} is outside of grid of size {:?}", self.size);
+		let length = (self.size.x * self.size.y) as usize;
+		(z as usize * length, length)
+	}
+}
+
+
+// Every position in `region`, along x first, then y.
+fn positions_2d(region: Aabb2i) -> impl Iterator<Item=Vec2i> {
+	let Aabb2i { lower, upper } = region;
+	(lower.y..upper.y).flat_map(move |y| (lower.x..upper.x).map(move |x| Vec2i::new(x, y)))
+}
+
+// Every position in `region`, along x first, then y, then z.
+fn positions_3d(region: Aabb3i) -> impl Iterator<Item=Vec3i> {
+	let Aabb3i { lower, upper } = region;
+	(lower.z..upper.z).flat_map(move |z| positions_2d(Aabb2i::new(lower.to_xy(), upper.to_xy())).map(move |position| position.extend(z)))
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn test_grid2() {
+		let mut grid = Grid2::from_fn(Vec2i::new(4, 3), |position| position.x + position.y * 10);
+		assert_eq!(grid.len(), 12);
+		assert_eq!(grid[Vec2i::new(3, 2)], 23);
+		assert_eq!(grid.get(Vec2i::new(4, 0)), None);
+		assert_eq!(grid.get(Vec2i::new(0, -1)), None);
+		assert_eq!(grid.bounds().size(), Vec2i::new(4, 3));
+
+		assert_eq!(grid.row(1), &[10, 11, 12, 13]);
+		assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![2, 12, 22]);
+		assert_eq!(grid.rows().count(), 3);
+
+		for cell in grid.column_mut(0) {
+			*cell = -1;
+		}
+		grid.row_mut(2)[3] = 99;
+		*grid.get_mut(Vec2i::new(1, 1)).unwrap() = 50;
+		grid[Vec2i::new(2, 0)] = 7;
+		assert_eq!(grid.as_slice(), &[-1, 1, 7, 3, -1, 50, 12, 13, -1, 21, 22, 99]);
+
+		let (position, &value) = grid.iter().find(|&(_, &value)| value == 50).unwrap();
+		assert_eq!(position, Vec2i::new(1, 1));
+		assert_eq!(value, 50);
+
+		let doubled = grid.map(|&value| value * 2);
+		let sums = grid.zip(&doubled, |&a, &b| a + b);
+		assert_eq!(sums[Vec2i::new(3, 2)], 297);
+
+		assert_eq!(Grid2::from_vec(Vec2i::new(2, 2), vec![1, 2, 3, 4])[Vec2i::new(0, 1)], 3);
+
+		// Rows of a grid with no width are still there, just empty
+		let narrow = Grid2::<i32>::new(Vec2i::new(0, 3), 0);
+		assert_eq!(narrow.rows().collect::<Vec<_>>(), vec![&[] as &[i32]; 3]);
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn test_deserialize_checks_size() {
+		use super::GridDesc;
+
+		let grid = Grid2::try_from(GridDesc { size: Vec2i::new(2, 2), cells: vec![1, 2, 3, 4] }).unwrap();
+		assert_eq!(grid[Vec2i::new(1, 1)], 4);
+
+		assert!(Grid2::try_from(GridDesc { size: Vec2i::new(2, 3), cells: vec![1, 2, 3, 4] }).is_err());
+		assert!(Grid2::try_from(GridDesc { size: Vec2i::new(-2, -2), cells: vec![1, 2, 3, 4] }).is_err());
+		assert!(Grid3::try_from(GridDesc { size: Vec3i::new(2, 1, 2), cells: vec![1, 2, 3] }).is_err());
+		assert!(Grid3::<u8>::try_from(GridDesc { size: Vec3i::splat(i32::MAX), cells: Vec::new() }).is_err());
+	}
+
+	#[test]
+	fn test_regions() {
+		let mut grid = Grid2::from_fn(Vec2i::new(4, 4), |position| position.x + position.y * 4);
+
+		let region = grid.copy_region(Aabb2i::new(Vec2i::new(2, 1), Vec2i::new(6, 3)));
+		assert_eq!(region.size(), Vec2i::new(2, 2));
+		assert_eq!(region.as_slice(), &[6, 7, 10, 11]);
+		assert!(grid.copy_region(Aabb2i::new(Vec2i::splat(5), Vec2i::splat(8))).is_empty());
+
+		let stamp = Grid2::new(Vec2i::new(3, 2), -1);
+		grid.blit(&stamp, Vec2i::new(-1, 3));
+		assert_eq!(grid.row(3), &[-1, -1, 14, 15]);
+		assert_eq!(grid.row(2), &[8, 9, 10, 11]);
+
+		grid.resize(Vec2i::new(2, 5), 0);
+		assert_eq!(grid.size(), Vec2i::new(2, 5));
+		assert_eq!(grid.as_slice(), &[0, 1, 4, 5, 8, 9, -1, -1, 0, 0]);
+
+		grid.fill(3);
+		assert!(grid.iter().all(|(_, &value)| value == 3));
+	}
+
+	#[test]
+	fn test_neighbors() {
+		let grid = Grid2::new(Vec2i::new(3, 3), ());
+		assert_eq!(grid.neighbors_4(Vec2i::new(1, 1)).count(), 4);
+		assert_eq!(grid.neighbors_8(Vec2i::new(1, 1)).count(), 8);
+		assert_eq!(grid.neighbors_8(Vec2i::zero()).collect::<Vec<_>>(), vec![Vec2i::new(1, 0), Vec2i::new(1, 1), Vec2i::new(0, 1)]);
+		assert_eq!(grid.neighbors_4(Vec2i::new(2, 1)).count(), 3);
+
+		let grid = Grid3::new(Vec3i::splat(3), ());
+		assert_eq!(grid.neighbors_6(Vec3i::one()).count(), 6);
+		assert_eq!(grid.neighbors_26(Vec3i::one()).count(), 26);
+		assert_eq!(grid.neighbors_6(Vec3i::zero()).count(), 3);
+		assert_eq!(grid.neighbors_26(Vec3i::zero()).count(), 7);
+		assert!(grid.neighbors_26(Vec3i::one()).all(|neighbor| neighbor != Vec3i::one()));
+	}
+
+	#[test]
+	fn test_grid3() {
+		let mut grid = Grid3::from_fn(Vec3i::new(3, 2, 2), |position| position.x + position.y * 10 + position.z * 100);
+		assert_eq!(grid[Vec3i::new(2, 1, 1)], 112);
+		assert_eq!(grid.get(Vec3i::new(0, 0, 2)), None);
+		assert_eq!(grid.row(1, 1), &[110, 111, 112]);
+		assert_eq!(grid.slice(1), &[100, 101, 102, 110, 111, 112]);
+		assert_eq!(grid.bounds().volume(), 12);
+
+		grid.slice_mut(0).fill(0);
+		grid.row_mut(0, 1)[1] = 5;
+		assert_eq!(grid.as_slice(), &[0, 0, 0, 0, 0, 0, 100, 5, 102, 110, 111, 112]);
+
+		let region = grid.copy_region(Aabb3i::new(Vec3i::new(1, 0, 1), Vec3i::new(3, 1, 2)));
+		assert_eq!(region.as_slice(), &[5, 102]);
+
+		let mut target = Grid3::new(Vec3i::splat(2), -1);
+		target.blit(&region, Vec3i::new(1, 1, 0));
+		assert_eq!(target.as_slice(), &[-1, -1, -1, 5, -1, -1, -1, -1]);
+
+		target.resize(Vec3i::new(2, 2, 3), 9);
+		assert_eq!(target.slice(2), &[9, 9, 9, 9]);
+		assert_eq!(target[Vec3i::new(1, 1, 0)], 5);
+
+		let mask = target.map(|&value| value > 0);
+		assert_eq!(target.zip(&mask, |&value, &positive| if positive { value } else { 0 }).as_slice().iter().sum::<i32>(), 41);
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_out_of_bounds() {
+		let grid = Grid2::new(Vec2i::new(2, 2), 0);
+		let _ = grid[Vec2i::new(2, 0)];
+	}
+}
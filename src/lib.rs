@@ -3,5 +3,14 @@ pub use rand;
 pub mod math;
 pub use math::*;
 
+pub mod animation;
+pub use animation::*;
+
+pub mod geometry;
+pub use geometry::*;
+
+pub mod grid;
+pub use grid::*;
+
 pub mod defer;
 pub use defer::defer;
@@ -7,17 +7,25 @@ pub mod aabb;
 pub mod lerp;
 pub mod color;
 pub mod plane;
+pub mod line;
 pub mod vector;
 pub mod matrix;
 pub mod quaternion;
+pub mod bezier;
+pub mod spline;
+pub mod curve;
 
 pub use aabb::*;
 pub use lerp::*;
 pub use plane::*;
+pub use line::*;
 pub use color::*;
 pub use vector::*;
 pub use matrix::*;
 pub use quaternion::*;
+pub use bezier::*;
+pub use spline::*;
+pub use curve::*;
 
 
 
@@ -1,4 +1,4 @@
-use crate::{Vec2, ToVec2Scalar};
+use crate::{Vec2, ToVec2Scalar, Lerp};
 
 /// A Closed 2D Range - that is min and max count as being inside the bounds of the Aabb2
 #[derive(Debug, Copy, Clone)]
@@ -102,6 +102,24 @@ impl Aabb2 {
 		self.min.x <= point.x && point.x <= self.max.x
 		&& self.min.y <= point.y && point.y <= self.max.y
 	}
+
+	/// Whether `other` is entirely inside the box.
+	pub fn contains_rect(&self, other: Aabb2) -> bool {
+		self.min.x <= other.min.x && other.max.x <= self.max.x
+		&& self.min.y <= other.min.y && other.max.y <= self.max.y
+	}
+
+	/// The point within the box closest to `point`, or `point` itself if the box is empty.
+	pub fn closest_point(&self, point: Vec2) -> Vec2 {
+		if self.is_empty() {
+			return point;
+		}
+
+		Vec2::new(
+			point.x.clamp(self.min.x, self.max.x),
+			point.y.clamp(self.min.y, self.max.y),
+		)
+	}
 }
 
 
@@ -143,4 +161,11 @@ impl Aabb2 {
 }
 
 
-
+impl Lerp<Aabb2> for f32 {
+	fn lerp(self, start: Aabb2, end: Aabb2) -> Aabb2 {
+		Aabb2 {
+			min: self.lerp(start.min, end.min),
+			max: self.lerp(start.max, end.max),
+		}
+	}
+}
@@ -90,6 +90,14 @@ impl Aabb2i {
 		self.grow(-amount)
 	}
 
+	/// The range covered by both `self` and `other`, which is empty if they don't overlap.
+	pub fn intersection(&self, other: Aabb2i) -> Self {
+		Aabb2i {
+			lower: Vec2i::new(self.lower.x.max(other.lower.x), self.lower.y.max(other.lower.y)),
+			upper: Vec2i::new(self.upper.x.min(other.upper.x), self.upper.y.min(other.upper.y)),
+		}
+	}
+
 	// TODO(pat.m): include or exclude upper bound point?
 	pub fn include_point(&self, point: Vec2i) -> Self {
 		Aabb2i {
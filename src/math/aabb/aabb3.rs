@@ -0,0 +1,221 @@
+use crate::{Vec3, ToVec3Scalar, Lerp, Mat3x4};
+
+/// A Closed 3D Range - that is min and max count as being inside the bounds of the Aabb3
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Aabb3 {
+	pub min: Vec3,
+	pub max: Vec3,
+}
+
+/// Constructors
+impl Aabb3 {
+	pub fn new(min: Vec3, max: Vec3) -> Aabb3 {
+		Aabb3 { min, max }
+	}
+
+	pub fn empty() -> Aabb3 {
+		Aabb3::new(
+			Vec3::splat(f32::INFINITY),
+			Vec3::splat(-f32::INFINITY)
+		)
+	}
+
+	pub fn zero() -> Aabb3 {
+		Aabb3::from_point(Vec3::zero())
+	}
+
+	pub fn from_center_extents(center: Vec3, extents: impl ToVec3Scalar) -> Aabb3 {
+		let extents = extents.to_vec3();
+		Aabb3::new(center - extents, center + extents)
+	}
+
+	pub fn from_min_size(min: Vec3, size: impl ToVec3Scalar) -> Aabb3 {
+		Aabb3::new(min, min + size.to_vec3())
+	}
+
+	pub fn from_point(center: Vec3) -> Aabb3 {
+		Aabb3::new(center, center)
+	}
+
+	pub fn from_points(points: &[Vec3]) -> Aabb3 {
+		points.iter()
+			.fold(Aabb3::empty(), |bounds, &point| bounds.include_point(point))
+	}
+}
+
+/// Properties
+impl Aabb3 {
+	pub fn is_empty(&self) -> bool {
+		self.min.x > self.max.x
+		|| self.min.y > self.max.y
+		|| self.min.z > self.max.z
+	}
+
+	pub fn center(&self) -> Vec3 {
+		(self.min + self.max) / 2.0
+	}
+
+	pub fn size(&self) -> Vec3 {
+		if self.is_empty() {
+			Vec3::zero()
+		} else {
+			self.max - self.min
+		}
+	}
+
+	pub fn extents(&self) -> Vec3 {
+		self.size() / 2.0
+	}
+
+	pub fn volume(&self) -> f32 {
+		let Vec3{x, y, z} = self.size();
+		x * y * z
+	}
+
+	pub fn surface_area(&self) -> f32 {
+		let Vec3{x, y, z} = self.size();
+		2.0 * (x * y + y * z + z * x)
+	}
+
+	/// The eight corners, with bit 0 of the index selecting max x, bit 1 max y and bit 2 max z.
+	pub fn corners(&self) -> [Vec3; 8] {
+		std::array::from_fn(|i| Vec3::new(
+			if i & 1 == 0 { self.min.x } else { self.max.x },
+			if i & 2 == 0 { self.min.y } else { self.max.y },
+			if i & 4 == 0 { self.min.z } else { self.max.z },
+		))
+	}
+}
+
+
+/// Queries
+impl Aabb3 {
+	pub fn contains_point(&self, point: Vec3) -> bool {
+		self.min.x <= point.x && point.x <= self.max.x
+		&& self.min.y <= point.y && point.y <= self.max.y
+		&& self.min.z <= point.z && point.z <= self.max.z
+	}
+
+	/// Whether `other` is entirely inside the box.
+	pub fn contains_box(&self, other: Aabb3) -> bool {
+		self.min.x <= other.min.x && other.max.x <= self.max.x
+		&& self.min.y <= other.min.y && other.max.y <= self.max.y
+		&& self.min.z <= other.min.z && other.max.z <= self.max.z
+	}
+
+	/// The point within the box closest to `point`, or `point` itself if the box is empty.
+	pub fn closest_point(&self, point: Vec3) -> Vec3 {
+		if self.is_empty() {
+			return point;
+		}
+
+		Vec3::new(
+			point.x.clamp(self.min.x, self.max.x),
+			point.y.clamp(self.min.y, self.max.y),
+			point.z.clamp(self.min.z, self.max.z),
+		)
+	}
+}
+
+
+/// Modifications
+impl Aabb3 {
+	pub fn grow(&self, amount: impl ToVec3Scalar) -> Self {
+		let amount = amount.to_vec3();
+		Aabb3 {
+			min: self.min - amount,
+			max: self.max + amount,
+		}
+	}
+
+	pub fn shrink(&self, amount: impl ToVec3Scalar) -> Self {
+		self.grow(-amount.to_vec3())
+	}
+
+	pub fn translate(&self, amount: impl ToVec3Scalar) -> Self {
+		let amount = amount.to_vec3();
+		Aabb3 {
+			min: self.min + amount,
+			max: self.max + amount,
+		}
+	}
+
+	pub fn include_point(&self, point: Vec3) -> Self {
+		Aabb3 {
+			min: Vec3::new(self.min.x.min(point.x), self.min.y.min(point.y), self.min.z.min(point.z)),
+			max: Vec3::new(self.max.x.max(point.x), self.max.y.max(point.y), self.max.z.max(point.z)),
+		}
+	}
+
+	pub fn include_box(&self, other: Aabb3) -> Self {
+		Aabb3 {
+			min: Vec3::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y), self.min.z.min(other.min.z)),
+			max: Vec3::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y), self.max.z.max(other.max.z)),
+		}
+	}
+
+	/// The bounds of this box after transformation.
+	pub fn transform(&self, transform: &Mat3x4) -> Self {
+		if self.is_empty() {
+			return *self;
+		}
+
+		let [x, y, z, translation] = transform.columns();
+		let extents = self.extents();
+		let transformed_extents = Vec3::new(
+			x.x.abs() * extents.x + y.x.abs() * extents.y + z.x.abs() * extents.z,
+			x.y.abs() * extents.x + y.y.abs() * extents.y + z.y.abs() * extents.z,
+			x.z.abs() * extents.x + y.z.abs() * extents.y + z.z.abs() * extents.z,
+		);
+
+		let center = self.center();
+		Aabb3::from_center_extents(x * center.x + y * center.y + z * center.z + translation, transformed_extents)
+	}
+}
+
+
+impl Lerp<Aabb3> for f32 {
+	fn lerp(self, start: Aabb3, end: Aabb3) -> Aabb3 {
+		Aabb3 {
+			min: self.lerp(start.min, end.min),
+			max: self.lerp(start.max, end.max),
+		}
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn test_properties() {
+		let aabb = Aabb3::from_min_size(Vec3::one(), Vec3::new(1.0, 2.0, 3.0));
+		assert_almost_eq!(aabb.volume(), 6.0);
+		assert_almost_eq!(aabb.surface_area(), 22.0);
+		assert_vec_eq!(aabb.center(), Vec3::new(1.5, 2.0, 2.5));
+
+		assert!(Aabb3::empty().is_empty());
+		assert_almost_eq!(Aabb3::empty().volume(), 0.0);
+		assert!(Aabb3::from_points(&aabb.corners()).contains_point(aabb.center()));
+		assert_vec_eq!(aabb.closest_point(Vec3::new(0.0, 2.5, 10.0)), Vec3::new(1.0, 2.5, 4.0));
+		assert_vec_eq!(Aabb3::empty().closest_point(Vec3::new(1.0, 2.0, 3.0)), Vec3::new(1.0, 2.0, 3.0));
+	}
+
+	#[test]
+	fn test_transform() {
+		let aabb = Aabb3::from_center_extents(Vec3::zero(), Vec3::new(1.0, 2.0, 3.0));
+
+		let rotated = aabb.transform(&Mat3x4::rotate_y_translate(PI / 2.0, Vec3::from_x(5.0)));
+		assert_vec_eq!(rotated.min, Vec3::new(2.0, -2.0, -1.0));
+		assert_vec_eq!(rotated.max, Vec3::new(8.0, 2.0, 1.0));
+
+		let transform = Mat3x4::scale_rotate_translate(2.0, Quat::from_axis_angle(Vec3::new(1.0, 2.0, 3.0).normalize(), 1.0), Vec3::one());
+		let transformed = aabb.transform(&transform);
+		for corner in aabb.corners() {
+			assert!(transformed.grow(0.001).contains_point(transform * corner));
+		}
+	}
+}
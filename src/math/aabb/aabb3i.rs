@@ -0,0 +1,103 @@
+use crate::math::*;
+
+/// A Half Open 3D Range.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Aabb3i {
+	/// The inclusive lower bound of the range.
+	pub lower: Vec3i,
+
+	/// The exclusive upper bound of the range.
+	pub upper: Vec3i,
+}
+
+/// Constructors & Conversions
+impl Aabb3i {
+	pub fn new(lower: Vec3i, upper: Vec3i) -> Aabb3i {
+		Aabb3i { lower, upper }
+	}
+
+	pub fn empty() -> Aabb3i {
+		Aabb3i::new(
+			Vec3i::splat(i32::MAX),
+			Vec3i::splat(i32::MIN)
+		)
+	}
+
+	pub fn around_point(center: Vec3i, extents: Vec3i) -> Aabb3i {
+		Aabb3i::new(center - extents, center + extents)
+	}
+
+	pub fn to_aabb3(&self) -> Aabb3 {
+		if self.is_empty() {
+			Aabb3::empty()
+		} else {
+			Aabb3 {
+				min: self.lower.to_vec3(),
+				max: self.upper.to_vec3(),
+			}
+		}
+	}
+}
+
+/// Properties
+impl Aabb3i {
+	pub fn is_empty(&self) -> bool {
+		self.lower.x >= self.upper.x
+		|| self.lower.y >= self.upper.y
+		|| self.lower.z >= self.upper.z
+	}
+
+	pub fn center(&self) -> Vec3i {
+		(self.lower + self.upper) / 2
+	}
+
+	pub fn size(&self) -> Vec3i {
+		if self.is_empty() {
+			Vec3i::zero()
+		} else {
+			self.upper - self.lower
+		}
+	}
+
+	pub fn extents(&self) -> Vec3i {
+		self.size() / 2
+	}
+
+	pub fn volume(&self) -> i32 {
+		let Vec3i { x, y, z } = self.size();
+		x * y * z
+	}
+}
+
+
+/// Queries
+impl Aabb3i {
+	pub fn contains_point(&self, point: Vec3i) -> bool {
+		self.lower.x <= point.x && point.x < self.upper.x
+		&& self.lower.y <= point.y && point.y < self.upper.y
+		&& self.lower.z <= point.z && point.z < self.upper.z
+	}
+}
+
+/// Modifications
+impl Aabb3i {
+	pub fn grow(&self, amount: Vec3i) -> Self {
+		Aabb3i {
+			lower: self.lower - amount,
+			upper: self.upper + amount,
+		}
+	}
+
+	pub fn shrink(&self, amount: Vec3i) -> Self {
+		self.grow(-amount)
+	}
+
+	/// The range covered by both `self` and `other`, which is empty if they don't overlap.
+	pub fn intersection(&self, other: Aabb3i) -> Self {
+		Aabb3i {
+			lower: Vec3i::new(self.lower.x.max(other.lower.x), self.lower.y.max(other.lower.y), self.lower.z.max(other.lower.z)),
+			upper: Vec3i::new(self.upper.x.min(other.upper.x), self.upper.y.min(other.upper.y), self.upper.z.min(other.upper.z)),
+		}
+	}
+}
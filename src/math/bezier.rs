@@ -0,0 +1,478 @@
+use crate::math::*;
+
+
+/// A quadratic bezier curve in 2D, starting at `p0`, ending at `p2` and pulled towards `p1`.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuadBezier2 {
+	pub p0: Vec2,
+	pub p1: Vec2,
+	pub p2: Vec2,
+}
+
+/// A quadratic bezier curve in 3D, starting at `p0`, ending at `p2` and pulled towards `p1`.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuadBezier3 {
+	pub p0: Vec3,
+	pub p1: Vec3,
+	pub p2: Vec3,
+}
+
+/// A cubic bezier curve in 2D, starting at `p0`, ending at `p3` and pulled towards `p1` and `p2`.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CubicBezier2 {
+	pub p0: Vec2,
+	pub p1: Vec2,
+	pub p2: Vec2,
+	pub p3: Vec2,
+}
+
+/// A cubic bezier curve in 3D, starting at `p0`, ending at `p3` and pulled towards `p1` and `p2`.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CubicBezier3 {
+	pub p0: Vec3,
+	pub p1: Vec3,
+	pub p2: Vec3,
+	pub p3: Vec3,
+}
+
+
+macro_rules! impl_bezier_common {
+	($ty:ident, $vec:ident) => {
+		impl $ty {
+			/// Approximates the parameter of the point on the curve closest to `point`.
+			pub fn nearest_t(&self, point: $vec) -> f32 {
+				// Find coarse local minima, then refine each by minimising the squared distance with Newton's method.
+				const COARSE_SAMPLES: usize = 16;
+
+				let distance = |t: f32| (self.eval(t) - point).square_length();
+				let coarse: [f32; COARSE_SAMPLES + 1] = std::array::from_fn(|i| distance(i as f32 / COARSE_SAMPLES as f32));
+
+				let mut best_t = 0.0;
+				let mut best_distance = f32::INFINITY;
+
+				for i in 0..=COARSE_SAMPLES {
+					let is_local_minimum = (i == 0 || coarse[i] <= coarse[i-1])
+						&& (i == COARSE_SAMPLES || coarse[i] <= coarse[i+1]);
+
+					if !is_local_minimum {
+						continue
+					}
+
+					let (t, distance) = self.refine_nearest_t(point, i as f32 / COARSE_SAMPLES as f32, coarse[i]);
+					if distance < best_distance {
+						best_t = t;
+						best_distance = distance;
+					}
+				}
+
+				best_t
+			}
+
+			fn refine_nearest_t(&self, point: $vec, mut t: f32, mut distance: f32) -> (f32, f32) {
+				for _ in 0..8 {
+					let diff = self.eval(t) - point;
+					let d1 = self.derivative(t);
+					let d2 = self.second_derivative(t);
+
+					let numerator = diff.dot(d1);
+					let denominator = d1.dot(d1) + diff.dot(d2);
+					if denominator.abs() < 1.0e-6 {
+						break
+					}
+
+					// Newton's method can head towards a maximum or overshoot, so only accept improvements
+					let next_t = (t - numerator / denominator).clamp(0.0, 1.0);
+					let next_distance = (self.eval(next_t) - point).square_length();
+					if next_distance >= distance {
+						break
+					}
+
+					t = next_t;
+					distance = next_distance;
+				}
+
+				(t, distance)
+			}
+
+			pub fn nearest_point(&self, point: $vec) -> $vec {
+				self.eval(self.nearest_t(point))
+			}
+
+			pub fn distance_to(&self, point: $vec) -> f32 {
+				(self.nearest_point(point) - point).length()
+			}
+
+			/// Unit direction of travel at `t`.
+			pub fn tangent(&self, t: f32) -> $vec {
+				self.derivative(t).normalize()
+			}
+		}
+	};
+}
+
+macro_rules! impl_quad_bezier {
+	($ty:ident, $cubic_ty:ident, $vec:ident) => {
+		impl $ty {
+			pub fn new(p0: $vec, p1: $vec, p2: $vec) -> $ty {
+				$ty { p0, p1, p2 }
+			}
+
+			pub fn eval(&self, t: f32) -> $vec {
+				let u = 1.0 - t;
+				self.p0 * (u*u) + self.p1 * (2.0*u*t) + self.p2 * (t*t)
+			}
+
+			pub fn derivative(&self, t: f32) -> $vec {
+				((self.p1 - self.p0) * (1.0 - t) + (self.p2 - self.p1) * t) * 2.0
+			}
+
+			pub fn second_derivative(&self, _t: f32) -> $vec {
+				(self.p2 - self.p1 * 2.0 + self.p0) * 2.0
+			}
+
+			/// Splits the curve at `t` into two curves covering `[0, t]` and `[t, 1]`.
+			pub fn split(&self, t: f32) -> ($ty, $ty) {
+				let p01 = t.lerp(self.p0, self.p1);
+				let p12 = t.lerp(self.p1, self.p2);
+				let p012 = t.lerp(p01, p12);
+
+				($ty::new(self.p0, p01, p012), $ty::new(p012, p12, self.p2))
+			}
+
+			/// Converts to an equivalent cubic curve.
+			pub fn to_cubic(&self) -> $cubic_ty {
+				$cubic_ty::new(
+					self.p0,
+					(2.0f32/3.0).lerp(self.p0, self.p1),
+					(2.0f32/3.0).lerp(self.p2, self.p1),
+					self.p2,
+				)
+			}
+		}
+
+		impl_bezier_common!($ty, $vec);
+	};
+}
+
+macro_rules! impl_cubic_bezier {
+	($ty:ident, $vec:ident) => {
+		impl $ty {
+			pub fn new(p0: $vec, p1: $vec, p2: $vec, p3: $vec) -> $ty {
+				$ty { p0, p1, p2, p3 }
+			}
+
+			pub fn eval(&self, t: f32) -> $vec {
+				let u = 1.0 - t;
+				self.p0 * (u*u*u)
+					+ self.p1 * (3.0*u*u*t)
+					+ self.p2 * (3.0*u*t*t)
+					+ self.p3 * (t*t*t)
+			}
+
+			pub fn derivative(&self, t: f32) -> $vec {
+				let u = 1.0 - t;
+				((self.p1 - self.p0) * (u*u)
+					+ (self.p2 - self.p1) * (2.0*u*t)
+					+ (self.p3 - self.p2) * (t*t)) * 3.0
+			}
+
+			pub fn second_derivative(&self, t: f32) -> $vec {
+				let a = self.p2 - self.p1 * 2.0 + self.p0;
+				let b = self.p3 - self.p2 * 2.0 + self.p1;
+				t.lerp(a, b) * 6.0
+			}
+
+			/// Splits the curve at `t` into two curves covering `[0, t]` and `[t, 1]`.
+			pub fn split(&self, t: f32) -> ($ty, $ty) {
+				let p01 = t.lerp(self.p0, self.p1);
+				let p12 = t.lerp(self.p1, self.p2);
+				let p23 = t.lerp(self.p2, self.p3);
+				let p012 = t.lerp(p01, p12);
+				let p123 = t.lerp(p12, p23);
+				let p0123 = t.lerp(p012, p123);
+
+				($ty::new(self.p0, p01, p012, p0123), $ty::new(p0123, p123, p23, self.p3))
+			}
+		}
+
+		impl_bezier_common!($ty, $vec);
+	};
+}
+
+impl From<[Vec2; 3]> for QuadBezier2 {
+	fn from([p0, p1, p2]: [Vec2; 3]) -> QuadBezier2 { QuadBezier2::new(p0, p1, p2) }
+}
+
+impl From<[Vec3; 3]> for QuadBezier3 {
+	fn from([p0, p1, p2]: [Vec3; 3]) -> QuadBezier3 { QuadBezier3::new(p0, p1, p2) }
+}
+
+impl From<[Vec2; 4]> for CubicBezier2 {
+	fn from([p0, p1, p2, p3]: [Vec2; 4]) -> CubicBezier2 { CubicBezier2::new(p0, p1, p2, p3) }
+}
+
+impl From<[Vec3; 4]> for CubicBezier3 {
+	fn from([p0, p1, p2, p3]: [Vec3; 4]) -> CubicBezier3 { CubicBezier3::new(p0, p1, p2, p3) }
+}
+
+impl_quad_bezier!(QuadBezier2, CubicBezier2, Vec2);
+impl_quad_bezier!(QuadBezier3, CubicBezier3, Vec3);
+impl_cubic_bezier!(CubicBezier2, Vec2);
+impl_cubic_bezier!(CubicBezier3, Vec3);
+
+
+macro_rules! impl_curve_for_bezier {
+	($ty:ident, |$v:ident| $to_vec3:expr) => {
+		impl Curve for $ty {
+			fn domain(&self) -> (f32, f32) { (0.0, 1.0) }
+			fn position(&self, t: f32) -> Vec3 { let $v = self.eval(t); $to_vec3 }
+			fn velocity(&self, t: f32) -> Vec3 { let $v = self.derivative(t); $to_vec3 }
+		}
+	};
+}
+
+impl_curve_for_bezier!(QuadBezier2, |v| v.extend(0.0));
+impl_curve_for_bezier!(QuadBezier3, |v| v);
+impl_curve_for_bezier!(CubicBezier2, |v| v.extend(0.0));
+impl_curve_for_bezier!(CubicBezier3, |v| v);
+
+
+/// 2D specific
+impl QuadBezier2 {
+	/// Unit normal at `t`, pointing to the left of the direction of travel.
+	pub fn normal(&self, t: f32) -> Vec2 {
+		self.tangent(t).perp()
+	}
+
+	/// Signed curvature at `t` - positive when turning counter-clockwise.
+	pub fn curvature(&self, t: f32) -> f32 {
+		curvature_2d(self.derivative(t), self.second_derivative(t))
+	}
+
+	/// The tightest axis aligned box containing the curve.
+	pub fn bounds(&self) -> Aabb2 {
+		let mut bounds = Aabb2::from_points(&[self.p0, self.p2]);
+
+		// Extrema occur where the derivative on an axis is zero
+		let denominator = self.p0 - self.p1 * 2.0 + self.p2;
+		let numerator = self.p0 - self.p1;
+
+		for (n, d) in [(numerator.x, denominator.x), (numerator.y, denominator.y)] {
+			if d.abs() > 1.0e-6 {
+				let t = n / d;
+				if t > 0.0 && t < 1.0 {
+					bounds = bounds.include_point(self.eval(t));
+				}
+			}
+		}
+
+		bounds
+	}
+}
+
+impl CubicBezier2 {
+	/// Unit normal at `t`, pointing to the left of the direction of travel.
+	pub fn normal(&self, t: f32) -> Vec2 {
+		self.tangent(t).perp()
+	}
+
+	/// Signed curvature at `t` - positive when turning counter-clockwise.
+	pub fn curvature(&self, t: f32) -> f32 {
+		curvature_2d(self.derivative(t), self.second_derivative(t))
+	}
+
+	/// The tightest axis aligned box containing the curve.
+	pub fn bounds(&self) -> Aabb2 {
+		let mut bounds = Aabb2::from_points(&[self.p0, self.p3]);
+
+		// Extrema occur where the derivative on an axis is zero, which is quadratic in t
+		let a = (-self.p0 + self.p1 * 3.0 - self.p2 * 3.0 + self.p3) * 3.0;
+		let b = (self.p0 - self.p1 * 2.0 + self.p2) * 6.0;
+		let c = (self.p1 - self.p0) * 3.0;
+
+		for (a, b, c) in [(a.x, b.x, c.x), (a.y, b.y, c.y)] {
+			for t in solve_quadratic(a, b, c).into_iter().flatten() {
+				if t > 0.0 && t < 1.0 {
+					bounds = bounds.include_point(self.eval(t));
+				}
+			}
+		}
+
+		bounds
+	}
+}
+
+
+/// 3D specific
+impl QuadBezier3 {
+	/// Unit principal normal at `t`, pointing towards the center of curvature.
+	/// Undefined where the curve is straight.
+	pub fn normal(&self, t: f32) -> Vec3 {
+		principal_normal_3d(self.derivative(t), self.second_derivative(t))
+	}
+
+	pub fn curvature(&self, t: f32) -> f32 {
+		curvature_3d(self.derivative(t), self.second_derivative(t))
+	}
+}
+
+impl CubicBezier3 {
+	/// Unit principal normal at `t`, pointing towards the center of curvature.
+	/// Undefined where the curve is straight.
+	pub fn normal(&self, t: f32) -> Vec3 {
+		principal_normal_3d(self.derivative(t), self.second_derivative(t))
+	}
+
+	pub fn curvature(&self, t: f32) -> f32 {
+		curvature_3d(self.derivative(t), self.second_derivative(t))
+	}
+}
+
+
+fn curvature_2d(d1: Vec2, d2: Vec2) -> f32 {
+	d1.wedge(d2) / d1.length().powi(3)
+}
+
+fn curvature_3d(d1: Vec3, d2: Vec3) -> f32 {
+	d1.cross(d2).length() / d1.length().powi(3)
+}
+
+fn principal_normal_3d(d1: Vec3, d2: Vec3) -> Vec3 {
+	d1.cross(d2).cross(d1).normalize()
+}
+
+// Real roots of a*t^2 + b*t + c, degrading to the linear case when a is ~0.
+fn solve_quadratic(a: f32, b: f32, c: f32) -> [Option<f32>; 2] {
+	const EPSILON: f32 = 1.0e-6;
+
+	if a.abs() < EPSILON {
+		if b.abs() < EPSILON {
+			return [None, None];
+		}
+
+		return [Some(-c / b), None];
+	}
+
+	let discriminant = b*b - 4.0*a*c;
+	if discriminant < 0.0 {
+		return [None, None];
+	}
+
+	let root = discriminant.sqrt();
+	[Some((-b + root) / (2.0 * a)), Some((-b - root) / (2.0 * a))]
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn test_cubic() -> CubicBezier2 {
+		CubicBezier2::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 3.0), Vec2::new(3.0, -2.0), Vec2::new(4.0, 1.0))
+	}
+
+	fn sampled_bounds(f: impl Fn(f32) -> Vec2) -> Aabb2 {
+		let points: Vec<Vec2> = (0..=1000).map(|i| f(i as f32 / 1000.0)).collect();
+		Aabb2::from_points(&points)
+	}
+
+	#[test]
+	fn test_eval_and_derivatives() {
+		let curve = test_cubic();
+		assert_vec_eq!(curve.eval(0.0), curve.p0);
+		assert_vec_eq!(curve.eval(1.0), curve.p3);
+		assert_vec_eq!(curve.derivative(0.0), (curve.p1 - curve.p0) * 3.0);
+		assert_vec_eq!(curve.derivative(1.0), (curve.p3 - curve.p2) * 3.0);
+
+		// Compare against finite differences
+		let h = 0.001;
+		let t = 0.3;
+		let numeric_d1 = (curve.eval(t + h) - curve.eval(t - h)) / (2.0 * h);
+		let numeric_d2 = (curve.derivative(t + h) - curve.derivative(t - h)) / (2.0 * h);
+		assert!((numeric_d1 - curve.derivative(t)).length() < 0.01);
+		assert!((numeric_d2 - curve.second_derivative(t)).length() < 0.01);
+
+		let quad = QuadBezier3::new(Vec3::zero(), Vec3::new(1.0, 2.0, 0.0), Vec3::new(2.0, 0.0, 1.0));
+		let cubic = quad.to_cubic();
+		for i in 0..=10 {
+			let t = i as f32 / 10.0;
+			assert_vec_eq!(quad.eval(t), cubic.eval(t));
+			assert_vec_eq!(quad.derivative(t), cubic.derivative(t));
+		}
+	}
+
+	#[test]
+	fn test_split() {
+		let curve = test_cubic();
+		let (left, right) = curve.split(0.3);
+
+		for i in 0..=10 {
+			let t = i as f32 / 10.0;
+			assert_vec_eq!(left.eval(t), curve.eval(t * 0.3));
+			assert_vec_eq!(right.eval(t), curve.eval(0.3 + t * 0.7));
+		}
+
+		let quad = QuadBezier2::new(Vec2::zero(), Vec2::new(1.0, 1.0), Vec2::new(2.0, 0.0));
+		let (left, right) = quad.split(0.5);
+		assert_vec_eq!(left.eval(1.0), quad.eval(0.5));
+		assert_vec_eq!(right.eval(0.5), quad.eval(0.75));
+	}
+
+	#[test]
+	fn test_bounds() {
+		let curve = test_cubic();
+		let bounds = curve.bounds();
+		let expected = sampled_bounds(|t| curve.eval(t));
+		assert_vec_eq!(bounds.min, expected.min);
+		assert_vec_eq!(bounds.max, expected.max);
+
+		let quad = QuadBezier2::new(Vec2::zero(), Vec2::new(1.0, 2.0), Vec2::new(-1.0, 0.0));
+		let bounds = quad.bounds();
+		let expected = sampled_bounds(|t| quad.eval(t));
+		assert_vec_eq!(bounds.min, expected.min);
+		assert_vec_eq!(bounds.max, expected.max);
+	}
+
+	#[test]
+	fn test_nearest_point() {
+		let curve = test_cubic();
+
+		for i in 0..=10 {
+			let t = i as f32 / 10.0;
+			let on_curve = curve.eval(t);
+			assert_vec_eq!(curve.nearest_point(on_curve + curve.normal(t) * 0.05), on_curve);
+		}
+
+		// Beyond the ends, the nearest point is the end point
+		assert_vec_eq!(curve.nearest_point(Vec2::new(-2.0, -1.0)), curve.p0);
+
+		let curve = CubicBezier3::new(Vec3::zero(), Vec3::from_x(1.0), Vec3::from_x(2.0), Vec3::from_x(3.0));
+		assert_almost_eq!(curve.distance_to(Vec3::new(1.5, 2.0, 0.0)), 2.0);
+
+		// Points near a loop
+		let looped = CubicBezier2::new(Vec2::new(2.0, 0.0), Vec2::new(3.0, -2.0), Vec2::new(-1.0, -2.0), Vec2::new(4.0, 1.0));
+		for i in 0..=50 {
+			let on_curve = looped.eval(i as f32 / 50.0);
+			assert!(looped.distance_to(on_curve + Vec2::splat(0.01)) < 0.015);
+		}
+	}
+
+	#[test]
+	fn test_curvature() {
+		let quad = QuadBezier2::new(Vec2::zero(), Vec2::new(1.0, 1.0), Vec2::new(2.0, 0.0));
+		assert_almost_eq!(quad.curvature(0.5), -1.0);
+		assert_vec_eq!(quad.normal(0.5), Vec2::new(0.0, 1.0));
+
+		let quad = QuadBezier3::new(Vec3::zero(), Vec3::new(1.0, 1.0, 0.0), Vec3::new(2.0, 0.0, 0.0));
+		assert_almost_eq!(quad.curvature(0.5), 1.0);
+		assert_vec_eq!(quad.normal(0.5), Vec3::new(0.0, -1.0, 0.0));
+
+		let straight = CubicBezier2::new(Vec2::zero(), Vec2::from_x(1.0), Vec2::from_x(2.0), Vec2::from_x(3.0));
+		assert_almost_eq!(straight.curvature(0.3), 0.0);
+	}
+}
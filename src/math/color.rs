@@ -1,3 +1,4 @@
+use std::ops::{Add, Sub, Mul, Div};
 use crate::math::vector::*;
 use crate::math::Lerp;
 
@@ -167,6 +168,34 @@ impl Default for Color {
 	}
 }
 
+impl Add for Color {
+	type Output = Color;
+	fn add(self, o: Color) -> Color {
+		Color::rgba(self.r + o.r, self.g + o.g, self.b + o.b, self.a + o.a)
+	}
+}
+
+impl Sub for Color {
+	type Output = Color;
+	fn sub(self, o: Color) -> Color {
+		Color::rgba(self.r - o.r, self.g - o.g, self.b - o.b, self.a - o.a)
+	}
+}
+
+impl Mul<f32> for Color {
+	type Output = Color;
+	fn mul(self, o: f32) -> Color {
+		Color::rgba(self.r * o, self.g * o, self.b * o, self.a * o)
+	}
+}
+
+impl Div<f32> for Color {
+	type Output = Color;
+	fn div(self, o: f32) -> Color {
+		Color::rgba(self.r / o, self.g / o, self.b / o, self.a / o)
+	}
+}
+
 impl Lerp<Color> for f32 {
 	fn lerp(self, start: Color, end: Color) -> Color {
 		Color {
@@ -0,0 +1,329 @@
+use crate::math::*;
+
+
+/// A parametric curve, evaluated in 3D. 2D curves lie on the XY plane.
+pub trait Curve {
+	/// The range of valid parameters, from start to end of the curve.
+	fn domain(&self) -> (f32, f32);
+
+	fn position(&self, t: f32) -> Vec3;
+
+	/// Derivative of `position` with respect to `t`. Approximated by finite differences unless overridden.
+	fn velocity(&self, t: f32) -> Vec3 {
+		const STEP: f32 = 1.0e-3;
+
+		let (start, end) = self.domain();
+		let before = (t - STEP).max(start);
+		let after = (t + STEP).min(end);
+
+		(self.position(after) - self.position(before)) / (after - before)
+	}
+}
+
+
+/// A point on a curve with an orientation, as produced by `ArcLengthCurve::frames`.
+/// The orientation faces along the curve, so `orientation.forward()` is the tangent.
+#[derive(Copy, Clone, Debug)]
+pub struct CurveFrame {
+	pub position: Vec3,
+	pub orientation: Quat,
+
+	/// Distance along the curve.
+	pub distance: f32,
+}
+
+
+/// Wraps a curve with a lookup table mapping distance along the curve to curve parameters,
+/// allowing the curve to be traversed at constant speed.
+///
+/// The curve is parameterized by distance when used as a `Curve` itself.
+#[derive(Clone, Debug)]
+pub struct ArcLengthCurve<C> {
+	curve: C,
+	params: Vec<f32>,
+	distances: Vec<f32>,
+}
+
+impl<C: Curve> ArcLengthCurve<C> {
+	/// Builds the lookup table by measuring `samples` evenly spaced chords of `curve`.
+	/// More samples trade memory and build time for accuracy.
+	pub fn new(curve: C, samples: usize) -> ArcLengthCurve<C> {
+		let samples = samples.max(1);
+		let (start, end) = curve.domain();
+
+		let mut params = Vec::with_capacity(samples + 1);
+		let mut distances = Vec::with_capacity(samples + 1);
+
+		let mut previous = curve.position(start);
+		let mut distance = 0.0;
+
+		for i in 0..=samples {
+			let t = (i as f32 / samples as f32).lerp(start, end);
+			let position = curve.position(t);
+
+			distance += (position - previous).length();
+			previous = position;
+
+			params.push(t);
+			distances.push(distance);
+		}
+
+		ArcLengthCurve { curve, params, distances }
+	}
+
+	pub fn curve(&self) -> &C {
+		&self.curve
+	}
+
+	pub fn into_curve(self) -> C {
+		self.curve
+	}
+
+	pub fn length(&self) -> f32 {
+		self.distances.last().copied().unwrap_or(0.0)
+	}
+
+	/// Returns the curve parameter at `distance` along the curve. `distance` is clamped to the length of the curve.
+	pub fn t_at_distance(&self, distance: f32) -> f32 {
+		let index = self.distances.partition_point(|&d| d < distance);
+
+		if index == 0 {
+			return self.params[0];
+		}
+
+		let Some(&next_distance) = self.distances.get(index) else {
+			return self.params[self.params.len() - 1];
+		};
+
+		let previous_distance = self.distances[index - 1];
+		let t = inverse_lerp(previous_distance, next_distance, distance);
+		t.lerp(self.params[index - 1], self.params[index])
+	}
+
+	/// Returns the distance along the curve at curve parameter `t`.
+	pub fn distance_at_t(&self, t: f32) -> f32 {
+		let index = self.params.partition_point(|&p| p < t);
+
+		if index == 0 {
+			return 0.0;
+		}
+
+		let Some(&next_param) = self.params.get(index) else {
+			return self.length();
+		};
+
+		let local = inverse_lerp(self.params[index - 1], next_param, t);
+		local.lerp(self.distances[index - 1], self.distances[index])
+	}
+
+	pub fn position_at_distance(&self, distance: f32) -> Vec3 {
+		self.curve.position(self.t_at_distance(distance))
+	}
+
+	/// Unit tangent at `distance` along the curve.
+	pub fn tangent_at_distance(&self, distance: f32) -> Vec3 {
+		self.curve.velocity(self.t_at_distance(distance)).normalize()
+	}
+
+	/// Returns `count` points evenly spaced along the curve, including both ends.
+	pub fn sample_uniform(&self, count: usize) -> Vec<Vec3> {
+		self.uniform_distances(count)
+			.map(|distance| self.position_at_distance(distance))
+			.collect()
+	}
+
+	/// Returns `count` evenly spaced, rotation minimizing frames along the curve, including both ends.
+	/// The first frame is oriented so that its up vector is as close to `up` as possible.
+	///
+	/// Frames do not twist around the curve any more than necessary, which makes them suitable for sweeping
+	/// tubes or tracks. They are not guaranteed to match up at the ends of closed curves.
+	pub fn frames(&self, count: usize, up: Vec3) -> Vec<CurveFrame> {
+		let distances: Vec<f32> = self.uniform_distances(count).collect();
+		let positions: Vec<Vec3> = distances.iter().map(|&d| self.position_at_distance(d)).collect();
+		let tangents: Vec<Vec3> = distances.iter().map(|&d| self.tangent_at_distance(d)).collect();
+
+		rotation_minimizing_frames(&positions, &tangents, up).into_iter()
+			.zip(positions)
+			.zip(distances)
+			.map(|((orientation, position), distance)| CurveFrame { position, orientation, distance })
+			.collect()
+	}
+
+	fn uniform_distances(&self, count: usize) -> impl Iterator<Item=f32> {
+		let length = self.length();
+		let divisions = count.saturating_sub(1).max(1) as f32;
+		(0..count).map(move |i| length * i as f32 / divisions)
+	}
+}
+
+impl<C: Curve> Curve for ArcLengthCurve<C> {
+	fn domain(&self) -> (f32, f32) {
+		(0.0, self.length())
+	}
+
+	fn position(&self, distance: f32) -> Vec3 {
+		self.position_at_distance(distance)
+	}
+}
+
+
+/// Computes orientations for a sequence of points with unit `tangents`, using the double reflection method.
+/// Each orientation faces along its tangent, and the first has its up vector as close to `up` as possible.
+/// Zero or non-finite tangents, such as where a curve comes to a stop, take the orientation of the nearest usable one.
+// https://www.microsoft.com/en-us/research/wp-content/uploads/2016/12/Computation-of-rotation-minimizing-frames.pdf
+pub fn rotation_minimizing_frames(positions: &[Vec3], tangents: &[Vec3], up: Vec3) -> Vec<Quat> {
+	assert_eq!(positions.len(), tangents.len(), "Expected a tangent for each position");
+
+	if tangents.is_empty() {
+		return Vec::new();
+	}
+
+	// Leading degenerate tangents take the first usable one, and later ones the one before them
+	let usable = |tangent: &&Vec3| tangent.length() > 0.5 && tangent.length().is_finite();
+	let first_tangent = tangents.iter().find(usable).copied().unwrap_or(Vec3::from_z(-1.0));
+	let tangents: Vec<Vec3> = tangents.iter()
+		.scan(first_tangent, |previous, tangent| {
+			if usable(&tangent) {
+				*previous = *tangent;
+			}
+			Some(*previous)
+		})
+		.collect();
+
+	let mut reference = orthogonalize(up, first_tangent)
+		.or_else(|| orthogonalize(Vec3::from_x(1.0), first_tangent))
+		.or_else(|| orthogonalize(Vec3::from_y(1.0), first_tangent))
+		.unwrap();
+
+	let mut orientations = Vec::with_capacity(positions.len());
+	orientations.push(orientation_from_basis(first_tangent, reference));
+
+	for i in 1..positions.len() {
+		// Reflect the frame across the bisecting plane of the two points
+		let v1 = positions[i] - positions[i-1];
+		let c1 = v1.dot(v1);
+
+		let (reflected_reference, reflected_tangent) = if c1 > 1.0e-12 {
+			(reference - v1 * (2.0 / c1 * v1.dot(reference)), tangents[i-1] - v1 * (2.0 / c1 * v1.dot(tangents[i-1])))
+		} else {
+			(reference, tangents[i-1])
+		};
+
+		// Then reflect again to align the tangent
+		let v2 = tangents[i] - reflected_tangent;
+		let c2 = v2.dot(v2);
+
+		let next_reference = if c2 > 1.0e-12 {
+			reflected_reference - v2 * (2.0 / c2 * v2.dot(reflected_reference))
+		} else {
+			reflected_reference
+		};
+
+		// Correct any accumulated drift
+		reference = orthogonalize(next_reference, tangents[i]).unwrap_or(reference);
+		orientations.push(orientation_from_basis(tangents[i], reference));
+	}
+
+	orientations
+}
+
+// Removes the component of `v` along unit vector `axis` and normalizes the result, if it isn't degenerate or non-finite.
+fn orthogonalize(v: Vec3, axis: Vec3) -> Option<Vec3> {
+	let v = v - axis * v.dot(axis);
+	let length = v.length();
+	(length > 1.0e-4 && length.is_finite()).then(|| v / length)
+}
+
+fn orientation_from_basis(forward: Vec3, up: Vec3) -> Quat {
+	let right = forward.cross(up);
+	Quat::from_mat3x4(&Mat3x4::from_columns([right, up, -forward, Vec3::zero()]))
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn test_uniform_sampling() {
+		// A straight line with control points bunched at the start, so the curve parameter is not proportional to distance
+		let line = CubicBezier3::new(Vec3::zero(), Vec3::from_x(0.01), Vec3::from_x(0.02), Vec3::from_x(3.0));
+		let curve = ArcLengthCurve::new(line, 256);
+
+		assert_almost_eq!(curve.length(), 3.0);
+		assert!((line.eval(0.5).x - 1.5).abs() > 0.5);
+
+		let points = curve.sample_uniform(7);
+		assert_eq!(points.len(), 7);
+		for (i, point) in points.iter().enumerate() {
+			assert_vec_eq!(*point, Vec3::from_x(i as f32 * 0.5));
+		}
+
+		let t = curve.t_at_distance(1.0);
+		assert_vec_eq!(line.eval(t), Vec3::from_x(1.0));
+		assert_almost_eq!(curve.distance_at_t(t), 1.0);
+
+		assert_almost_eq!(curve.t_at_distance(-1.0), 0.0);
+		assert_almost_eq!(curve.t_at_distance(10.0), 1.0);
+	}
+
+	#[test]
+	fn test_length() {
+		// Quarter circle approximation
+		let k = 0.5522847;
+		let arc = CubicBezier2::new(Vec2::from_x(1.0), Vec2::new(1.0, k), Vec2::new(k, 1.0), Vec2::from_y(1.0));
+		let curve = ArcLengthCurve::new(arc, 512);
+		assert_almost_eq!(curve.length(), PI / 2.0);
+
+		// Spline domains span every segment
+		let spline = CatmullRomSpline::new(vec![Vec2::zero(), Vec2::from_x(1.0), Vec2::new(1.0, 1.0)], CatmullRomKind::Centripetal);
+		let curve = ArcLengthCurve::new(spline, 256);
+		assert_eq!(curve.domain(), (0.0, curve.length()));
+		assert_vec_eq!(curve.position(curve.length()), Vec3::new(1.0, 1.0, 0.0));
+	}
+
+	#[test]
+	fn test_frames() {
+		// A helix
+		let points: Vec<Vec3> = (0..16)
+			.map(|i| {
+				let angle = i as f32 * PI / 4.0;
+				Vec3::new(angle.cos(), i as f32 * 0.2, angle.sin())
+			})
+			.collect();
+
+		let helix = CatmullRomSpline::new(points, CatmullRomKind::Centripetal);
+		let curve = ArcLengthCurve::new(helix, 1024);
+		let frames = curve.frames(64, Vec3::from_y(1.0));
+
+		assert_eq!(frames.len(), 64);
+		assert_vec_eq!(frames[0].position, Vec3::from_x(1.0));
+		assert_almost_eq!(frames[63].distance, curve.length());
+
+		for frame in frames.iter() {
+			assert_almost_eq!(frame.orientation.magnitude(), 1.0);
+			assert_vec_eq!(frame.orientation.forward(), curve.tangent_at_distance(frame.distance));
+		}
+
+		// Frames along a planar curve keep their up vector normal to the plane
+		let arc = CubicBezier2::new(Vec2::zero(), Vec2::from_x(2.0), Vec2::new(2.0, 2.0), Vec2::new(0.0, 2.0));
+		let frames = ArcLengthCurve::new(arc, 256).frames(20, Vec3::from_z(1.0));
+
+		for frame in frames.iter() {
+			assert_vec_eq!(frame.orientation.up(), Vec3::from_z(1.0));
+		}
+
+		// Starting with a zero tangent
+		let stalled = QuadBezier3::new(Vec3::zero(), Vec3::zero(), Vec3::from_x(1.0));
+		let frames = ArcLengthCurve::new(stalled, 64).frames(8, Vec3::from_y(1.0));
+		for frame in frames.iter() {
+			assert_vec_eq!(frame.orientation.forward(), Vec3::from_x(1.0));
+			assert_vec_eq!(frame.orientation.up(), Vec3::from_y(1.0));
+		}
+
+		let points = [Vec3::zero(), Vec3::zero()];
+		let frames = rotation_minimizing_frames(&points, &[Vec3::zero(), Vec3::splat(f32::NAN)], Vec3::from_y(1.0));
+		assert!(frames.iter().all(|frame| frame.magnitude().is_finite()));
+	}
+}
@@ -1,3 +1,10 @@
+use std::ops::{Add, Sub, Mul};
+use std::f32::consts::{PI, TAU};
+
+pub mod easing;
+pub use easing::*;
+
+
 pub trait Lerp<Bound> {
 	fn lerp(self, start: Bound, end: Bound) -> Bound;
 }
@@ -9,6 +16,96 @@ impl Lerp<f32> for f32 {
 	}
 }
 
+impl<T, const N: usize> Lerp<[T; N]> for f32
+	where f32: Lerp<T>
+{
+	fn lerp(self, start: [T; N], end: [T; N]) -> [T; N] {
+		let mut end = end.into_iter();
+		start.map(|start| self.lerp(start, end.next().unwrap()))
+	}
+}
+
+macro_rules! impl_lerp_for_tuple {
+	($($ty:ident $idx:tt),+) => (
+		impl<$($ty),+> Lerp<($($ty,)+)> for f32
+			where $(f32: Lerp<$ty>),+
+		{
+			fn lerp(self, start: ($($ty,)+), end: ($($ty,)+)) -> ($($ty,)+) {
+				($(self.lerp(start.$idx, end.$idx),)+)
+			}
+		}
+	);
+}
+
+impl_lerp_for_tuple!(A 0, B 1);
+impl_lerp_for_tuple!(A 0, B 1, C 2);
+impl_lerp_for_tuple!(A 0, B 1, C 2, D 3);
+
+
+
+/// Returns the factor `t` such that `t.lerp(start, end) == value`.
+/// Returns 0 if the range is empty.
+pub fn inverse_lerp(start: f32, end: f32, value: f32) -> f32 {
+	let range = end - start;
+	if range.abs() > f32::EPSILON {
+		(value - start) / range
+	} else {
+		0.0
+	}
+}
+
+/// Maps `value` from the range `from` to the range `to`, extrapolating if `value` lies outside of `from`.
+pub fn remap<T>(value: f32, from: (f32, f32), to: (T, T)) -> T
+	where f32: Lerp<T>
+{
+	inverse_lerp(from.0, from.1, value).lerp(to.0, to.1)
+}
+
+/// Maps `value` from the range `from` to the range `to`, clamping to the bounds of `to`.
+pub fn remap_clamped<T>(value: f32, from: (f32, f32), to: (T, T)) -> T
+	where f32: Lerp<T>
+{
+	inverse_lerp(from.0, from.1, value)
+		.clamp(0.0, 1.0)
+		.lerp(to.0, to.1)
+}
+
+/// Hermite interpolation between 0 and 1 as `value` moves from `start` to `end`, as in GLSL.
+pub fn smoothstep(start: f32, end: f32, value: f32) -> f32 {
+	let t = inverse_lerp(start, end, value).clamp(0.0, 1.0);
+	t * t * (3.0 - 2.0 * t)
+}
+
+/// Like `smoothstep`, but with zero first and second derivatives at the edges.
+pub fn smootherstep(start: f32, end: f32, value: f32) -> f32 {
+	let t = inverse_lerp(start, end, value).clamp(0.0, 1.0);
+	t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+/// Frame-rate independent exponential approach of `current` towards `target`.
+/// After one second only `e^-rate` of the initial distance remains, regardless of how `dt` is divided.
+pub fn lerp_decay<T>(current: T, target: T, rate: f32, dt: f32) -> T
+	where f32: Lerp<T>
+{
+	(1.0 - (-rate * dt).exp()).lerp(current, target)
+}
+
+/// Critically damped approach of `current` towards `target`, reaching it in roughly `smooth_time` seconds.
+/// `velocity` must be persisted between calls.
+// Game Programming Gems 4, Chapter 1.10
+pub fn smooth_damp<T>(current: T, target: T, velocity: &mut T, smooth_time: f32, dt: f32) -> T
+	where T: Copy + Add<Output=T> + Sub<Output=T> + Mul<f32, Output=T>
+{
+	let omega = 2.0 / smooth_time.max(0.0001);
+	let x = omega * dt;
+	let decay = 1.0 / (1.0 + x + 0.48*x*x + 0.235*x*x*x);
+
+	let change = current - target;
+	let temp = (*velocity + change * omega) * dt;
+	*velocity = (*velocity - temp * omega) * decay;
+	target + (change + temp) * decay
+}
+
 
 
 // http://robertpenner.com/easing/
@@ -21,6 +118,26 @@ pub trait EaseFloatExt {
 	fn ease_quad_out(self) -> Self;
 	fn ease_quad_inout(self) -> Self;
 
+	fn ease_cubic_in(self) -> Self;
+	fn ease_cubic_out(self) -> Self;
+	fn ease_cubic_inout(self) -> Self;
+
+	fn ease_quart_in(self) -> Self;
+	fn ease_quart_out(self) -> Self;
+	fn ease_quart_inout(self) -> Self;
+
+	fn ease_quint_in(self) -> Self;
+	fn ease_quint_out(self) -> Self;
+	fn ease_quint_inout(self) -> Self;
+
+	fn ease_sine_in(self) -> Self;
+	fn ease_sine_out(self) -> Self;
+	fn ease_sine_inout(self) -> Self;
+
+	fn ease_circ_in(self) -> Self;
+	fn ease_circ_out(self) -> Self;
+	fn ease_circ_inout(self) -> Self;
+
 	fn ease_exp_in(self) -> Self;
 	fn ease_exp_out(self) -> Self;
 	fn ease_exp_inout(self) -> Self;
@@ -32,6 +149,17 @@ pub trait EaseFloatExt {
 	fn ease_bounce_in(self) -> Self;
 	fn ease_bounce_out(self) -> Self;
 	fn ease_bounce_inout(self) -> Self;
+
+	fn ease_elastic_in(self) -> Self;
+	fn ease_elastic_out(self) -> Self;
+	fn ease_elastic_inout(self) -> Self;
+
+	/// CSS-style `cubic-bezier(x1, y1, x2, y2)` timing function.
+	/// The curve starts at (0, 0) and ends at (1, 1), with `x1` and `x2` clamped to [0, 1].
+	fn ease_cubic_bezier(self, x1: f32, y1: f32, x2: f32, y2: f32) -> Self;
+
+	/// CSS-style `steps(n, jump-end)` timing function.
+	fn ease_steps(self, steps: u32) -> Self;
 }
 
 
@@ -56,6 +184,97 @@ impl EaseFloatExt for f32 {
 		}
 	}
 
+	fn ease_cubic_in(self) -> f32 {
+		self.clamp(0.0, 1.0).powi(3)
+	}
+
+	fn ease_cubic_out(self) -> f32 {
+		let t = self.clamp(0.0, 1.0) - 1.0;
+		t.powi(3) + 1.0
+	}
+
+	fn ease_cubic_inout(self) -> f32 {
+		let t = self.clamp(0.0, 1.0) * 2.0;
+		if t < 1.0 {
+			t.ease_cubic_in() / 2.0
+		} else {
+			(t - 1.0).ease_cubic_out() / 2.0 + 0.5
+		}
+	}
+
+	fn ease_quart_in(self) -> f32 {
+		self.clamp(0.0, 1.0).powi(4)
+	}
+
+	fn ease_quart_out(self) -> f32 {
+		let t = self.clamp(0.0, 1.0) - 1.0;
+		1.0 - t.powi(4)
+	}
+
+	fn ease_quart_inout(self) -> f32 {
+		let t = self.clamp(0.0, 1.0) * 2.0;
+		if t < 1.0 {
+			t.ease_quart_in() / 2.0
+		} else {
+			(t - 1.0).ease_quart_out() / 2.0 + 0.5
+		}
+	}
+
+	fn ease_quint_in(self) -> f32 {
+		self.clamp(0.0, 1.0).powi(5)
+	}
+
+	fn ease_quint_out(self) -> f32 {
+		let t = self.clamp(0.0, 1.0) - 1.0;
+		t.powi(5) + 1.0
+	}
+
+	fn ease_quint_inout(self) -> f32 {
+		let t = self.clamp(0.0, 1.0) * 2.0;
+		if t < 1.0 {
+			t.ease_quint_in() / 2.0
+		} else {
+			(t - 1.0).ease_quint_out() / 2.0 + 0.5
+		}
+	}
+
+
+	fn ease_sine_in(self) -> f32 {
+		let t = self.clamp(0.0, 1.0);
+		1.0 - (t * PI / 2.0).cos()
+	}
+
+	fn ease_sine_out(self) -> f32 {
+		let t = self.clamp(0.0, 1.0);
+		(t * PI / 2.0).sin()
+	}
+
+	fn ease_sine_inout(self) -> f32 {
+		let t = self.clamp(0.0, 1.0);
+		(1.0 - (t * PI).cos()) / 2.0
+	}
+
+
+	fn ease_circ_in(self) -> f32 {
+		let t = self.clamp(0.0, 1.0);
+		1.0 - (1.0 - t*t).sqrt()
+	}
+
+	fn ease_circ_out(self) -> f32 {
+		let t = self.clamp(0.0, 1.0) - 1.0;
+		(1.0 - t*t).sqrt()
+	}
+
+	fn ease_circ_inout(self) -> f32 {
+		let t = self.clamp(0.0, 1.0) * 2.0;
+		if t < 1.0 {
+			t.ease_circ_in() / 2.0
+		} else {
+			(t - 1.0).ease_circ_out() / 2.0 + 0.5
+		}
+	}
+
+
 	fn ease_exp_in(self) -> f32 {
 		let t = self.clamp(0.0, 1.0);
 		2.0f32.powf(10.0 * (t - 1.0))
@@ -131,4 +350,188 @@ impl EaseFloatExt for f32 {
 			(1.0 + (t - 1.0).ease_bounce_out()) / 2.0
 		}
 	}
+
+
+	fn ease_elastic_in(self) -> f32 {
+		let t = self.clamp(0.0, 1.0);
+		if t <= 0.0 || t >= 1.0 {
+			return t;
+		}
+
+		let period = 0.3;
+		let s = period / 4.0;
+		let t = t - 1.0;
+		-(2.0f32.powf(10.0 * t) * ((t - s) * TAU / period).sin())
+	}
+
+	fn ease_elastic_out(self) -> f32 {
+		1.0 - (1.0 - self).ease_elastic_in()
+	}
+
+	fn ease_elastic_inout(self) -> f32 {
+		let t = self.clamp(0.0, 1.0) * 2.0;
+
+		if t < 1.0 {
+			t.ease_elastic_in() / 2.0
+		} else {
+			(t - 1.0).ease_elastic_out() / 2.0 + 0.5
+		}
+	}
+
+
+	fn ease_cubic_bezier(self, x1: f32, y1: f32, x2: f32, y2: f32) -> f32 {
+		let t = self.clamp(0.0, 1.0);
+		let x1 = x1.clamp(0.0, 1.0);
+		let x2 = x2.clamp(0.0, 1.0);
+
+		let s = solve_cubic_bezier_param(t, x1, x2);
+		cubic_bezier_component(s, y1, y2)
+	}
+
+	fn ease_steps(self, steps: u32) -> f32 {
+		let t = self.clamp(0.0, 1.0);
+		if steps == 0 || t >= 1.0 {
+			return t;
+		}
+
+		let steps = steps as f32;
+		(t * steps).floor() / steps
+	}
+}
+
+
+// Evaluates one axis of a cubic bezier with endpoints fixed at 0 and 1.
+fn cubic_bezier_component(s: f32, p1: f32, p2: f32) -> f32 {
+	let inv_s = 1.0 - s;
+	3.0 * inv_s * inv_s * s * p1
+		+ 3.0 * inv_s * s * s * p2
+		+ s * s * s
+}
+
+fn cubic_bezier_component_derivative(s: f32, p1: f32, p2: f32) -> f32 {
+	let inv_s = 1.0 - s;
+	3.0 * inv_s * inv_s * p1
+		+ 6.0 * inv_s * s * (p2 - p1)
+		+ 3.0 * s * s * (1.0 - p2)
+}
+
+// Finds the curve parameter whose x component is `x`.
+// Newton's method converges quickly for most curves, but can stall on flat sections - so fall back to bisection.
+fn solve_cubic_bezier_param(x: f32, x1: f32, x2: f32) -> f32 {
+	const EPSILON: f32 = 1.0e-6;
+
+	let mut s = x;
+	for _ in 0..8 {
+		let error = cubic_bezier_component(s, x1, x2) - x;
+		if error.abs() < EPSILON {
+			return s;
+		}
+
+		let slope = cubic_bezier_component_derivative(s, x1, x2);
+		if slope.abs() < EPSILON {
+			break
+		}
+
+		s -= error / slope;
+	}
+
+	let (mut lower, mut upper) = (0.0f32, 1.0f32);
+	let mut s = x;
+	for _ in 0..32 {
+		let error = cubic_bezier_component(s, x1, x2) - x;
+		if error.abs() < EPSILON {
+			break
+		}
+
+		if error > 0.0 {
+			upper = s;
+		} else {
+			lower = s;
+		}
+
+		s = (lower + upper) / 2.0;
+	}
+
+	s
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn test_remap() {
+		assert_almost_eq!(inverse_lerp(2.0, 4.0, 3.0), 0.5);
+		assert_almost_eq!(inverse_lerp(2.0, 2.0, 3.0), 0.0);
+
+		assert_almost_eq!(remap(5.0, (0.0, 10.0), (100.0, 200.0)), 150.0);
+		assert_almost_eq!(remap(20.0, (0.0, 10.0), (100.0, 200.0)), 300.0);
+		assert_almost_eq!(remap_clamped(20.0, (0.0, 10.0), (100.0, 200.0)), 200.0);
+		assert_vec_eq!(remap(0.5, (0.0, 2.0), (Vec2::zero(), Vec2::new(4.0, 8.0))), Vec2::new(1.0, 2.0));
+	}
+
+	#[test]
+	fn test_smoothstep() {
+		assert_almost_eq!(smoothstep(1.0, 3.0, 0.0), 0.0);
+		assert_almost_eq!(smoothstep(1.0, 3.0, 2.0), 0.5);
+		assert_almost_eq!(smoothstep(1.0, 3.0, 4.0), 1.0);
+		assert_almost_eq!(smootherstep(1.0, 3.0, 2.0), 0.5);
+		assert_almost_eq!(smootherstep(0.0, 1.0, 0.25), 0.103515625);
+	}
+
+	#[test]
+	fn test_lerp_decay_frame_rate_independent() {
+		let target = Vec3::new(10.0, -5.0, 2.0);
+
+		let mut coarse = Vec3::zero();
+		for _ in 0..10 {
+			coarse = lerp_decay(coarse, target, 3.0, 0.1);
+		}
+
+		let mut fine = Vec3::zero();
+		for _ in 0..1000 {
+			fine = lerp_decay(fine, target, 3.0, 0.001);
+		}
+
+		assert_vec_eq!(coarse, fine);
+		assert_vec_eq!(coarse, target * (1.0 - (-3.0f32).exp()));
+	}
+
+	#[test]
+	fn test_smooth_damp() {
+		let mut value = 0.0;
+		let mut velocity = 0.0;
+		let mut max_value = 0.0f32;
+
+		for _ in 0..600 {
+			value = smooth_damp(value, 1.0, &mut velocity, 0.5, 1.0/60.0);
+			max_value = max_value.max(value);
+		}
+
+		assert_almost_eq!(value, 1.0);
+		assert_almost_eq!(velocity, 0.0);
+		assert!(max_value <= 1.0, "smooth_damp overshot");
+
+		let mut color = Color::black();
+		let mut color_velocity = Color::transparent();
+		for _ in 0..100 {
+			color = smooth_damp(color, Color::white(), &mut color_velocity, 0.2, 0.1);
+		}
+
+		assert_vec_eq!(color.to_vec4(), Color::white().to_vec4());
+	}
+
+	#[test]
+	fn test_compound_lerp() {
+		let [a, b] = 0.5f32.lerp([Vec2::zero(), Vec2::one()], [Vec2::splat(2.0), Vec2::splat(3.0)]);
+		assert_vec_eq!(a, Vec2::one());
+		assert_vec_eq!(b, Vec2::splat(2.0));
+
+		let (a, b, c) = 0.25f32.lerp((0.0, Vec3::zero(), Vec2i::zero()), (4.0, Vec3::splat(4.0), Vec2i::new(4, -6)));
+		assert_almost_eq!(a, 1.0);
+		assert_vec_eq!(b, Vec3::one());
+		assert_eq!(c, Vec2i::new(1, -2));
+	}
 }
@@ -0,0 +1,180 @@
+use crate::math::lerp::EaseFloatExt;
+
+
+/// A data-driven choice of easing curve, dispatching to the matching `EaseFloatExt` function.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Easing {
+	#[default]
+	Linear,
+
+	QuadIn,
+	QuadOut,
+	QuadInOut,
+
+	CubicIn,
+	CubicOut,
+	CubicInOut,
+
+	QuartIn,
+	QuartOut,
+	QuartInOut,
+
+	QuintIn,
+	QuintOut,
+	QuintInOut,
+
+	SineIn,
+	SineOut,
+	SineInOut,
+
+	CircIn,
+	CircOut,
+	CircInOut,
+
+	ExpIn,
+	ExpOut,
+	ExpInOut,
+
+	BackIn,
+	BackOut,
+	BackInOut,
+
+	BounceIn,
+	BounceOut,
+	BounceInOut,
+
+	ElasticIn,
+	ElasticOut,
+	ElasticInOut,
+
+	/// See `EaseFloatExt::ease_cubic_bezier`.
+	CubicBezier(f32, f32, f32, f32),
+
+	/// See `EaseFloatExt::ease_steps`.
+	Steps(u32),
+}
+
+impl Easing {
+	/// Common CSS timing functions.
+	pub const EASE: Easing = Easing::CubicBezier(0.25, 0.1, 0.25, 1.0);
+	pub const EASE_IN: Easing = Easing::CubicBezier(0.42, 0.0, 1.0, 1.0);
+	pub const EASE_OUT: Easing = Easing::CubicBezier(0.0, 0.0, 0.58, 1.0);
+	pub const EASE_IN_OUT: Easing = Easing::CubicBezier(0.42, 0.0, 0.58, 1.0);
+
+	pub fn apply(&self, t: f32) -> f32 {
+		match *self {
+			Easing::Linear => t.ease_linear(),
+
+			Easing::QuadIn => t.ease_quad_in(),
+			Easing::QuadOut => t.ease_quad_out(),
+			Easing::QuadInOut => t.ease_quad_inout(),
+
+			Easing::CubicIn => t.ease_cubic_in(),
+			Easing::CubicOut => t.ease_cubic_out(),
+			Easing::CubicInOut => t.ease_cubic_inout(),
+
+			Easing::QuartIn => t.ease_quart_in(),
+			Easing::QuartOut => t.ease_quart_out(),
+			Easing::QuartInOut => t.ease_quart_inout(),
+
+			Easing::QuintIn => t.ease_quint_in(),
+			Easing::QuintOut => t.ease_quint_out(),
+			Easing::QuintInOut => t.ease_quint_inout(),
+
+			Easing::SineIn => t.ease_sine_in(),
+			Easing::SineOut => t.ease_sine_out(),
+			Easing::SineInOut => t.ease_sine_inout(),
+
+			Easing::CircIn => t.ease_circ_in(),
+			Easing::CircOut => t.ease_circ_out(),
+			Easing::CircInOut => t.ease_circ_inout(),
+
+			Easing::ExpIn => t.ease_exp_in(),
+			Easing::ExpOut => t.ease_exp_out(),
+			Easing::ExpInOut => t.ease_exp_inout(),
+
+			Easing::BackIn => t.ease_back_in(),
+			Easing::BackOut => t.ease_back_out(),
+			Easing::BackInOut => t.ease_back_inout(),
+
+			Easing::BounceIn => t.ease_bounce_in(),
+			Easing::BounceOut => t.ease_bounce_out(),
+			Easing::BounceInOut => t.ease_bounce_inout(),
+
+			Easing::ElasticIn => t.ease_elastic_in(),
+			Easing::ElasticOut => t.ease_elastic_out(),
+			Easing::ElasticInOut => t.ease_elastic_inout(),
+
+			Easing::CubicBezier(x1, y1, x2, y2) => t.ease_cubic_bezier(x1, y1, x2, y2),
+			Easing::Steps(steps) => t.ease_steps(steps),
+		}
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	const ALL_EASINGS: &[Easing] = &[
+		Easing::Linear,
+		Easing::QuadIn, Easing::QuadOut, Easing::QuadInOut,
+		Easing::CubicIn, Easing::CubicOut, Easing::CubicInOut,
+		Easing::QuartIn, Easing::QuartOut, Easing::QuartInOut,
+		Easing::QuintIn, Easing::QuintOut, Easing::QuintInOut,
+		Easing::SineIn, Easing::SineOut, Easing::SineInOut,
+		Easing::CircIn, Easing::CircOut, Easing::CircInOut,
+		Easing::ExpIn, Easing::ExpOut, Easing::ExpInOut,
+		Easing::BackIn, Easing::BackOut, Easing::BackInOut,
+		Easing::BounceIn, Easing::BounceOut, Easing::BounceInOut,
+		Easing::ElasticIn, Easing::ElasticOut, Easing::ElasticInOut,
+		Easing::EASE, Easing::EASE_IN, Easing::EASE_OUT, Easing::EASE_IN_OUT,
+		Easing::Steps(4),
+	];
+
+	#[test]
+	fn test_endpoints() {
+		for easing in ALL_EASINGS {
+			// Exponential easings only approach their endpoints
+			assert!(easing.apply(0.0).abs() < 0.01, "{:?} doesn't start at 0", easing);
+			assert!((easing.apply(1.0) - 1.0).abs() < 0.01, "{:?} doesn't end at 1", easing);
+		}
+	}
+
+	#[test]
+	fn test_inout_midpoint() {
+		for easing in [Easing::QuadInOut, Easing::CubicInOut, Easing::QuartInOut, Easing::QuintInOut,
+			Easing::SineInOut, Easing::CircInOut, Easing::ElasticInOut, Easing::EASE_IN_OUT]
+		{
+			assert!((easing.apply(0.5) - 0.5).abs() < 0.001, "{:?} isn't symmetric", easing);
+		}
+	}
+
+	#[test]
+	fn test_cubic_bezier() {
+		// Control points along the diagonal give a linear curve
+		for i in 0..=10 {
+			let t = i as f32 / 10.0;
+			assert_almost_eq!(t.ease_cubic_bezier(1.0/3.0, 1.0/3.0, 2.0/3.0, 2.0/3.0), t);
+			assert_almost_eq!(t.ease_cubic_bezier(0.0, 0.0, 1.0, 1.0), t);
+		}
+
+		// Known value for css 'ease-in-out'
+		assert_almost_eq!(0.25f32.ease_cubic_bezier(0.42, 0.0, 0.58, 1.0), 0.1291);
+
+		// Degenerate curves with flat x sections still converge
+		assert_almost_eq!(0.5f32.ease_cubic_bezier(1.0, 0.0, 0.0, 1.0), 0.5);
+	}
+
+	#[test]
+	fn test_steps() {
+		assert_almost_eq!(0.0f32.ease_steps(4), 0.0);
+		assert_almost_eq!(0.24f32.ease_steps(4), 0.0);
+		assert_almost_eq!(0.25f32.ease_steps(4), 0.25);
+		assert_almost_eq!(0.99f32.ease_steps(4), 0.75);
+		assert_almost_eq!(1.0f32.ease_steps(4), 1.0);
+		assert_almost_eq!(0.3f32.ease_steps(0), 0.3);
+	}
+}
@@ -0,0 +1,128 @@
+use crate::vector::{Vec2, Vec3};
+
+/// An infinite 2D line through `origin` along the unit vector `direction`.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Line2 {
+	pub origin: Vec2,
+	pub direction: Vec2,
+}
+
+/// An infinite 3D line through `origin` along the unit vector `direction`.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Line3 {
+	pub origin: Vec3,
+	pub direction: Vec3,
+}
+
+macro_rules! impl_line {
+	($ty:ident, $vec:ident) => {
+		/// Constructors
+		impl $ty {
+			/// Normalizes `direction`, which must not be zero.
+			pub fn new(origin: $vec, direction: $vec) -> $ty {
+				$ty { origin, direction: direction.normalize() }
+			}
+
+			/// The line through `a` towards `b`, which must be different points.
+			pub fn from_points(a: $vec, b: $vec) -> $ty {
+				$ty::new(a, b - a)
+			}
+		}
+
+		/// Queries
+		impl $ty {
+			/// The point `t` units along the line from `origin`.
+			pub fn point_at(&self, t: f32) -> $vec {
+				self.origin + self.direction * t
+			}
+
+			/// How far along the line the point closest to `point` is.
+			pub fn closest_t(&self, point: $vec) -> f32 {
+				(point - self.origin).dot(self.direction)
+			}
+
+			pub fn closest_point(&self, point: $vec) -> $vec {
+				self.point_at(self.closest_t(point))
+			}
+
+			pub fn distance_to(&self, point: $vec) -> f32 {
+				(point - self.closest_point(point)).length()
+			}
+		}
+	}
+}
+
+impl_line!(Line2, Vec2);
+impl_line!(Line3, Vec3);
+
+impl Line2 {
+	/// Where the two lines cross, or `None` if they are parallel.
+	pub fn intersect(&self, other: &Line2) -> Option<Vec2> {
+		let denominator = self.direction.wedge(other.direction);
+		if denominator.abs() <= f32::EPSILON {
+			return None;
+		}
+
+		Some(self.point_at((other.origin - self.origin).wedge(other.direction) / denominator))
+	}
+}
+
+impl Line3 {
+	/// The closest points on this line and on `other`, or `None` if they are parallel.
+	pub fn closest_points(&self, other: &Line3) -> Option<(Vec3, Vec3)> {
+		let b = self.direction.dot(other.direction);
+		let denominator = 1.0 - b * b;
+		if denominator <= f32::EPSILON {
+			return None;
+		}
+
+		let offset = self.origin - other.origin;
+		let (c, f) = (self.direction.dot(offset), other.direction.dot(offset));
+		let s = (b * f - c) / denominator;
+		let t = (f - b * c) / denominator;
+
+		Some((self.point_at(s), other.point_at(t)))
+	}
+
+	pub fn distance_to_line(&self, other: &Line3) -> f32 {
+		match self.closest_points(other) {
+			Some((a, b)) => (b - a).length(),
+			None => self.distance_to(other.origin),
+		}
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn test_line2() {
+		let line = Line2::from_points(Vec2::zero(), Vec2::new(2.0, 2.0));
+		assert_vec_eq!(line.closest_point(Vec2::from_x(2.0)), Vec2::one());
+		assert_almost_eq!(line.distance_to(Vec2::from_y(2.0)), SQRT_2);
+
+		let other = Line2::new(Vec2::from_x(3.0), Vec2::from_y(1.0));
+		assert_vec_eq!(line.intersect(&other).unwrap(), Vec2::splat(3.0));
+		assert!(line.intersect(&Line2::new(Vec2::from_x(1.0), Vec2::one())).is_none());
+	}
+
+	#[test]
+	fn test_line3() {
+		let line = Line3::new(Vec3::zero(), Vec3::from_x(1.0));
+		let other = Line3::new(Vec3::new(2.0, 1.0, 3.0), Vec3::new(0.0, 1.0, 1.0));
+
+		let (a, b) = line.closest_points(&other).unwrap();
+		assert_vec_eq!(a, Vec3::from_x(2.0));
+		assert_vec_eq!(b, Vec3::new(2.0, -1.0, 1.0));
+		assert_almost_eq!(line.distance_to_line(&other), SQRT_2);
+
+		let parallel = Line3::new(Vec3::new(5.0, 0.0, 2.0), Vec3::from_x(-3.0));
+		assert!(line.closest_points(&parallel).is_none());
+		assert_almost_eq!(line.distance_to_line(&parallel), 2.0);
+	}
+}
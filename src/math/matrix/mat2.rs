@@ -1,5 +1,6 @@
 use std::ops::Mul;
 use crate::vector::*;
+use crate::lerp::Lerp;
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
@@ -80,6 +81,12 @@ impl Default for Mat2 {
 	}
 }
 
+impl Lerp<Mat2> for f32 {
+	fn lerp(self, start: Mat2, end: Mat2) -> Mat2 {
+		Mat2 { rows: self.lerp(start.rows, end.rows) }
+	}
+}
+
 impl Mul<Mat2> for Mat2 {
 	type Output = Mat2;
 	fn mul(self, o: Mat2) -> Mat2 {
@@ -1,6 +1,8 @@
 use std::ops::Mul;
 use crate::matrix::{Mat3x4, Mat4};
 use crate::vector::*;
+use crate::lerp::Lerp;
+use std::f32::consts::{PI, TAU};
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
@@ -78,6 +80,34 @@ impl Mat2x3 {
 		])
 	}
 
+	/// Decomposes into scale, rotation and translation such that
+	/// `Mat2x3::scale_rotate_translate(s, ph, t)` reproduces this matrix.
+	/// Any shear is lost, and reflections are represented by a negative y scale.
+	pub fn decompose(&self) -> (Vec2, f32, Vec2) {
+		let [x, y, translation] = self.columns();
+
+		let mut scale = Vec2::new(x.length(), y.length());
+		if x.wedge(y) < 0.0 {
+			scale.y = -scale.y;
+		}
+
+		(scale, x.to_angle(), translation)
+	}
+
+	/// Interpolates translation, rotation and scale separately, taking the shortest path between rotations.
+	pub fn lerp_transform(&self, end: &Mat2x3, t: f32) -> Mat2x3 {
+		let (start_scale, start_angle, start_translation) = self.decompose();
+		let (end_scale, end_angle, end_translation) = end.decompose();
+
+		let angle_delta = (end_angle - start_angle + PI).rem_euclid(TAU) - PI;
+
+		Mat2x3::scale_rotate_translate(
+			t.lerp(start_scale, end_scale),
+			start_angle + angle_delta * t,
+			t.lerp(start_translation, end_translation),
+		)
+	}
+
 	pub fn column_x(&self) -> Vec2 {
 		let [a,b] = &self.rows;
 		Vec2::new(a.x, b.x)
@@ -118,6 +148,12 @@ impl Mat2x3 {
 }
 
 
+impl Lerp<Mat2x3> for f32 {
+	fn lerp(self, start: Mat2x3, end: Mat2x3) -> Mat2x3 {
+		Mat2x3 { rows: self.lerp(start.rows, end.rows) }
+	}
+}
+
 impl Mul<Mat2x3> for Mat2x3 {
 	type Output = Mat2x3;
 	fn mul(self, o: Mat2x3) -> Mat2x3 {
@@ -173,3 +209,38 @@ impl Mul<f32> for Mat2x3 {
 		])
 	}
 }
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn assert_mat_eq(a: Mat2x3, b: Mat2x3) {
+		for (a, b) in a.columns().into_iter().zip(b.columns()) {
+			assert_vec_eq!(a, b, "{a:?} != {b:?}");
+		}
+	}
+
+	#[test]
+	fn test_decompose() {
+		let m = Mat2x3::scale_rotate_translate(Vec2::new(2.0, -3.0), PI/3.0, Vec2::new(4.0, 5.0));
+		let (s, ph, t) = m.decompose();
+		assert_vec_eq!(s, Vec2::new(2.0, -3.0));
+		assert_almost_eq!(ph, PI/3.0);
+		assert_vec_eq!(t, Vec2::new(4.0, 5.0));
+	}
+
+	#[test]
+	fn test_lerp_transform() {
+		let start = Mat2x3::rotate_translate(PI * 0.9, Vec2::zero());
+		let end = Mat2x3::scale_rotate_translate(3.0, -PI * 0.9, Vec2::new(2.0, 4.0));
+
+		assert_mat_eq(start.lerp_transform(&end, 0.0), start);
+		assert_mat_eq(start.lerp_transform(&end, 1.0), end);
+
+		// Rotates through PI rather than through 0
+		let expected = Mat2x3::scale_rotate_translate(2.0, PI, Vec2::new(1.0, 2.0));
+		assert_mat_eq(start.lerp_transform(&end, 0.5), expected);
+	}
+}
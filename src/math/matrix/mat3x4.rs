@@ -1,6 +1,8 @@
 use std::ops::Mul;
 use crate::matrix::Mat4;
 use crate::vector::*;
+use crate::quaternion::Quat;
+use crate::lerp::Lerp;
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
@@ -78,6 +80,12 @@ impl Mat3x4 {
 		])
 	}
 
+	pub fn scale_rotate_translate(s: impl ToVec3Scalar, r: Quat, t: Vec3) -> Mat3x4 {
+		let s = s.to_vec3();
+		let [x, y, z, _] = r.to_mat3x4().columns();
+		Mat3x4::from_columns([x * s.x, y * s.y, z * s.z, t])
+	}
+
 	pub fn scale(s: impl ToVec3Scalar) -> Mat3x4 {
 		Mat3x4::scale_translate(s, Vec3::zero())
 	}
@@ -123,6 +131,46 @@ impl Mat3x4 {
 		[self.column_x(), self.column_y(), self.column_z(), self.column_w()]
 	}
 
+	/// Decomposes into scale, rotation and translation such that
+	/// `Mat3x4::scale_rotate_translate(s, r, t)` reproduces this matrix.
+	/// Any shear is lost, and reflections are represented by a negative x scale.
+	pub fn decompose(&self) -> (Vec3, Quat, Vec3) {
+		let [x, y, z, translation] = self.columns();
+
+		let mut scale = Vec3::new(x.length(), y.length(), z.length());
+		if self.determinant() < 0.0 {
+			scale.x = -scale.x;
+		}
+
+		// Columns with no scale have no direction, so they're rebuilt from the other two, or left unrotated if fewer remain
+		let largest = scale.x.abs().max(scale.y).max(scale.z);
+		let usable = [scale.x.abs(), scale.y, scale.z].map(|length| length > f32::EPSILON * largest);
+		let (x, y, z) = (x / scale.x, y / scale.y, z / scale.z);
+		let [x, y, z] = match usable {
+			[true, true, true] => [x, y, z],
+			[false, true, true] => [y.cross(z).normalize(), y, z],
+			[true, false, true] => [x, z.cross(x).normalize(), z],
+			[true, true, false] => [x, y, x.cross(y).normalize()],
+			_ => [Vec3::from_x(1.0), Vec3::from_y(1.0), Vec3::from_z(1.0)],
+		};
+
+		let rotation_matrix = Mat3x4::from_columns([x, y, z, Vec3::zero()]);
+		(scale, Quat::from_mat3x4(&rotation_matrix), translation)
+	}
+
+	/// Interpolates translation and scale linearly and rotation spherically, unlike `Lerp`
+	/// which interpolates each element and so distorts rotations.
+	pub fn lerp_transform(&self, end: &Mat3x4, t: f32) -> Mat3x4 {
+		let (start_scale, start_rotation, start_translation) = self.decompose();
+		let (end_scale, end_rotation, end_translation) = end.decompose();
+
+		Mat3x4::scale_rotate_translate(
+			t.lerp(start_scale, end_scale),
+			start_rotation.slerp(end_rotation, t),
+			t.lerp(start_translation, end_translation),
+		)
+	}
+
 	pub fn determinant(&self) -> f32 {
 		let [a,b,c] = self.rows;
 
@@ -189,6 +237,12 @@ impl Default for Mat3x4 {
 	}
 }
 
+impl Lerp<Mat3x4> for f32 {
+	fn lerp(self, start: Mat3x4, end: Mat3x4) -> Mat3x4 {
+		Mat3x4 { rows: self.lerp(start.rows, end.rows) }
+	}
+}
+
 impl Mul<Mat3x4> for Mat3x4 {
 	type Output = Mat3x4;
 	fn mul(self, o: Mat3x4) -> Mat3x4 {
@@ -304,4 +358,55 @@ mod tests {
 		assert_vec_eq!(r180.column_y(), Vec3::from_y(-1.0));
 		assert_vec_eq!(r180.column_z(), Vec3::from_z(1.0));
 	}
+
+	fn assert_mat_eq(a: Mat3x4, b: Mat3x4) {
+		for (a, b) in a.columns().into_iter().zip(b.columns()) {
+			assert_vec_eq!(a, b, "{a:?} != {b:?}");
+		}
+	}
+
+	#[test]
+	fn test_decompose() {
+		let scale = Vec3::new(2.0, 0.5, 3.0);
+		let rotation = Quat::from_axis_angle(Vec3::new(1.0, 1.0, 0.0).normalize(), PI/3.0);
+		let translation = Vec3::new(1.0, -2.0, 5.0);
+
+		let m = Mat3x4::scale_rotate_translate(scale, rotation, translation);
+		let (s, r, t) = m.decompose();
+		assert_vec_eq!(s, scale);
+		assert_almost_eq!(r.dot(rotation).abs(), 1.0);
+		assert_vec_eq!(t, translation);
+
+		let mirrored = Mat3x4::scale(Vec3::new(1.0, 1.0, -1.0)) * m;
+		let (s, r, t) = mirrored.decompose();
+		assert_mat_eq(Mat3x4::scale_rotate_translate(s, r, t), mirrored);
+	}
+
+	#[test]
+	fn test_lerp_transform() {
+		let start = Mat3x4::translate(Vec3::zero());
+		let end = Mat3x4::rotate_y_translate(PI/2.0, Vec3::new(2.0, 0.0, 0.0)) * Mat3x4::scale(3.0);
+
+		assert_mat_eq(start.lerp_transform(&end, 0.0), start);
+		assert_mat_eq(start.lerp_transform(&end, 1.0), end);
+
+		let expected = Mat3x4::rotate_y_translate(PI/4.0, Vec3::new(1.0, 0.0, 0.0)) * Mat3x4::scale(2.0);
+		assert_mat_eq(start.lerp_transform(&end, 0.5), expected);
+
+		// Growing from nothing
+		let grown = Mat3x4::rotate_y_translate(PI/2.0, Vec3::from_x(2.0)) * Mat3x4::scale(4.0);
+		assert_mat_eq(Mat3x4::scale(0.0).lerp_transform(&Mat3x4::identity(), 0.5), Mat3x4::scale(0.5));
+		assert_mat_eq(Mat3x4::scale(0.0).lerp_transform(&grown, 1.0), grown);
+
+		let flat = Mat3x4::rotate_y(PI/2.0) * Mat3x4::scale(Vec3::new(0.0, 4.0, 4.0));
+		let (s, r, _) = flat.decompose();
+		assert_vec_eq!(s, Vec3::new(0.0, 4.0, 4.0));
+		assert_mat_eq(Mat3x4::scale_rotate_translate(s, r, Vec3::zero()), flat);
+		let expected = Mat3x4::rotate_y_translate(PI/2.0, Vec3::from_x(1.0)) * Mat3x4::scale(Vec3::new(2.0, 4.0, 4.0));
+		assert_mat_eq(flat.lerp_transform(&grown, 0.5), expected);
+
+		// Component-wise lerp shrinks rotations, transform lerp does not
+		let half_rotation = 0.5f32.lerp(Mat3x4::identity(), Mat3x4::rotate_y(PI/2.0));
+		assert!(half_rotation.column_x().length() < 0.9);
+	}
 }
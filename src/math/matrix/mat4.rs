@@ -1,6 +1,7 @@
 use std::ops::Mul;
 use crate::vector::*;
 use crate::matrix::Mat3x4;
+use crate::lerp::Lerp;
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
@@ -45,6 +46,14 @@ impl Mat4 {
 		Mat3x4::from_rows([a, b, c])
 	}
 
+	/// Interpolates translation, rotation and scale separately. See `Mat3x4::lerp_transform`.
+	/// Assumes both matrices are affine - the bottom row is ignored.
+	pub fn lerp_transform(&self, end: &Mat4, t: f32) -> Mat4 {
+		self.to_mat3x4()
+			.lerp_transform(&end.to_mat3x4(), t)
+			.to_mat4()
+	}
+
 	pub fn transpose(&self) -> Mat4 {
 		let [a,b,c,d] = self.rows;
 
@@ -309,6 +318,12 @@ impl Default for Mat4 {
 	}
 }
 
+impl Lerp<Mat4> for f32 {
+	fn lerp(self, start: Mat4, end: Mat4) -> Mat4 {
+		Mat4 { rows: self.lerp(start.rows, end.rows) }
+	}
+}
+
 impl Mul<Mat4> for Mat4 {
 	type Output = Mat4;
 	fn mul(self, o: Mat4) -> Mat4 {
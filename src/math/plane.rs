@@ -1,4 +1,6 @@
 use crate::vector::{Vec2, Vec3, Vec4};
+use crate::line::Line3;
+use crate::lerp::Lerp;
 
 /// Represents a 3D plane of the form `normal . xyz - distance = 0`
 #[repr(C)]
@@ -44,6 +46,32 @@ impl Plane3 {
 	pub fn mirror(&self, p: Vec3) -> Vec3 {
 		p - self.normal * self.distance_to(p) * 2.0
 	}
+
+	/// The line where the two planes meet, or `None` if they are parallel.
+	pub fn intersect_plane(&self, other: &Plane3) -> Option<Line3> {
+		let direction = self.normal.cross(other.normal);
+		if direction.square_length() <= f32::EPSILON {
+			return None;
+		}
+
+		// Head across this plane from its point nearest the origin until reaching the other plane
+		let start = self.project(Vec3::zero());
+		let across = direction.cross(self.normal);
+		let origin = start - across * (other.distance_to(start) / other.normal.dot(across));
+
+		Some(Line3::new(origin, direction))
+	}
+
+	/// The point where three planes meet, or `None` if they don't meet at a single point.
+	pub fn intersect_planes(&self, b: &Plane3, c: &Plane3) -> Option<Vec3> {
+		let (bc, ca, ab) = (b.normal.cross(c.normal), c.normal.cross(self.normal), self.normal.cross(b.normal));
+		let denominator = self.normal.dot(bc);
+		if denominator.abs() <= f32::EPSILON {
+			return None;
+		}
+
+		Some((bc * self.distance + ca * b.distance + ab * c.distance) / denominator)
+	}
 }
 
 
@@ -99,3 +127,91 @@ impl Plane2 {
 		p - self.normal * self.distance_to(p) * 2.0
 	}
 }
+
+
+// Planes are interpolated as homogeneous vectors and then renormalized, so that the result
+// passes through the intersection of `start` and `end` if they intersect.
+// Opposing planes have no such blend halfway, so the nearer of the two is used instead.
+impl Lerp<Plane3> for f32 {
+	fn lerp(self, start: Plane3, end: Plane3) -> Plane3 {
+		let normal = self.lerp(start.normal, end.normal);
+		let distance = self.lerp(start.distance, end.distance);
+		let length = normal.length();
+		if length <= f32::EPSILON {
+			return if self < 0.5 { start } else { end };
+		}
+
+		let inv_length = 1.0 / length;
+		Plane3 {
+			normal: normal * inv_length,
+			distance: distance * inv_length,
+		}
+	}
+}
+
+impl Lerp<Plane2> for f32 {
+	fn lerp(self, start: Plane2, end: Plane2) -> Plane2 {
+		let normal = self.lerp(start.normal, end.normal);
+		let distance = self.lerp(start.distance, end.distance);
+		let length = normal.length();
+		if length <= f32::EPSILON {
+			return if self < 0.5 { start } else { end };
+		}
+
+		let inv_length = 1.0 / length;
+		Plane2 {
+			normal: normal * inv_length,
+			distance: distance * inv_length,
+		}
+	}
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn test_intersect_plane() {
+		let floor = Plane3::new(Vec3::from_y(1.0), 1.0);
+		let wall = Plane3::new(Vec3::new(1.0, 0.0, 1.0), 2.0);
+
+		let line = floor.intersect_plane(&wall).unwrap();
+		assert_almost_eq!(line.direction.dot(Vec3::new(1.0, 0.0, -1.0)).abs(), SQRT_2);
+		for t in [-5.0, 0.0, 3.0] {
+			assert_almost_eq!(floor.distance_to(line.point_at(t)), 0.0);
+			assert_almost_eq!(wall.distance_to(line.point_at(t)), 0.0);
+		}
+
+		assert!(floor.intersect_plane(&Plane3::new(Vec3::from_y(-2.0), 5.0)).is_none());
+	}
+
+	#[test]
+	fn test_intersect_planes() {
+		let (x, y, z) = (Plane3::new(Vec3::from_x(1.0), 1.0), Plane3::new(Vec3::from_y(1.0), 2.0), Plane3::new(Vec3::one(), 0.0));
+		let point = x.intersect_planes(&y, &z).unwrap();
+		assert_vec_eq!(point, Vec3::new(1.0, 2.0, -3.0));
+
+		// Planes sharing a line
+		let w = Plane3::new(Vec3::new(0.0, 1.0, 1.0), 0.0);
+		assert!(Plane3::new(Vec3::from_y(1.0), 0.0).intersect_planes(&Plane3::new(Vec3::from_z(1.0), 0.0), &w).is_none());
+	}
+
+	#[test]
+	fn test_lerp() {
+		let (floor, wall) = (Plane3::new(Vec3::from_y(1.0), 0.0), Plane3::new(Vec3::from_x(1.0), 0.0));
+		let plane = 0.5f32.lerp(floor, wall);
+		assert_vec_eq!(plane.normal, Vec3::new(1.0, 1.0, 0.0).normalize());
+		assert_almost_eq!(plane.distance, 0.0);
+
+		// Opposing planes fall back to the nearer endpoint
+		let ceiling = Plane3::new(Vec3::from_y(-1.0), -2.0);
+		assert_vec_eq!(0.5f32.lerp(floor, ceiling).normal, ceiling.normal);
+		assert_vec_eq!(0.25f32.lerp(floor, ceiling).normal, floor.normal);
+
+		let (left, right) = (Plane2::new(Vec2::from_x(1.0), 1.0), Plane2::new(Vec2::from_x(-1.0), 1.0));
+		let plane = 0.5f32.lerp(left, right);
+		assert!(plane.normal.x.is_finite() && plane.distance.is_finite());
+	}
+}
@@ -32,6 +32,16 @@ impl Quat {
 		)
 	}
 
+	/// Creates a rotation of `v.length()` radians around `v`. The inverse of `to_scaled_axis`.
+	pub fn from_scaled_axis(v: Vec3) -> Quat {
+		let angle = v.length();
+		if angle < 0.0001 {
+			return Quat::new(1.0, v / 2.0).normalize();
+		}
+
+		Quat::from_axis_angle(v / angle, angle)
+	}
+
 	pub fn from_pitch(pitch: f32) -> Quat {
 		Quat::from_axis_angle(Vec3::from_x(1.0), pitch)
 	}
@@ -62,11 +72,95 @@ impl Quat {
 		Quat::new(self.real, -self.imaginary)
 	}
 
+	/// Returns the axis of rotation scaled by the angle of rotation, taking the shortest path.
+	/// Assumes a unit quaternion.
+	pub fn to_scaled_axis(&self) -> Vec3 {
+		// q and -q represent the same rotation, but only one of them rotates by less than PI
+		let (real, imaginary) = if self.real < 0.0 {
+			(-self.real, -self.imaginary)
+		} else {
+			(self.real, self.imaginary)
+		};
+
+		let sin_half_angle = imaginary.length();
+		if sin_half_angle < 0.0001 {
+			return imaginary * 2.0;
+		}
+
+		let angle = 2.0 * sin_half_angle.atan2(real);
+		imaginary * (angle / sin_half_angle)
+	}
+
+	pub fn dot(&self, o: Quat) -> f32 {
+		self.real * o.real + self.imaginary.dot(o.imaginary)
+	}
+
+	/// Spherical linear interpolation along the shortest arc between two unit quaternions.
+	pub fn slerp(&self, end: Quat, t: f32) -> Quat {
+		let mut end = end;
+		let mut cos_angle = self.dot(end);
+
+		// q and -q represent the same rotation, so flip to take the shortest path
+		if cos_angle < 0.0 {
+			end = end * -1.0;
+			cos_angle = -cos_angle;
+		}
+
+		// Fall back to nlerp when the quaternions are nearly parallel to avoid dividing by ~0
+		if cos_angle > 0.9995 {
+			return t.lerp(*self, end).normalize();
+		}
+
+		let angle = cos_angle.acos();
+		let inv_sin_angle = 1.0 / angle.sin();
+		let start_factor = ((1.0 - t) * angle).sin() * inv_sin_angle;
+		let end_factor = (t * angle).sin() * inv_sin_angle;
+
+		*self * start_factor + end * end_factor
+	}
+
 	pub fn scale(&self, f: f32) -> Quat {
 		// TODO: improve
 		(*self * f + Quat::identity() * (1.0 - f)).normalize()
 	}
 
+	/// Extracts the rotation from the upper 3x3 of `m`, which is assumed to be orthonormal.
+	// https://www.euclideanspace.com/maths/geometry/rotations/conversions/matrixToQuaternion/
+	pub fn from_mat3x4(m: &Mat3x4) -> Quat {
+		let [a, b, c] = m.rows;
+		let trace = a.x + b.y + c.z;
+
+		let (real, x, y, z);
+
+		if trace > 0.0 {
+			let s = (trace + 1.0).sqrt() * 2.0;
+			real = s / 4.0;
+			x = (c.y - b.z) / s;
+			y = (a.z - c.x) / s;
+			z = (b.x - a.y) / s;
+		} else if a.x > b.y && a.x > c.z {
+			let s = (1.0 + a.x - b.y - c.z).sqrt() * 2.0;
+			real = (c.y - b.z) / s;
+			x = s / 4.0;
+			y = (a.y + b.x) / s;
+			z = (a.z + c.x) / s;
+		} else if b.y > c.z {
+			let s = (1.0 + b.y - a.x - c.z).sqrt() * 2.0;
+			real = (a.z - c.x) / s;
+			x = (a.y + b.x) / s;
+			y = s / 4.0;
+			z = (b.z + c.y) / s;
+		} else {
+			let s = (1.0 + c.z - a.x - b.y).sqrt() * 2.0;
+			real = (b.x - a.y) / s;
+			x = (a.z + c.x) / s;
+			y = (b.z + c.y) / s;
+			z = s / 4.0;
+		}
+
+		Quat::new(real, Vec3::new(x, y, z)).normalize()
+	}
+
 	pub fn to_mat4(&self) -> Mat4 {
 		self.to_mat3x4().to_mat4()
 	}
@@ -295,4 +389,48 @@ mod tests {
 		// assert_almost_eq!(r180.pitch(), 0.0);
 		// assert_almost_eq!(r180.roll(), PI);
 	}
+
+	#[test]
+	fn test_from_mat3x4() {
+		let rotations = [
+			Quat::identity(),
+			Quat::from_pitch(PI/3.0),
+			Quat::from_yaw(PI),
+			Quat::from_roll(-PI/2.0),
+			Quat::from_axis_angle(Vec3::new(1.0, 2.0, -3.0).normalize(), 2.5),
+		];
+
+		for q in rotations {
+			let restored = Quat::from_mat3x4(&q.to_mat3x4());
+			assert_almost_eq!(q.dot(restored).abs(), 1.0);
+		}
+	}
+
+	#[test]
+	fn test_scaled_axis() {
+		let v = Vec3::new(0.3, -1.2, 0.5);
+		assert_vec_eq!(Quat::from_scaled_axis(v).to_scaled_axis(), v);
+		assert_vec_eq!(Quat::from_scaled_axis(Vec3::zero()).to_scaled_axis(), Vec3::zero());
+		assert_vec_eq!(Quat::from_yaw(PI/2.0).to_scaled_axis(), Vec3::from_y(PI/2.0));
+
+		// Rotations over PI wrap around to the shorter path
+		assert_vec_eq!(Quat::from_yaw(1.5 * PI).to_scaled_axis(), Vec3::from_y(-PI/2.0));
+	}
+
+	#[test]
+	fn test_slerp() {
+		let start = Quat::from_yaw(0.0);
+		let end = Quat::from_yaw(PI/2.0);
+
+		let mid = start.slerp(end, 0.5);
+		assert_almost_eq!(mid.yaw(), PI/4.0);
+		assert_almost_eq!(mid.magnitude(), 1.0);
+
+		let quarter = start.slerp(end, 0.25);
+		assert_almost_eq!(quarter.yaw(), PI/8.0);
+
+		// Takes the shortest path even if the target is on the far hemisphere
+		let flipped = start.slerp(end * -1.0, 0.5);
+		assert_vec_eq!(flipped.forward(), mid.forward());
+	}
 }
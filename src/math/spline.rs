@@ -0,0 +1,422 @@
+use crate::math::vector::{FloatVector, Vec2, Vec3};
+use crate::math::curve::Curve;
+
+
+/// A piecewise cubic curve. Every segment can be expressed as a cubic bezier, so evaluation
+/// and derivatives are shared between spline types.
+///
+/// Splines are parameterized over `[0, segment_count]`, where the integer part of the parameter
+/// selects a segment and the fractional part is the position within that segment.
+pub trait Spline {
+	type Point: FloatVector;
+
+	fn segment_count(&self) -> usize;
+	fn is_closed(&self) -> bool;
+
+	/// Control points of segment `index` as a cubic bezier.
+	fn segment_bezier(&self, index: usize) -> [Self::Point; 4];
+
+	/// Splits a spline parameter into a segment index and a parameter within that segment.
+	/// Parameters are wrapped for closed splines, and clamped otherwise.
+	fn locate(&self, t: f32) -> (usize, f32) {
+		let count = self.segment_count();
+		if count == 0 {
+			return (0, 0.0);
+		}
+
+		let t = if self.is_closed() {
+			t.rem_euclid(count as f32)
+		} else {
+			t.clamp(0.0, count as f32)
+		};
+
+		let index = (t.floor() as usize).min(count - 1);
+		(index, t - index as f32)
+	}
+
+	/// Panics if the spline has no segments.
+	fn eval(&self, t: f32) -> Self::Point {
+		let (index, t) = self.locate(t);
+		bezier_eval(self.segment_bezier(index), t)
+	}
+
+	/// Derivative with respect to the spline parameter. Panics if the spline has no segments.
+	fn derivative(&self, t: f32) -> Self::Point {
+		let (index, t) = self.locate(t);
+		bezier_derivative(self.segment_bezier(index), t)
+	}
+
+	/// Panics if the spline has no segments.
+	fn second_derivative(&self, t: f32) -> Self::Point {
+		let (index, t) = self.locate(t);
+		bezier_second_derivative(self.segment_bezier(index), t)
+	}
+
+	fn segments(&self) -> impl Iterator<Item=[Self::Point; 4]> {
+		(0..self.segment_count()).map(|index| self.segment_bezier(index))
+	}
+}
+
+
+/// Controls the spacing of knots in a `CatmullRomSpline`, which affects how tightly it follows its points.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CatmullRomKind {
+	/// Evenly spaced knots. Can overshoot and form cusps or loops where points are unevenly spaced.
+	Uniform,
+
+	/// Knots spaced by the square root of the distance between points. Never forms cusps or self intersections within a segment.
+	#[default]
+	Centripetal,
+
+	/// Knots spaced by the distance between points.
+	Chordal,
+}
+
+impl CatmullRomKind {
+	pub fn alpha(&self) -> f32 {
+		match self {
+			CatmullRomKind::Uniform => 0.0,
+			CatmullRomKind::Centripetal => 0.5,
+			CatmullRomKind::Chordal => 1.0,
+		}
+	}
+}
+
+
+/// A spline passing through each of its points.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CatmullRomSpline<T> {
+	pub points: Vec<T>,
+	pub kind: CatmullRomKind,
+	pub closed: bool,
+}
+
+impl<T: FloatVector> CatmullRomSpline<T> {
+	pub fn new(points: Vec<T>, kind: CatmullRomKind) -> CatmullRomSpline<T> {
+		CatmullRomSpline { points, kind, closed: false }
+	}
+
+	pub fn new_closed(points: Vec<T>, kind: CatmullRomKind) -> CatmullRomSpline<T> {
+		CatmullRomSpline { points, kind, closed: true }
+	}
+}
+
+impl<T: FloatVector> Spline for CatmullRomSpline<T> {
+	type Point = T;
+
+	fn segment_count(&self) -> usize {
+		match self.points.len() {
+			0 | 1 => 0,
+			n if self.closed => n,
+			n => n - 1,
+		}
+	}
+
+	fn is_closed(&self) -> bool { self.closed }
+
+	fn segment_bezier(&self, index: usize) -> [T; 4] {
+		let index = index as isize;
+		let [p0, p1, p2, p3] = [-1, 0, 1, 2].map(|offset| extended_point(&self.points, self.closed, index + offset));
+
+		// Knot intervals - coincident points are given a unit interval to avoid dividing by zero
+		let alpha = self.kind.alpha();
+		let interval = |a: T, b: T| {
+			let d = a.distance(b).powf(alpha);
+			if d < 1.0e-5 { 1.0 } else { d }
+		};
+
+		let d01 = interval(p0, p1);
+		let d12 = interval(p1, p2);
+		let d23 = interval(p2, p3);
+
+		// Tangents at p1 and p2, scaled to the [0, 1] segment parameter
+		let m1 = ((p1 - p0) / d01 - (p2 - p0) / (d01 + d12) + (p2 - p1) / d12) * d12;
+		let m2 = ((p2 - p1) / d12 - (p3 - p1) / (d12 + d23) + (p3 - p2) / d23) * d12;
+
+		[p1, p1 + m1 / 3.0, p2 - m2 / 3.0, p2]
+	}
+}
+
+
+/// A uniform cubic B-spline. Passes near, but generally not through, its points.
+/// Open splines span from near the second point to near the second to last point.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BSpline<T> {
+	pub points: Vec<T>,
+	pub closed: bool,
+}
+
+impl<T: FloatVector> BSpline<T> {
+	pub fn new(points: Vec<T>) -> BSpline<T> {
+		BSpline { points, closed: false }
+	}
+
+	pub fn new_closed(points: Vec<T>) -> BSpline<T> {
+		BSpline { points, closed: true }
+	}
+}
+
+impl<T: FloatVector> Spline for BSpline<T> {
+	type Point = T;
+
+	fn segment_count(&self) -> usize {
+		match self.points.len() {
+			n if self.closed && n >= 3 => n,
+			n if n >= 4 => n - 3,
+			_ => 0,
+		}
+	}
+
+	fn is_closed(&self) -> bool { self.closed }
+
+	fn segment_bezier(&self, index: usize) -> [T; 4] {
+		let n = self.points.len();
+		let [p0, p1, p2, p3] = [0, 1, 2, 3].map(|offset| self.points[(index + offset) % n]);
+
+		[
+			(p0 + p1 * 4.0 + p2) / 6.0,
+			(p1 * 2.0 + p2) / 3.0,
+			(p1 + p2 * 2.0) / 3.0,
+			(p1 + p2 * 4.0 + p3) / 6.0,
+		]
+	}
+}
+
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HermitePoint<T> {
+	pub position: T,
+
+	/// Derivative of the spline at `position`, with respect to the spline parameter.
+	pub tangent: T,
+}
+
+/// A spline passing through each of its points with explicitly specified tangents.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HermiteSpline<T> {
+	pub points: Vec<HermitePoint<T>>,
+	pub closed: bool,
+}
+
+impl<T: FloatVector> HermiteSpline<T> {
+	pub fn new(points: Vec<HermitePoint<T>>) -> HermiteSpline<T> {
+		HermiteSpline { points, closed: false }
+	}
+
+	pub fn new_closed(points: Vec<HermitePoint<T>>) -> HermiteSpline<T> {
+		HermiteSpline { points, closed: true }
+	}
+}
+
+impl<T: FloatVector> Spline for HermiteSpline<T> {
+	type Point = T;
+
+	fn segment_count(&self) -> usize {
+		match self.points.len() {
+			0 | 1 => 0,
+			n if self.closed => n,
+			n => n - 1,
+		}
+	}
+
+	fn is_closed(&self) -> bool { self.closed }
+
+	fn segment_bezier(&self, index: usize) -> [T; 4] {
+		let n = self.points.len();
+		let start = self.points[index % n];
+		let end = self.points[(index + 1) % n];
+
+		[
+			start.position,
+			start.position + start.tangent / 3.0,
+			end.position - end.tangent / 3.0,
+			end.position,
+		]
+	}
+}
+
+
+macro_rules! impl_curve_for_spline {
+	($ty:ident<$vec:ident>, |$v:ident| $to_vec3:expr) => {
+		impl Curve for $ty<$vec> {
+			fn domain(&self) -> (f32, f32) { (0.0, self.segment_count() as f32) }
+			fn position(&self, t: f32) -> Vec3 { let $v = Spline::eval(self, t); $to_vec3 }
+			fn velocity(&self, t: f32) -> Vec3 { let $v = Spline::derivative(self, t); $to_vec3 }
+		}
+	};
+}
+
+impl_curve_for_spline!(CatmullRomSpline<Vec2>, |v| v.extend(0.0));
+impl_curve_for_spline!(CatmullRomSpline<Vec3>, |v| v);
+impl_curve_for_spline!(BSpline<Vec2>, |v| v.extend(0.0));
+impl_curve_for_spline!(BSpline<Vec3>, |v| v);
+impl_curve_for_spline!(HermiteSpline<Vec2>, |v| v.extend(0.0));
+impl_curve_for_spline!(HermiteSpline<Vec3>, |v| v);
+
+
+// Gets the point at `index`, wrapping if closed, otherwise linearly extrapolating one point past either end.
+fn extended_point<T: FloatVector>(points: &[T], closed: bool, index: isize) -> T {
+	let n = points.len() as isize;
+
+	if closed {
+		points[index.rem_euclid(n) as usize]
+	} else if index < 0 {
+		points[0] * 2.0 - points[1]
+	} else if index >= n {
+		points[n as usize - 1] * 2.0 - points[n as usize - 2]
+	} else {
+		points[index as usize]
+	}
+}
+
+fn bezier_eval<T: FloatVector>([p0, p1, p2, p3]: [T; 4], t: f32) -> T {
+	let u = 1.0 - t;
+	p0 * (u*u*u) + p1 * (3.0*u*u*t) + p2 * (3.0*u*t*t) + p3 * (t*t*t)
+}
+
+fn bezier_derivative<T: FloatVector>([p0, p1, p2, p3]: [T; 4], t: f32) -> T {
+	let u = 1.0 - t;
+	((p1 - p0) * (u*u) + (p2 - p1) * (2.0*u*t) + (p3 - p2) * (t*t)) * 3.0
+}
+
+fn bezier_second_derivative<T: FloatVector>([p0, p1, p2, p3]: [T; 4], t: f32) -> T {
+	((p2 - p1 * 2.0 + p0) * (1.0 - t) + (p3 - p2 * 2.0 + p1) * t) * 6.0
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn points() -> Vec<Vec2> {
+		vec![
+			Vec2::new(0.0, 0.0),
+			Vec2::new(1.0, 2.0),
+			Vec2::new(1.2, 2.1),
+			Vec2::new(4.0, -1.0),
+			Vec2::new(5.0, 0.0),
+		]
+	}
+
+	#[test]
+	fn test_catmull_rom_interpolates() {
+		for kind in [CatmullRomKind::Uniform, CatmullRomKind::Centripetal, CatmullRomKind::Chordal] {
+			let spline = CatmullRomSpline::new(points(), kind);
+			assert_eq!(spline.segment_count(), 4);
+
+			for (i, &point) in points().iter().enumerate() {
+				assert_vec_eq!(spline.eval(i as f32), point);
+			}
+
+			// Tangent directions are continuous across segments
+			for i in 1..4 {
+				let (before, after) = (spline.segment_bezier(i-1), spline.segment_bezier(i));
+				assert_vec_eq!(before[3], after[0]);
+				assert_vec_eq!((before[3] - before[2]).normalize(), (after[1] - after[0]).normalize());
+			}
+		}
+	}
+
+	#[test]
+	fn test_catmull_rom_matches_pyramid_formulation() {
+		let spline = CatmullRomSpline::new(points(), CatmullRomKind::Centripetal);
+		let pts = points();
+
+		// Direct Barry-Goldman evaluation of the second segment
+		let knots = {
+			let mut knots = [0.0f32; 4];
+			for i in 1..4 {
+				knots[i] = knots[i-1] + pts[i-1].distance(pts[i]).sqrt();
+			}
+			knots
+		};
+
+		for i in 0..=10 {
+			let local_t = i as f32 / 10.0;
+			let time = local_t.lerp(knots[1], knots[2]);
+			let factor = |from: f32, to: f32| (time - from) / (to - from);
+
+			let a1 = factor(knots[0], knots[1]).lerp(pts[0], pts[1]);
+			let a2 = factor(knots[1], knots[2]).lerp(pts[1], pts[2]);
+			let a3 = factor(knots[2], knots[3]).lerp(pts[2], pts[3]);
+			let b1 = factor(knots[0], knots[2]).lerp(a1, a2);
+			let b2 = factor(knots[1], knots[3]).lerp(a2, a3);
+			let expected = factor(knots[1], knots[2]).lerp(b1, b2);
+
+			assert_vec_eq!(spline.eval(1.0 + local_t), expected);
+		}
+	}
+
+	#[test]
+	fn test_closed() {
+		let spline = CatmullRomSpline::new_closed(points(), CatmullRomKind::Centripetal);
+		assert_eq!(spline.segment_count(), 5);
+		assert_vec_eq!(spline.eval(5.0), points()[0]);
+		assert_vec_eq!(spline.eval(-1.0), points()[4]);
+
+		let (last, first) = (spline.segment_bezier(4), spline.segment_bezier(0));
+		assert_vec_eq!(last[3], first[0]);
+		assert_vec_eq!((last[3] - last[2]).normalize(), (first[1] - first[0]).normalize());
+
+		let spline = BSpline::new_closed(points());
+		assert_eq!(spline.segment_count(), 5);
+		assert_vec_eq!(spline.eval(0.0), spline.eval(5.0));
+		let (last, first) = (spline.segment_bezier(4), spline.segment_bezier(0));
+		assert_vec_eq!(last[3] - last[2], first[1] - first[0]);
+	}
+
+	#[test]
+	fn test_bspline() {
+		let spline = BSpline::new(points());
+		assert_eq!(spline.segment_count(), 2);
+
+		let pts = points();
+		assert_vec_eq!(spline.eval(0.0), (pts[0] + pts[1] * 4.0 + pts[2]) / 6.0);
+		assert_vec_eq!(spline.eval(2.0), (pts[2] + pts[3] * 4.0 + pts[4]) / 6.0);
+
+		// C2 continuity
+		let (before, after) = (spline.segment_bezier(0), spline.segment_bezier(1));
+		assert_vec_eq!((before[3] - before[2]) * 3.0, (after[1] - after[0]) * 3.0);
+		assert_vec_eq!((before[3] - before[2] * 2.0 + before[1]) * 6.0, (after[2] - after[1] * 2.0 + after[0]) * 6.0);
+
+		// Collinear evenly spaced points give a straight, constant speed line
+		let line = BSpline::new(vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+		assert_almost_eq!(line.eval(0.5), 1.5);
+		assert_almost_eq!(line.derivative(1.3), 1.0);
+	}
+
+	#[test]
+	fn test_hermite() {
+		let spline = HermiteSpline::new(vec![
+			HermitePoint { position: Vec3::zero(), tangent: Vec3::from_x(3.0) },
+			HermitePoint { position: Vec3::new(1.0, 1.0, 0.0), tangent: Vec3::from_y(3.0) },
+		]);
+
+		assert_eq!(spline.segment_count(), 1);
+		assert_vec_eq!(spline.eval(0.0), Vec3::zero());
+		assert_vec_eq!(spline.eval(1.0), Vec3::new(1.0, 1.0, 0.0));
+		assert_vec_eq!(spline.derivative(0.0), Vec3::from_x(3.0));
+		assert_vec_eq!(spline.derivative(1.0), Vec3::from_y(3.0));
+
+		let bezier = CubicBezier3::from(spline.segment_bezier(0));
+		assert_vec_eq!(bezier.eval(0.3), spline.eval(0.3));
+	}
+
+	#[test]
+	fn test_locate() {
+		let spline = CatmullRomSpline::new(points(), CatmullRomKind::Uniform);
+		assert_eq!(spline.locate(-1.0), (0, 0.0));
+		assert_eq!(spline.locate(2.5), (2, 0.5));
+		assert_eq!(spline.locate(4.0), (3, 1.0));
+		assert_eq!(spline.locate(10.0), (3, 1.0));
+
+		let empty = BSpline::<Vec2>::new(vec![Vec2::zero(); 3]);
+		assert_eq!(empty.segment_count(), 0);
+	}
+}
@@ -212,6 +212,52 @@ impl_lerp_for_vec!(Vec2, x, y);
 impl_lerp_for_vec!(Vec3, x, y, z);
 impl_lerp_for_vec!(Vec4, x, y, z, w);
 
+/// Operations common to the floating point vector types, for writing code that is generic over dimension.
+pub trait FloatVector: Copy + Default + std::fmt::Debug
+	+ Add<Output=Self> + Sub<Output=Self> + Neg<Output=Self>
+	+ Mul<f32, Output=Self> + Div<f32, Output=Self>
+	+ AddAssign + SubAssign + Sum
+{
+	fn dot(self, o: Self) -> f32;
+
+	fn square_length(self) -> f32 { self.dot(self) }
+	fn length(self) -> f32 { self.square_length().sqrt() }
+	fn normalize(self) -> Self { self / self.length() }
+	fn distance(self, o: Self) -> f32 { (o - self).length() }
+}
+
+impl FloatVector for f32 {
+	fn dot(self, o: f32) -> f32 { self * o }
+}
+
+macro_rules! impl_float_vector {
+	($ty:ident, $($els:ident),+) => (
+		impl FloatVector for $ty {
+			fn dot(self, o: $ty) -> f32 { 0.0 $(+ self.$els * o.$els)+ }
+		}
+	);
+}
+
+impl_float_vector!(Vec2, x, y);
+impl_float_vector!(Vec3, x, y, z);
+impl_float_vector!(Vec4, x, y, z, w);
+
+// Integer vectors are interpolated in float space and rounded to the nearest integer.
+macro_rules! impl_rounded_lerp_for_vec {
+	($ty:ident, $($els:ident),+) => (
+		impl Lerp<$ty> for f32 {
+			fn lerp(self, start: $ty, end: $ty) -> $ty {
+				$ty {
+					$($els: self.lerp(start.$els as f32, end.$els as f32).round() as i32),+
+				}
+			}
+		}
+	);
+}
+
+impl_rounded_lerp_for_vec!(Vec2i, x, y);
+impl_rounded_lerp_for_vec!(Vec3i, x, y, z);
+
 
 // mint interop
 macro_rules! impl_mint_intomint {
@@ -1,4 +1,4 @@
-use crate::math::vector::{Vec2, Vec4};
+use crate::math::vector::{Vec2, Vec3i, Vec4};
 use rand_derive2::RandGen;
 
 
@@ -32,6 +32,7 @@ impl Vec3 {
 	pub fn to_xy(&self) -> Vec2 { Vec2::new(self.x, self.y) }
 	pub fn to_xz(&self) -> Vec2 { Vec2::new(self.x, self.z) }
 	pub fn extend(&self, w: f32) -> Vec4 { Vec4::new(self.x, self.y, self.z, w) }
+	pub fn to_vec3i(&self) -> Vec3i { Vec3i::new(self.x as i32, self.y as i32, self.z as i32) }
 
 	pub fn square_length(&self) -> f32 { self.dot(*self) }
 	pub fn length(&self) -> f32 { self.square_length().sqrt() }
@@ -45,6 +46,9 @@ impl Vec3 {
 			self.x*o.y - self.y*o.x,
 		)
 	}
+
+	pub fn floor(&self) -> Vec3 { Vec3::new(self.x.floor(), self.y.floor(), self.z.floor()) }
+	pub fn ceil(&self) -> Vec3 { Vec3::new(self.x.ceil(), self.y.ceil(), self.z.ceil()) }
 }
 
 